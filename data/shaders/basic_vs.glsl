@@ -1,6 +1,7 @@
 #version 150 core
 #include <locals>
 #include <globals>
+#include <skinning>
 
 in vec4 a_Position;
 in vec4 a_Normal;
@@ -9,5 +10,5 @@ out vec2 v_TexCoord;
 
 void main() {
     v_TexCoord = mix(u_UvRange.xy, u_UvRange.zw, a_TexCoord);
-    gl_Position = u_ViewProj * u_World * a_Position;
+    gl_Position = u_ViewProj * world_transform() * a_Position;
 }
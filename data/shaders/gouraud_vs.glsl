@@ -2,6 +2,7 @@
 #include <locals>
 #include <lights>
 #include <globals>
+#include <skinning>
 
 #define MAX_SHADOWS 2
 
@@ -15,8 +16,9 @@ flat out vec4 v_LightEvalFlat[MAX_SHADOWS];
 out vec4 v_ShadowCoord[MAX_SHADOWS];
 
 void main() {
-    vec4 world = u_World * a_Position;
-    vec3 normal = normalize(mat3(u_World) * a_Normal.xyz);
+    mat4 model = world_transform();
+    vec4 world = model * a_Position;
+    vec3 normal = normalize(normal_matrix(model) * a_Normal.xyz);
     for(int i=0; i<MAX_SHADOWS; ++i) {
         v_ShadowCoord[i] = vec4(0.0);
         v_LightEval[i] = v_LightEvalFlat[i] = vec4(0.0);
@@ -35,7 +37,7 @@ void main() {
             dot_nl = 0.0;
         }
         v_ResultColor += light.intensity.x * u_Color * irradiance; //ambient
-        vec4 color = light.intensity.y * max(0.0, dot_nl) * u_Color * light.color;
+        vec4 color = spot_factor(light, dir) * light.intensity.y * max(0.0, dot_nl) * u_Color * light.color;
         // compute shadow coordinates
         int shadow_index = light.shadow_params[0];
         if (0 <= shadow_index && shadow_index < MAX_SHADOWS) {
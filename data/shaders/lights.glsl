@@ -14,3 +14,13 @@ struct Light {
 layout(std140) uniform b_Lights {
     Light u_Lights[MAX_LIGHTS];
 };
+
+// Returns the attenuation of a spot light, `dir` being the direction
+// from the lit surface to the light.
+float spot_factor(Light light, vec3 dir) {
+    if (light.focus.z == 0.0) {
+        return 1.0;
+    }
+    float cos_angle = dot(normalize(dir), light.dir.xyz);
+    return clamp((cos_angle - light.focus.y) / max(light.focus.x - light.focus.y, 0.0001), 0.0, 1.0);
+}
@@ -4,3 +4,8 @@ uniform b_Locals {
     vec4 u_MatParams;
     vec4 u_UvRange;
 };
+
+// Transforms normals into world space, taking non-uniform scale into account.
+mat3 normal_matrix(mat4 world) {
+    return transpose(inverse(mat3(world)));
+}
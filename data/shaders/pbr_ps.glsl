@@ -30,6 +30,14 @@ const int NORMAL_MAP              = 1 << 1;
 const int METALLIC_ROUGHNESS_MAP  = 1 << 2;
 const int EMISSIVE_MAP            = 1 << 3;
 const int OCCLUSION_MAP           = 1 << 4;
+const int BASE_COLOR_UV1          = 1 << 5;
+const int NORMAL_UV1              = 1 << 6;
+const int METALLIC_ROUGHNESS_UV1  = 1 << 7;
+const int EMISSIVE_UV1            = 1 << 8;
+const int OCCLUSION_UV1           = 1 << 9;
+const int ALPHA_MASK              = 1 << 10;
+const int ALPHA_BLEND             = 1 << 11;
+const int DOUBLE_SIDED            = 1 << 12;
 
 uniform sampler2D u_BaseColorSampler;
 uniform sampler2D u_NormalSampler;
@@ -45,10 +53,18 @@ layout(std140) uniform b_PbrParams {
     float u_NormalScale;
     float u_OcclusionStrength;
     int u_PbrFlags;
+    float u_AlphaCutoff;
+    mat4 u_BaseColorTransform;
+    mat4 u_NormalTransform;
+    mat4 u_EmissiveTransform;
+    mat4 u_MetallicRoughnessTransform;
+    mat4 u_OcclusionTransform;
 };
 
 in vec3 v_Position;
 in vec2 v_TexCoord;
+in vec2 v_TexCoord1;
+in vec4 v_Color;
 in mat3 v_Tbn;
 
 out vec4 Target0;
@@ -112,6 +128,14 @@ bool available(int flag)
     return (u_PbrFlags & flag) == flag;
 }
 
+// Returns the texture co-ordinates selected by the given `*_UV1` flag,
+// mapped by the texture transform of the map.
+vec2 tex_coord(int uv1_flag, mat4 transform)
+{
+    vec2 uv = available(uv1_flag) ? v_TexCoord1 : v_TexCoord;
+    return (transform * vec4(uv, 0.0, 1.0)).xy;
+}
+
 void main()
 {
     mat3 tbn = v_Tbn;
@@ -119,17 +143,20 @@ void main()
 
     vec3 n;
     if (available(NORMAL_MAP)) {
-        n = texture(u_NormalSampler, v_TexCoord).rgb;
+        n = texture(u_NormalSampler, tex_coord(NORMAL_UV1, u_NormalTransform)).rgb;
         n = normalize(tbn * ((2.0 * n - 1.0) * vec3(u_NormalScale, u_NormalScale, 1.0)));
     } else {
         n = tbn[2].xyz;
     }
+    if (available(DOUBLE_SIDED) && !gl_FrontFacing) {
+        n = -n;
+    }
 
     float perceptual_roughness = u_MetallicRoughnessValues.y;
     float metallic = u_MetallicRoughnessValues.x;
 
     if (available(METALLIC_ROUGHNESS_MAP)) {
-	vec4 mr_sample = texture(u_MetallicRoughnessSampler, v_TexCoord);
+	vec4 mr_sample = texture(u_MetallicRoughnessSampler, tex_coord(METALLIC_ROUGHNESS_UV1, u_MetallicRoughnessTransform));
 	perceptual_roughness = mr_sample.g * perceptual_roughness;
 	metallic = mr_sample.b * metallic;
     }
@@ -139,10 +166,20 @@ void main()
 
     vec4 base_color;
     if (available(BASE_COLOR_MAP)) {
-	base_color = texture(u_BaseColorSampler, v_TexCoord) * u_BaseColorFactor;
+	base_color = texture(u_BaseColorSampler, tex_coord(BASE_COLOR_UV1, u_BaseColorTransform)) * u_BaseColorFactor;
     } else {
 	base_color = u_BaseColorFactor;
     }
+    base_color *= v_Color;
+
+    if (available(ALPHA_MASK)) {
+        if (base_color.a < u_AlphaCutoff) {
+            discard;
+        }
+        base_color.a = 1.0;
+    } else if (!available(ALPHA_BLEND)) {
+        base_color.a = 1.0;
+    }
 
     vec3 f0 = vec3(0.04);
     vec3 diffuse_color = mix(base_color.rgb * (1.0 - f0), vec3(0.0, 0.0, 0.0), metallic);
@@ -191,16 +228,17 @@ void main()
 	float d = ggx(pbr_inputs);
 	vec3 diffuse_contrib = (1.0 - f) * lambertian_diffuse(pbr_inputs);
 	vec3 spec_contrib = f * g * d / (4.0 * ndotl * ndotv);
-	color += ndotl * light.intensity.y * light.color.rgb * (diffuse_contrib + spec_contrib);
+	float spot = spot_factor(light, light.pos.xyz - light.pos.w * v_Position);
+	color += spot * ndotl * light.intensity.y * light.color.rgb * (diffuse_contrib + spec_contrib);
     }
 
     if (available(OCCLUSION_MAP)) {
-	float ao = texture(u_OcclusionSampler, v_TexCoord).r;
+	float ao = texture(u_OcclusionSampler, tex_coord(OCCLUSION_UV1, u_OcclusionTransform)).r;
         color = mix(color, color * ao, u_OcclusionStrength);
     }
 
     if (available(EMISSIVE_MAP)) {
-        vec3 emissive = texture(u_EmissiveSampler, v_TexCoord).rgb * u_EmissiveFactor;
+        vec3 emissive = texture(u_EmissiveSampler, tex_coord(EMISSIVE_UV1, u_EmissiveTransform)).rgb * u_EmissiveFactor;
         color += emissive;
     }
 
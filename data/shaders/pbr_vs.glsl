@@ -23,30 +23,37 @@
 #version 150 core
 #include <locals>
 #include <globals>
+#include <skinning>
 
 in vec4 a_Position;
 in vec2 a_TexCoord;
+in vec2 a_TexCoord1;
+in vec4 a_Color;
 in vec4 a_Normal;
 in vec4 a_Tangent;
 
 out vec3 v_Position;
 out vec2 v_TexCoord;
+out vec2 v_TexCoord1;
+out vec4 v_Color;
 out mat3 v_Tbn;
 out vec3 v_Normal;
 
 void main()
 {
-    mat4 u_Model = u_World;
-    mat4 u_Mvp = u_ViewProj * u_World;
+    mat4 u_Model = world_transform();
+    mat4 u_Mvp = u_ViewProj * u_Model;
 
     vec4 position = u_Model * a_Position;
-    vec3 normal = normalize(vec3(u_Model * vec4(a_Normal.xyz, 0.0)));
+    vec3 normal = normalize(normal_matrix(u_Model) * a_Normal.xyz);
     vec3 tangent = normalize(vec3(u_Model * vec4(a_Tangent.xyz, 0.0)));
     vec3 bitangent = cross(normal, tangent) * a_Tangent.w;
 
     v_Tbn = mat3(tangent, bitangent, normal);
     v_Position = vec3(position.xyz) / position.w;
     v_TexCoord = a_TexCoord;
+    v_TexCoord1 = a_TexCoord1;
+    v_Color = a_Color;
 
     gl_Position = u_Mvp * a_Position;
 }
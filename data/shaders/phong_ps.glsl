@@ -31,6 +31,7 @@ void main() {
             continue;
         }
         vec3 dir = light.pos.xyz - light.pos.w * v_World.xyz;
+        shadow *= spot_factor(light, dir);
         float dot_nl = dot(normal, normalize(dir));
         // hemisphere light test
         if (dot(light.color_back, light.color_back) > 0.0) {
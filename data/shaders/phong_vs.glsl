@@ -2,6 +2,7 @@
 #include <locals>
 #include <lights>
 #include <globals>
+#include <skinning>
 
 in vec4 a_Position;
 in vec4 a_Normal;
@@ -11,9 +12,10 @@ out vec3 v_Half[MAX_LIGHTS];
 out vec4 v_ShadowCoord[MAX_LIGHTS];
 
 void main() {
-    vec4 world = u_World * a_Position;
+    mat4 model = world_transform();
+    vec4 world = model * a_Position;
     v_World = world.xyz;
-    v_Normal = normalize(mat3(u_World) * a_Normal.xyz);
+    v_Normal = normalize(normal_matrix(model) * a_Normal.xyz);
     for(uint i=0U; i < min(MAX_LIGHTS, u_NumLights); ++i) {
         Light light = u_Lights[i];
         vec3 dir = light.pos.xyz - light.pos.w * world.xyz;
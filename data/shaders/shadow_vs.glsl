@@ -1,9 +1,10 @@
 #version 150 core
 #include <locals>
 #include <globals>
+#include <skinning>
 
 in vec4 a_Position;
 
 void main() {
-    gl_Position = u_ViewProj * u_World * a_Position;
+    gl_Position = u_ViewProj * world_transform() * a_Position;
 }
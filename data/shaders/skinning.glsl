@@ -0,0 +1,27 @@
+in ivec4 a_JointIndices;
+in vec4 a_JointWeights;
+
+uniform samplerBuffer b_JointTransforms;
+
+mat4 joint_transform(int index) {
+    return mat4(
+        texelFetch(b_JointTransforms, 4 * index + 0),
+        texelFetch(b_JointTransforms, 4 * index + 1),
+        texelFetch(b_JointTransforms, 4 * index + 2),
+        texelFetch(b_JointTransforms, 4 * index + 3)
+    );
+}
+
+// Model to world transform of the current vertex.
+//
+// Skinned vertices are placed by the joints of their skeleton, which are
+// already in world space; all other vertices use `u_World`.
+mat4 world_transform() {
+    if (dot(a_JointWeights, vec4(1.0)) == 0.0) {
+        return u_World;
+    }
+    return a_JointWeights.x * joint_transform(a_JointIndices.x)
+        + a_JointWeights.y * joint_transform(a_JointIndices.y)
+        + a_JointWeights.z * joint_transform(a_JointIndices.z)
+        + a_JointWeights.w * joint_transform(a_JointIndices.w);
+}
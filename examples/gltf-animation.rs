@@ -11,7 +11,7 @@ fn main() {
 
     let default = concat!(env!("CARGO_MANIFEST_DIR"), "/test_data/BoxAnimated.gltf");
     let path = std::env::args().nth(1).unwrap_or(default.into());
-    let mut gltf = window.factory.load_gltf(&path);
+    let mut gltf = window.factory.load_gltf(&path).unwrap();
     gltf.group.set_parent(&window.scene);
 
     let mut mixer = three::animation::Mixer::new();
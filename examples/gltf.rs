@@ -11,7 +11,7 @@ fn main() {
 
     let default = concat!(env!("CARGO_MANIFEST_DIR"), "/test_data/Lantern.gltf");
     let path = std::env::args().nth(1).unwrap_or(default.into());
-    let mut gltf = win.factory.load_gltf(&path);
+    let mut gltf = win.factory.load_gltf(&path).unwrap();
     gltf.group.set_parent(&win.scene);
 
     let mut cam = if gltf.cameras.len() > 0 {
@@ -32,7 +32,7 @@ fn main() {
             left: "test_data/skybox/negx.jpg",
             right: "test_data/skybox/posx.jpg",
         };
-        let skybox = win.factory.load_cubemap(&skybox_path);
+        let skybox = win.factory.load_cubemap(&skybox_path).unwrap();
         win.scene.background = three::Background::Skybox(skybox);
     }
 
@@ -0,0 +1,30 @@
+extern crate three;
+
+use three::Object;
+
+fn main() {
+    let mut headless = three::Headless::new(640, 480);
+    headless.scene.background = three::Background::Color(0xC6F0FF);
+
+    let mut light = headless.factory.directional_light(0xFFFFFF, 0.8);
+    light.look_at([1.0, 1.0, 1.0], [0.0, 0.0, 0.0], None);
+    light.set_parent(&headless.scene);
+
+    let mut sphere = {
+        let geometry = three::Geometry::uv_sphere(2.0, 24, 24);
+        let material = three::material::Lambert {
+            color: 0xFF8000,
+            flat: false,
+        };
+        headless.factory.mesh(geometry, material)
+    };
+    sphere.set_parent(&headless.scene);
+
+    let mut camera = headless.factory.perspective_camera(60.0, 0.1 .. 100.0);
+    camera.set_position([0.0, 0.0, 8.0]);
+
+    headless.render(&camera);
+    let path = std::env::args().nth(1).unwrap_or("headless.png".into());
+    headless.renderer.capture().unwrap().save(&path).unwrap();
+    println!("Saved {}", path);
+}
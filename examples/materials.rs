@@ -43,6 +43,7 @@ fn main() {
             emissive_map: None,
             metallic_roughness_map: None,
             occlusion_map: None,
+            ..Default::default()
         }.into(),
     ];
     let count = materials.len();
@@ -20,7 +20,7 @@ fn main() {
 
     let mut root = win.factory.group();
     root.set_parent(&win.scene);
-    let (mut group_map, _meshes) = win.factory.load_obj(&path);
+    let (mut group_map, _meshes) = win.factory.load_obj(&path).unwrap();
     for g in group_map.values_mut() {
         g.set_parent(&root);
     }
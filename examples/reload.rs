@@ -90,7 +90,7 @@ fn main() {
         .unwrap();
 
     let map_path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_data/gradient.png");
-    let map = win.factory.load_texture(map_path);
+    let map = win.factory.load_texture(map_path).unwrap();
     let material = three::material::Sprite { map };
     let mut sprite = win.factory.sprite(material);
     sprite.set_scale(1.0);
@@ -51,7 +51,7 @@ fn main() {
     let pikachu_path: String = format!("{}/test_data/pikachu_anim.png", env!("CARGO_MANIFEST_DIR"));
     let pikachu_path_str: &str = pikachu_path.as_str();
     let material = three::material::Sprite {
-        map: win.factory.load_texture(pikachu_path_str),
+        map: win.factory.load_texture(pikachu_path_str).unwrap(),
     };
     let mut sprite = win.factory.sprite(material);
     sprite.set_scale(8.0);
@@ -70,7 +70,7 @@ fn main() {
 
     // Specify background image. Remove `if` to enable.
     if false {
-        let background = win.factory.load_texture("test_data/texture.png");
+        let background = win.factory.load_texture("test_data/texture.png").unwrap();
         win.scene.background = three::Background::Texture(background);
     }
 
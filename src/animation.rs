@@ -43,7 +43,7 @@
 //! ```rust,no_run
 //! # let mut window = three::Window::new("");
 //! use three::Object;
-//! let mut gltf = window.factory.load_gltf("AnimatedScene.gltf");
+//! let mut gltf = window.factory.load_gltf("AnimatedScene.gltf").unwrap();
 //! gltf.group.set_parent(&window.scene);
 //! ```
 //!
@@ -59,7 +59,7 @@
 //! # use three::Object;
 //! # let mut window = three::Window::new("");
 //! # let mut mixer = three::animation::Mixer::new();
-//! # let mut gltf = window.factory.load_gltf("AnimatedScene.gltf");
+//! # let mut gltf = window.factory.load_gltf("AnimatedScene.gltf").unwrap();
 //! # gltf.group.set_parent(&window.scene);
 //! let actions: Vec<three::animation::Action> = gltf.clips
 //!     .into_iter()
@@ -77,7 +77,7 @@
 //! # let mut window = three::Window::new("");
 //! # let camera = unimplemented!();
 //! # let mut mixer = three::animation::Mixer::new();
-//! # let mut gltf = window.factory.load_gltf("AnimatedScene.gltf");
+//! # let mut gltf = window.factory.load_gltf("AnimatedScene.gltf").unwrap();
 //! # gltf.group.set_parent(&window.scene);
 //! # let actions: Vec<three::animation::Action> = gltf.clips
 //! #     .into_iter()
@@ -105,12 +105,21 @@
 
 use cgmath;
 use froggy;
+use hub;
 use mint;
 use object;
+use std::cmp;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
 use std::hash::{Hash, Hasher};
 use std::sync::mpsc;
+use std::vec;
 
+use color::{self, Color};
 use mint::IntraXYZ as IntraXyz;
+use mesh::Mesh;
+use object::Object;
+use text::{self, Text};
 
 /// A target of an animation.
 pub type Target = object::Base;
@@ -125,10 +134,157 @@ pub enum Interpolation {
     Linear,
 
     /// Smooth cubic interpolation between keyframe values.
+    ///
+    /// The track values hold three entries per keyframe, namely the
+    /// in-tangent, the value, and the out-tangent, as in glTF cubic spline
+    /// samplers. Tangents are given in units per second.
     Cubic,
 
     /// Smooth Catmull–Rom spline interpolation between keyframe values.
     CatmullRom,
+
+    /// Interpolation between keyframe values along an easing curve.
+    Ease(Easing),
+}
+
+/// Standard easing curves for [`Interpolation::Ease`].
+///
+/// `In` curves start slowly, `Out` curves end slowly, and `InOut` curves do
+/// both.
+///
+/// [`Interpolation::Ease`]: enum.Interpolation.html#variant.Ease
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Easing {
+    /// Constant speed.
+    Linear,
+
+    /// Quadratic curve, accelerating from zero velocity.
+    QuadIn,
+
+    /// Quadratic curve, decelerating to zero velocity.
+    QuadOut,
+
+    /// Quadratic curve, accelerating then decelerating.
+    QuadInOut,
+
+    /// Cubic curve, accelerating from zero velocity.
+    CubicIn,
+
+    /// Cubic curve, decelerating to zero velocity.
+    CubicOut,
+
+    /// Cubic curve, accelerating then decelerating.
+    CubicInOut,
+
+    /// Sinusoidal curve, accelerating from zero velocity.
+    SineIn,
+
+    /// Sinusoidal curve, decelerating to zero velocity.
+    SineOut,
+
+    /// Sinusoidal curve, accelerating then decelerating.
+    SineInOut,
+
+    /// Exponential curve, accelerating from zero velocity.
+    ExpoIn,
+
+    /// Exponential curve, decelerating to zero velocity.
+    ExpoOut,
+
+    /// Exponential curve, accelerating then decelerating.
+    ExpoInOut,
+
+    /// Backs up slightly before moving towards the end value.
+    BackIn,
+
+    /// Overshoots the end value slightly before settling on it.
+    BackOut,
+
+    /// Backs up at the start and overshoots at the end.
+    BackInOut,
+
+    /// Bounces against the start value before moving away from it.
+    BounceIn,
+
+    /// Bounces against the end value before settling on it.
+    BounceOut,
+
+    /// Bounces against both the start and the end values.
+    BounceInOut,
+}
+
+impl Easing {
+    /// Maps the linear progress `t` in the range `[0.0, 1.0]` onto the curve.
+    ///
+    /// The result is `0.0` at the start and `1.0` at the end, but may leave
+    /// that range in between for the `Back` curves.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use three::animation::Easing;
+    /// assert_eq!(Easing::QuadIn.apply(0.5), 0.25);
+    /// assert_eq!(Easing::QuadOut.apply(1.0), 1.0);
+    /// ```
+    pub fn apply(
+        &self,
+        t: f32,
+    ) -> f32 {
+        use std::f32::consts::PI;
+        const BACK: f32 = 1.70158;
+        let t = t.max(0.0).min(1.0);
+        // Builds an in-out curve from an in curve.
+        let in_out = |f: &Fn(f32) -> f32| if t < 0.5 {
+            0.5 * f(2.0 * t)
+        } else {
+            1.0 - 0.5 * f(2.0 - 2.0 * t)
+        };
+        // Builds an out curve from an in curve.
+        let out = |f: &Fn(f32) -> f32| 1.0 - f(1.0 - t);
+        let quad = |t: f32| t * t;
+        let cubic = |t: f32| t * t * t;
+        let sine = |t: f32| 1.0 - (t * PI / 2.0).cos();
+        let expo = |t: f32| if t <= 0.0 { 0.0 } else { 2f32.powf(10.0 * (t - 1.0)) };
+        let back = |t: f32| t * t * ((BACK + 1.0) * t - BACK);
+        let bounce = |t: f32| {
+            // Bounces towards 1.0, i.e. this is an out curve.
+            let t = 1.0 - t;
+            let y = if t < 1.0 / 2.75 {
+                7.5625 * t * t
+            } else if t < 2.0 / 2.75 {
+                let t = t - 1.5 / 2.75;
+                7.5625 * t * t + 0.75
+            } else if t < 2.5 / 2.75 {
+                let t = t - 2.25 / 2.75;
+                7.5625 * t * t + 0.9375
+            } else {
+                let t = t - 2.625 / 2.75;
+                7.5625 * t * t + 0.984375
+            };
+            1.0 - y
+        };
+        match *self {
+            Easing::Linear => t,
+            Easing::QuadIn => quad(t),
+            Easing::QuadOut => out(&quad),
+            Easing::QuadInOut => in_out(&quad),
+            Easing::CubicIn => cubic(t),
+            Easing::CubicOut => out(&cubic),
+            Easing::CubicInOut => in_out(&cubic),
+            Easing::SineIn => sine(t),
+            Easing::SineOut => out(&sine),
+            Easing::SineInOut => in_out(&sine),
+            Easing::ExpoIn => expo(t),
+            Easing::ExpoOut => out(&expo),
+            Easing::ExpoInOut => in_out(&expo),
+            Easing::BackIn => back(t),
+            Easing::BackOut => out(&back),
+            Easing::BackInOut => in_out(&back),
+            Easing::BounceIn => bounce(t),
+            Easing::BounceOut => out(&bounce),
+            Easing::BounceInOut => in_out(&bounce),
+        }
+    }
 }
 
 /// Describes the looping behaviour of an [`Action`].
@@ -149,7 +305,8 @@ pub enum LoopMode {
 
     /// Play the clip alternatively in forward and reverse order.
     PingPong {
-        /// The maximum number of repetitions.
+        /// The maximum number of repetitions, where each change of direction
+        /// counts as one repetition.
         ///
         /// When set to `None`, the loop will repeat indefinately.
         limit: Option<u32>,
@@ -178,23 +335,39 @@ pub enum Binding {
 
     /// Targets the scale property of an [`Object`].
     ///
-    /// The corresponding keyframe values must be [`Scalar`].
+    /// The corresponding keyframe values must be [`Vector3`] for per-axis
+    /// scaling or [`Scalar`] for uniform scaling.
     ///
     /// [`Object`]: ../object/trait.Object.html
+    /// [`Vector3`]: enum.Values.html#variant.Vector3
     /// [`Scalar`]: enum.Values.html#variant.Scalar
     Scale,
-}
 
-/// An index into the frames of a track.
-enum FrameRef {
-    /// The time is before the start of the frames.
-    Unstarted,
+    /// Targets the morph target weights of a [`Mesh`].
+    ///
+    /// The corresponding keyframe values must be [`Weights`].
+    ///
+    /// [`Mesh`]: ../struct.Mesh.html
+    /// [`Weights`]: enum.Values.html#variant.Weights
+    Weights,
 
-    /// The time corresponds to the given frame index.
-    InProgress(usize),
+    /// Targets the material color of a [`Mesh`], which is the base color
+    /// factor for PBR materials.
+    ///
+    /// The corresponding keyframe values must be [`Color`]. Colors are
+    /// interpolated in linear space.
+    ///
+    /// [`Mesh`]: ../struct.Mesh.html
+    /// [`Color`]: enum.Values.html#variant.Color
+    Color,
 
-    /// The time is after the end of the last frame.
-    Ended,
+    /// Targets the opacity of a [`Text`].
+    ///
+    /// The corresponding keyframe values must be [`Scalar`].
+    ///
+    /// [`Text`]: ../struct.Text.html
+    /// [`Scalar`]: enum.Values.html#variant.Scalar
+    Opacity,
 }
 
 /// The keyframe values of a [`Track`].
@@ -209,15 +382,155 @@ pub enum Values {
     Quaternion(Vec<mint::Quaternion<f32>>),
 
     /// Scalar keyframes.
-    ///
-    /// ## Note
-    ///
-    /// Only uniform scaling is supported, hence the glTF importer takes the
-    /// Y axis as the scaling direction, ignoring any scaling in the X and Z axes.
     Scalar(Vec<f32>),
 
     /// 3D vector keyframes.
     Vector3(Vec<mint::Vector3<f32>>),
+
+    /// Morph target weight keyframes.
+    ///
+    /// Each keyframe holds one weight per morph target.
+    Weights(Vec<Vec<f32>>),
+
+    /// sRGB color keyframes.
+    Color(Vec<Color>),
+}
+
+impl Values {
+    /// Returns the number of keyframe values.
+    pub fn len(&self) -> usize {
+        match *self {
+            Values::Euler(ref values) => values.len(),
+            Values::Quaternion(ref values) => values.len(),
+            Values::Scalar(ref values) => values.len(),
+            Values::Vector3(ref values) => values.len(),
+            Values::Weights(ref values) => values.len(),
+            Values::Color(ref values) => values.len(),
+        }
+    }
+
+    /// Returns `true` if there are no keyframe values.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if the values are of a type accepted by `binding`.
+    fn is_compatible(
+        &self,
+        binding: Binding,
+    ) -> bool {
+        match (binding, self) {
+            (Binding::Position, &Values::Vector3(_)) |
+            (Binding::Orientation, &Values::Quaternion(_)) |
+            (Binding::Orientation, &Values::Euler(_)) |
+            (Binding::Scale, &Values::Vector3(_)) |
+            (Binding::Scale, &Values::Scalar(_)) |
+            (Binding::Weights, &Values::Weights(_)) |
+            (Binding::Color, &Values::Color(_)) |
+            (Binding::Opacity, &Values::Scalar(_)) => true,
+            _ => false,
+        }
+    }
+}
+
+/// A single keyframe value, as accepted by [`ClipBuilder::key`].
+///
+/// [`ClipBuilder::key`]: struct.ClipBuilder.html#method.key
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    /// See [`Values::Scalar`](enum.Values.html#variant.Scalar).
+    Scalar(f32),
+
+    /// See [`Values::Vector3`](enum.Values.html#variant.Vector3).
+    Vector3(mint::Vector3<f32>),
+
+    /// See [`Values::Quaternion`](enum.Values.html#variant.Quaternion).
+    Quaternion(mint::Quaternion<f32>),
+
+    /// See [`Values::Weights`](enum.Values.html#variant.Weights).
+    Weights(Vec<f32>),
+
+    /// See [`Values::Color`](enum.Values.html#variant.Color).
+    Color(Color),
+}
+
+impl From<f32> for Value {
+    fn from(value: f32) -> Self {
+        Value::Scalar(value)
+    }
+}
+
+impl From<[f32; 3]> for Value {
+    fn from(value: [f32; 3]) -> Self {
+        Value::Vector3(value.into())
+    }
+}
+
+impl From<mint::Vector3<f32>> for Value {
+    fn from(value: mint::Vector3<f32>) -> Self {
+        Value::Vector3(value)
+    }
+}
+
+impl From<mint::Point3<f32>> for Value {
+    fn from(value: mint::Point3<f32>) -> Self {
+        Value::Vector3([value.x, value.y, value.z].into())
+    }
+}
+
+impl From<mint::Quaternion<f32>> for Value {
+    fn from(value: mint::Quaternion<f32>) -> Self {
+        Value::Quaternion(value)
+    }
+}
+
+impl From<Vec<f32>> for Value {
+    fn from(value: Vec<f32>) -> Self {
+        Value::Weights(value)
+    }
+}
+
+impl From<Color> for Value {
+    fn from(value: Color) -> Self {
+        Value::Color(value)
+    }
+}
+
+#[cfg_attr(rustfmt, rustfmt_skip)]
+quick_error! {
+    #[doc = "Error returned when validating a [`Track`](struct.Track.html)."]
+    #[derive(Clone, Debug, PartialEq)]
+    pub enum ValidationError {
+        #[doc = "A keyframe was added before any track was started."]
+        MissingTrack {
+            description("keyframe added before any track")
+            display("Keyframe added before any track")
+        }
+
+        #[doc = "The track has no keyframes."]
+        EmptyTrack {
+            description("track has no keyframes")
+            display("Track has no keyframes")
+        }
+
+        #[doc = "The keyframe time at the given index is before the previous one."]
+        UnsortedTimes(index: usize) {
+            description("keyframe times are not sorted")
+            display("Keyframe time {} is before the previous keyframe time", index)
+        }
+
+        #[doc = "The number of keyframe values does not match the number of keyframe times."]
+        LengthMismatch(expected: usize, found: usize) {
+            description("keyframe value count does not match keyframe time count")
+            display("Expected {} keyframe values, found {}", expected, found)
+        }
+
+        #[doc = "The keyframe values are of a type not accepted by the binding."]
+        IncompatibleValues(binding: Binding) {
+            description("keyframe values incompatible with binding")
+            display("Keyframe values incompatible with {:?} binding", binding)
+        }
+    }
 }
 
 /// Message data sent from `Action` to `Mixer` over a channel.
@@ -227,6 +540,117 @@ enum Operation {
     Pause,
     Play,
     SetLoopMode(LoopMode),
+    SetWeight(f32),
+    SetTimeScale(f32),
+    Seek(f32),
+    Reset,
+    Fade(Fade),
+}
+
+/// A gradual change of the weight of an action.
+#[derive(Clone, Copy, Debug)]
+struct Fade {
+    /// The weight factor at the start of the fade.
+    start: f32,
+
+    /// The weight factor at the end of the fade.
+    end: f32,
+
+    /// The duration of the fade in seconds.
+    duration: f32,
+
+    /// The time elapsed since the start of the fade in seconds.
+    elapsed: f32,
+}
+
+impl Fade {
+    fn new(
+        start: f32,
+        end: f32,
+        duration: f32,
+    ) -> Self {
+        Fade {
+            start,
+            end,
+            duration,
+            elapsed: 0.0,
+        }
+    }
+
+    /// Returns the current weight factor.
+    fn factor(&self) -> f32 {
+        if self.elapsed >= self.duration {
+            self.end
+        } else {
+            let s = self.elapsed / self.duration;
+            self.start * (1.0 - s) + self.end * s
+        }
+    }
+}
+
+/// The blended value of an animated property.
+#[derive(Clone, Debug)]
+enum Pose {
+    Scalar(f32),
+    Quaternion(cgmath::Quaternion<f32>),
+    Vector3(cgmath::Vector3<f32>),
+    Weights(Vec<f32>),
+}
+
+impl Pose {
+    /// Moves the pose towards `other` by the fraction `s`.
+    fn blend(
+        &mut self,
+        other: Pose,
+        s: f32,
+    ) {
+        let blended = match (&*self, other) {
+            (&Pose::Scalar(ref a), Pose::Scalar(ref b)) => Pose::Scalar(a.lerp(b, s)),
+            (&Pose::Quaternion(ref a), Pose::Quaternion(ref b)) => Pose::Quaternion(a.lerp(b, s)),
+            (&Pose::Vector3(ref a), Pose::Vector3(ref b)) => Pose::Vector3(a.lerp(b, s)),
+            (&Pose::Weights(ref a), Pose::Weights(ref b)) => Pose::Weights(a.lerp(b, s)),
+            (_, other) => other,
+        };
+        *self = blended;
+    }
+}
+
+/// The accumulated poses of all actions, along with their total weights.
+type Poses = HashMap<(Target, Binding), (f32, Pose)>;
+
+/// Describes what happened to an [`Action`] during a [`Mixer::update`].
+///
+/// [`Action`]: struct.Action.html
+/// [`Mixer::update`]: struct.Mixer.html#method.update
+#[derive(Clone, Debug, PartialEq)]
+pub enum EventKind {
+    /// The action started playing from the beginning.
+    Started,
+
+    /// The action reached the end of its clip and started a new loop.
+    Looped,
+
+    /// The action played all of the loops allowed by its [`LoopMode`].
+    ///
+    /// [`LoopMode`]: enum.LoopMode.html
+    Finished,
+
+    /// The action passed a marker of its [`Clip`], in either direction.
+    ///
+    /// [`Clip`]: struct.Clip.html
+    Marker(String),
+}
+
+/// An event reported by [`Mixer::events`].
+///
+/// [`Mixer::events`]: struct.Mixer.html#method.events
+#[derive(Clone, Debug)]
+pub struct Event {
+    /// The action the event happened to.
+    pub action: Action,
+
+    /// What happened.
+    pub kind: EventKind,
 }
 
 /// Message type sent from `Action` to `Mixer`.
@@ -264,34 +688,50 @@ impl Hash for Action {
 
 /// Internal data for an animation action.
 struct ActionData {
+    /// Pointer to this action, used to report events.
+    pub pointer: Option<froggy::WeakPointer<ActionData>>,
+
     /// The animation data for this action.
     pub clip: Clip,
 
+    /// The duration of `clip` in seconds.
+    pub duration: f32,
+
     /// Specifies whether the action is enabled or disabled.
     ///
     /// A disabled action has no impact.
     pub enabled: bool,
 
+    /// Specifies whether the action has begun playing.
+    pub started: bool,
+
+    /// Specifies whether the action has played all of its loops.
+    pub finished: bool,
+
     /// Specifies the looping behaviour of this action.
     pub loop_mode: LoopMode,
 
+    /// The number of loops completed so far.
+    pub loop_count: u32,
+
     /// Specifies whether the action is paused.
     pub paused: bool,
 
     /// The local time of this action in seconds, starting at 0.0.
     ///
-    /// This value get clamped or wrapper to [0.0, clip.duration] depending on
-    /// the loop mode.
+    /// This value is always in the range `[0.0, duration]`. In ping-pong mode
+    /// it decreases while the clip is played in reverse order.
     pub local_time: f32,
 
     /// Time scaling factor.
     pub local_time_scale: f32,
 
-    // Unimplemented properties
-    // ------------------------
-    // * weight
-    // * zero_slope_at_end
-    // * zero_slope_at_start
+    /// The influence of this action relative to other actions animating the
+    /// same properties.
+    pub weight: f32,
+
+    /// The fade in progress, if any.
+    pub fade: Option<Fade>,
 }
 
 /// A reusable set of keyframe tracks which represent an animation.
@@ -302,6 +742,45 @@ pub struct Clip {
 
     /// The animation keyframe tracks.
     pub tracks: Vec<(Track, Target)>,
+
+    /// Named points in time, in seconds, reported as
+    /// [`EventKind::Marker`] events when an action passes them.
+    ///
+    /// [`EventKind::Marker`]: enum.EventKind.html#variant.Marker
+    pub markers: Vec<(f32, String)>,
+}
+
+impl Clip {
+    /// Starts building a clip with the given name.
+    ///
+    /// See [`ClipBuilder`](struct.ClipBuilder.html).
+    pub fn builder<S: Into<String>>(name: S) -> ClipBuilder {
+        ClipBuilder {
+            name: name.into(),
+            tracks: Vec::new(),
+            markers: Vec::new(),
+            error: None,
+        }
+    }
+
+    /// Checks that every track of the clip is valid.
+    ///
+    /// See [`Track::validate`](struct.Track.html#method.validate).
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        for &(ref track, _) in &self.tracks {
+            track.validate()?;
+        }
+        Ok(())
+    }
+
+    /// Returns the duration of the clip in seconds, i.e. the time of the last
+    /// keyframe over all tracks.
+    pub fn duration(&self) -> f32 {
+        self.tracks
+            .iter()
+            .filter_map(|&(ref track, _)| track.times.last().cloned())
+            .fold(0.0, |a, b| a.max(b))
+    }
 }
 
 /// A track of animation keyframes.
@@ -320,11 +799,222 @@ pub struct Track {
     pub interpolation: Interpolation,
 }
 
+/// Builds a [`Clip`] from keyframes given one at a time.
+///
+/// Each call to a target method such as [`position`] starts a new track,
+/// and subsequent calls to [`key`], [`ease`], and [`interpolation`] apply
+/// to that track.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # let mut window = three::Window::new("");
+/// # let mut mixer = three::animation::Mixer::new();
+/// use three::animation::{Clip, Easing};
+///
+/// let material = three::material::Basic { color: three::color::RED, map: None };
+/// let geometry = three::Geometry::cuboid(1.0, 1.0, 1.0);
+/// let cube = window.factory.mesh(geometry, material);
+/// let clip = Clip::builder("bounce")
+///     .position(&cube)
+///     .key(0.0, [0.0, 0.0, 0.0])
+///     .key(0.5, [0.0, 1.0, 0.0])
+///     .key(1.0, [0.0, 0.0, 0.0])
+///     .ease(Easing::QuadInOut)
+///     .color(&cube)
+///     .key(0.0, three::color::RED)
+///     .key(1.0, three::color::BLUE)
+///     .marker(0.5, "apex")
+///     .build()
+///     .unwrap();
+/// mixer.action(clip);
+/// ```
+///
+/// [`Clip`]: struct.Clip.html
+/// [`position`]: struct.ClipBuilder.html#method.position
+/// [`key`]: struct.ClipBuilder.html#method.key
+/// [`ease`]: struct.ClipBuilder.html#method.ease
+/// [`interpolation`]: struct.ClipBuilder.html#method.interpolation
+#[derive(Clone, Debug)]
+pub struct ClipBuilder {
+    name: String,
+    tracks: Vec<(Track, Target)>,
+    markers: Vec<(f32, String)>,
+    error: Option<ValidationError>,
+}
+
+impl ClipBuilder {
+    fn track<T: Object>(
+        mut self,
+        binding: Binding,
+        target: &T,
+    ) -> Self {
+        let track = Track {
+            binding,
+            times: Vec::new(),
+            // Replaced by the type of the first keyframe value.
+            values: Values::Scalar(Vec::new()),
+            interpolation: Interpolation::Linear,
+        };
+        self.tracks.push((track, target.upcast()));
+        self
+    }
+
+    /// Starts a track animating the position of `target`.
+    ///
+    /// Keyframe values must be 3D points or vectors.
+    pub fn position<T: Object>(
+        self,
+        target: &T,
+    ) -> Self {
+        self.track(Binding::Position, target)
+    }
+
+    /// Starts a track animating the orientation of `target`.
+    ///
+    /// Keyframe values must be quaternions.
+    pub fn orientation<T: Object>(
+        self,
+        target: &T,
+    ) -> Self {
+        self.track(Binding::Orientation, target)
+    }
+
+    /// Starts a track animating the scale of `target`.
+    ///
+    /// Keyframe values must be 3D vectors or scalars.
+    pub fn scale<T: Object>(
+        self,
+        target: &T,
+    ) -> Self {
+        self.track(Binding::Scale, target)
+    }
+
+    /// Starts a track animating the morph target weights of `mesh`.
+    ///
+    /// Keyframe values must be vectors of weights.
+    pub fn weights(
+        self,
+        mesh: &Mesh,
+    ) -> Self {
+        self.track(Binding::Weights, mesh)
+    }
+
+    /// Starts a track animating the material color of `mesh`.
+    ///
+    /// Keyframe values must be colors.
+    pub fn color(
+        self,
+        mesh: &Mesh,
+    ) -> Self {
+        self.track(Binding::Color, mesh)
+    }
+
+    /// Starts a track animating the opacity of `text`.
+    ///
+    /// Keyframe values must be scalars.
+    pub fn opacity(
+        self,
+        text: &Text,
+    ) -> Self {
+        self.track(Binding::Opacity, text)
+    }
+
+    /// Adds a keyframe at `time` seconds to the current track.
+    ///
+    /// Keyframes must be added in order of time.
+    pub fn key<V: Into<Value>>(
+        mut self,
+        time: f32,
+        value: V,
+    ) -> Self {
+        let value = value.into();
+        if self.tracks.is_empty() {
+            self.error = self.error.or(Some(ValidationError::MissingTrack));
+            return self;
+        }
+        let track = &mut self.tracks.last_mut().unwrap().0;
+        if track.times.is_empty() {
+            track.values = match value {
+                Value::Scalar(_) => Values::Scalar(Vec::new()),
+                Value::Vector3(_) => Values::Vector3(Vec::new()),
+                Value::Quaternion(_) => Values::Quaternion(Vec::new()),
+                Value::Weights(_) => Values::Weights(Vec::new()),
+                Value::Color(_) => Values::Color(Vec::new()),
+            };
+        }
+        track.times.push(time);
+        match (&mut track.values, value) {
+            (&mut Values::Scalar(ref mut values), Value::Scalar(value)) => values.push(value),
+            (&mut Values::Vector3(ref mut values), Value::Vector3(value)) => values.push(value),
+            (&mut Values::Quaternion(ref mut values), Value::Quaternion(value)) => values.push(value),
+            (&mut Values::Weights(ref mut values), Value::Weights(value)) => values.push(value),
+            (&mut Values::Color(ref mut values), Value::Color(value)) => values.push(value),
+            _ => {
+                let error = ValidationError::IncompatibleValues(track.binding);
+                self.error = self.error.or(Some(error));
+            }
+        }
+        self
+    }
+
+    /// Sets the easing curve used between the keyframes of the current track.
+    pub fn ease(
+        self,
+        easing: Easing,
+    ) -> Self {
+        self.interpolation(Interpolation::Ease(easing))
+    }
+
+    /// Sets the interpolation used between the keyframes of the current
+    /// track. The default is [`Interpolation::Linear`].
+    ///
+    /// [`Interpolation::Linear`]: enum.Interpolation.html#variant.Linear
+    pub fn interpolation(
+        mut self,
+        interpolation: Interpolation,
+    ) -> Self {
+        match self.tracks.last_mut() {
+            Some(&mut (ref mut track, _)) => track.interpolation = interpolation,
+            None => self.error = self.error.or(Some(ValidationError::MissingTrack)),
+        }
+        self
+    }
+
+    /// Adds a marker at `time` seconds, reported as an
+    /// [`EventKind::Marker`] event when an action passes it.
+    ///
+    /// [`EventKind::Marker`]: enum.EventKind.html#variant.Marker
+    pub fn marker<S: Into<String>>(
+        mut self,
+        time: f32,
+        name: S,
+    ) -> Self {
+        self.markers.push((time, name.into()));
+        self
+    }
+
+    /// Validates the tracks and returns the clip.
+    pub fn build(self) -> Result<Clip, ValidationError> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        let clip = Clip {
+            name: Some(self.name),
+            tracks: self.tracks,
+            markers: self.markers,
+        };
+        clip.validate()?;
+        Ok(clip)
+    }
+}
+
 /// Scheduler for the playback of animation actions.
 ///
 /// Use this to update animation actions.
 pub struct Mixer {
     actions: froggy::Storage<ActionData>,
+    events: Vec<Event>,
     rx: mpsc::Receiver<Message>,
     tx: mpsc::Sender<Message>,
 }
@@ -366,6 +1056,59 @@ impl Action {
     ) -> &mut Self {
         self.send(Operation::SetLoopMode(loop_mode))
     }
+
+    /// Sets the influence of the action relative to other actions animating
+    /// the same properties. The default weight is `1.0`.
+    ///
+    /// The weight is multiplied by the factor of any fade in progress.
+    pub fn set_weight(
+        &mut self,
+        weight: f32,
+    ) -> &mut Self {
+        self.send(Operation::SetWeight(weight))
+    }
+
+    /// Sets the speed of playback, where `1.0` is normal speed. Negative
+    /// values play the clip backwards.
+    pub fn set_time_scale(
+        &mut self,
+        time_scale: f32,
+    ) -> &mut Self {
+        self.send(Operation::SetTimeScale(time_scale))
+    }
+
+    /// Jumps to the given time within the clip, in seconds.
+    ///
+    /// The time is clamped to the duration of the clip. A finished action
+    /// resumes playing from there.
+    pub fn seek(
+        &mut self,
+        time: f32,
+    ) -> &mut Self {
+        self.send(Operation::Seek(time))
+    }
+
+    /// Rewinds the action to the start of its clip, clearing its loop count
+    /// and any fade, and plays it.
+    pub fn reset(&mut self) -> &mut Self {
+        self.send(Operation::Reset)
+    }
+
+    /// Fades the influence of the action in from zero over `duration` seconds.
+    pub fn fade_in(
+        &mut self,
+        duration: f32,
+    ) -> &mut Self {
+        self.send(Operation::Fade(Fade::new(0.0, 1.0, duration)))
+    }
+
+    /// Fades the influence of the action out to zero over `duration` seconds.
+    pub fn fade_out(
+        &mut self,
+        duration: f32,
+    ) -> &mut Self {
+        self.send(Operation::Fade(Fade::new(1.0, 0.0, duration)))
+    }
 }
 
 impl Mixer {
@@ -384,6 +1127,22 @@ impl Mixer {
                     action.enabled = true;
                 }
                 Operation::SetLoopMode(loop_mode) => action.loop_mode = loop_mode,
+                Operation::SetWeight(weight) => action.weight = weight,
+                Operation::SetTimeScale(time_scale) => action.local_time_scale = time_scale,
+                Operation::Seek(time) => {
+                    action.local_time = time.max(0.0).min(action.duration);
+                    action.finished = false;
+                }
+                Operation::Reset => {
+                    action.enabled = true;
+                    action.paused = false;
+                    action.started = false;
+                    action.finished = false;
+                    action.local_time = 0.0;
+                    action.loop_count = 0;
+                    action.fade = None;
+                }
+                Operation::Fade(fade) => action.fade = Some(fade),
             }
         }
     }
@@ -392,32 +1151,149 @@ impl Mixer {
         &mut self,
         delta_time: f32,
     ) {
+        let mut poses = Poses::new();
+        self.events.clear();
         for action in self.actions.iter_mut() {
-            action.update(delta_time);
+            let mut events = Vec::new();
+            action.update(delta_time, &mut poses, &mut events);
+            if events.is_empty() {
+                continue;
+            }
+            let pointer = match action.pointer.as_ref().map(|weak| weak.upgrade()) {
+                Some(Ok(pointer)) => pointer,
+                _ => continue,
+            };
+            for kind in events {
+                self.events.push(Event {
+                    action: Action {
+                        tx: self.tx.clone(),
+                        pointer: pointer.clone(),
+                    },
+                    kind,
+                });
+            }
+        }
+        for ((target, binding), (total_weight, pose)) in poses {
+            // Poses are blended with the rest pose by whatever weight is
+            // left over by the actions.
+            let weight = total_weight.min(1.0);
+            match (binding, pose) {
+                (Binding::Position, Pose::Vector3(position)) => {
+                    use cgmath::EuclideanSpace;
+                    let position = cgmath::Point3::from_vec(position).into();
+                    let operation = hub::Operation::AnimateTransform(Some(position), None, None, weight);
+                    let _ = target.tx.send((target.node.downgrade(), operation));
+                }
+                (Binding::Orientation, Pose::Quaternion(orientation)) => {
+                    let operation = hub::Operation::AnimateTransform(None, Some(orientation.into()), None, weight);
+                    let _ = target.tx.send((target.node.downgrade(), operation));
+                }
+                (Binding::Scale, Pose::Vector3(scale)) => {
+                    let operation = hub::Operation::AnimateTransform(None, None, Some(scale.into()), weight);
+                    let _ = target.tx.send((target.node.downgrade(), operation));
+                }
+                (Binding::Weights, Pose::Weights(weights)) => {
+                    // The rest pose of morph targets has all weights zero.
+                    let weights = weights.into_iter().map(|w| w * weight).collect();
+                    let message = (target.node.downgrade(), hub::Operation::SetWeights(weights));
+                    let _ = target.tx.send(message);
+                }
+                (Binding::Color, Pose::Vector3(rgb)) => {
+                    let color = color::from_linear_rgb(rgb.into());
+                    let message = (target.node.downgrade(), hub::Operation::SetColor(color));
+                    let _ = target.tx.send(message);
+                }
+                (Binding::Opacity, Pose::Scalar(opacity)) => {
+                    let operation = hub::Operation::SetText(text::Operation::Opacity(opacity));
+                    let _ = target.tx.send((target.node.downgrade(), operation));
+                }
+                _ => unreachable!(),
+            }
         }
     }
 
     /// Creates a new animation mixer.
     pub fn new() -> Self {
         let actions = froggy::Storage::new();
+        let events = Vec::new();
         let (tx, rx) = mpsc::channel();
-        Mixer { actions, rx, tx }
+        Mixer {
+            actions,
+            events,
+            rx,
+            tx,
+        }
     }
 
     /// Spawns a new animation [`Action`] to be updated by this mixer.
     ///
+    /// Invalid tracks of the clip are skipped with a warning, see
+    /// [`Clip::validate`].
+    ///
     /// [`Action`]: struct.Action.html
+    /// [`Clip::validate`]: struct.Clip.html#method.validate
     pub fn action(
         &mut self,
-        clip: Clip,
+        mut clip: Clip,
     ) -> Action {
+        clip.tracks.retain(|&(ref track, _)| match track.validate() {
+            Ok(()) => true,
+            Err(err) => {
+                warn!("Skipping invalid animation track: {}", err);
+                false
+            }
+        });
         let action_data = ActionData::new(clip);
         let pointer = self.actions.create(action_data);
+        self.actions[&pointer].pointer = Some(pointer.downgrade());
         let tx = self.tx.clone();
         Action { tx, pointer }
     }
 
+    /// Fades `from` out and `to` in over `duration` seconds, playing `to` if
+    /// it was paused or disabled.
+    ///
+    /// # Examples
+    ///
+    /// Transitioning from a walk cycle to a run cycle.
+    ///
+    /// ```rust,no_run
+    /// # let mut window = three::Window::new("");
+    /// # let mut mixer = three::animation::Mixer::new();
+    /// # let (walk_clip, run_clip) = unimplemented!();
+    /// let walk = mixer.action(walk_clip);
+    /// let mut run = mixer.action(run_clip);
+    /// run.set_weight(0.0);
+    ///
+    /// // Later on...
+    /// run.set_weight(1.0);
+    /// mixer.cross_fade(&walk, &run, 0.5);
+    /// ```
+    pub fn cross_fade(
+        &mut self,
+        from: &Action,
+        to: &Action,
+        duration: f32,
+    ) {
+        let messages = vec![
+            (from.pointer.downgrade(), Operation::Fade(Fade::new(1.0, 0.0, duration))),
+            (to.pointer.downgrade(), Operation::Play),
+            (to.pointer.downgrade(), Operation::Fade(Fade::new(0.0, 1.0, duration))),
+        ];
+        for message in messages {
+            let _ = self.tx.send(message);
+        }
+    }
+
     /// Updates the actions owned by the mixer.
+    ///
+    /// Actions animating the same property of the same object are blended
+    /// according to their weights. Where the weights add up to less than
+    /// one, the transforms and morph target weights are further blended
+    /// with their rest pose, which is how they were before they were first
+    /// animated, so that an action of weight `0.3` moves its targets 30% of
+    /// the way to its pose and fades ease into and out of it. Colors and
+    /// opacities are applied in full whatever the weights.
     pub fn update(
         &mut self,
         delta_time: f32,
@@ -425,103 +1301,252 @@ impl Mixer {
         self.process_messages();
         self.update_actions(delta_time);
     }
+
+    /// Drains the events that happened during the last call to
+    /// [`Mixer::update`].
+    ///
+    /// Events that are not drained are discarded by the next update.
+    ///
+    /// # Examples
+    ///
+    /// Playing a sound on every footstep.
+    ///
+    /// ```rust,no_run
+    /// # let mut window = three::Window::new("");
+    /// # let mut mixer = three::animation::Mixer::new();
+    /// use three::animation::EventKind;
+    /// mixer.update(window.input.delta_time());
+    /// for event in mixer.events() {
+    ///     match event.kind {
+    ///         EventKind::Marker(ref name) if name == "footstep" => {
+    ///             // Play the footstep sound.
+    ///         }
+    ///         _ => {}
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// [`Mixer::update`]: struct.Mixer.html#method.update
+    pub fn events(&mut self) -> vec::Drain<Event> {
+        self.events.drain(..)
+    }
+
+    /// Returns the current time of an [`Action`] within its clip, in seconds.
+    ///
+    /// The value is as of the last call to [`Mixer::update`].
+    ///
+    /// [`Action`]: struct.Action.html
+    /// [`Mixer::update`]: struct.Mixer.html#method.update
+    pub fn time(
+        &self,
+        action: &Action,
+    ) -> f32 {
+        self.actions[&action.pointer].local_time
+    }
+
+    /// Returns the number of loops an [`Action`] has completed.
+    ///
+    /// In ping-pong mode, each change of direction counts as one loop.
+    ///
+    /// [`Action`]: struct.Action.html
+    pub fn loop_count(
+        &self,
+        action: &Action,
+    ) -> u32 {
+        self.actions[&action.pointer].loop_count
+    }
+
+    /// Returns `true` if an [`Action`] has played all of the loops allowed by
+    /// its [`LoopMode`].
+    ///
+    /// A finished action holds the last pose of its clip.
+    ///
+    /// [`Action`]: struct.Action.html
+    /// [`LoopMode`]: enum.LoopMode.html
+    pub fn is_finished(
+        &self,
+        action: &Action,
+    ) -> bool {
+        self.actions[&action.pointer].finished
+    }
 }
 
 impl ActionData {
     fn new(clip: Clip) -> Self {
         ActionData {
+            pointer: None,
+            duration: clip.duration(),
             clip: clip,
             enabled: true,
+            started: false,
+            finished: false,
             loop_mode: LoopMode::Repeat { limit: None },
+            loop_count: 0,
             paused: false,
             local_time: 0.0,
             local_time_scale: 1.0,
+            weight: 1.0,
+            fade: None,
         }
     }
 
-    /// Updates a single animation action.
-    fn update(
+    /// Advances the local time by `delta_time` scaled seconds, wrapping
+    /// around or changing direction at the ends of the clip as specified by
+    /// the loop mode.
+    ///
+    /// Any time left over when wrapping is carried into the next loop. The
+    /// events that happen along the way are appended to `events`.
+    fn advance(
         &mut self,
         delta_time: f32,
+        events: &mut Vec<EventKind>,
     ) {
-        if self.paused || !self.enabled {
+        let duration = self.duration;
+        let (limit, ping_pong) = match self.loop_mode {
+            LoopMode::Once => (Some(0), false),
+            LoopMode::Repeat { limit } => (limit, false),
+            LoopMode::PingPong { limit } => (limit, true),
+        };
+        // Markers at the very start of the clip are passed when it starts.
+        let mut inclusive = !self.started;
+        if !self.started {
+            self.started = true;
+            events.push(EventKind::Started);
+        }
+        if duration <= 0.0 {
+            self.local_time = 0.0;
+            self.finished = true;
+            events.push(EventKind::Finished);
             return;
         }
 
-        self.local_time += delta_time * self.local_time_scale;
-        let mut finish_count = 0;
-        for &mut (ref track, ref mut target) in self.clip.tracks.iter_mut() {
-            let frame_index = match track.frame_at_time(self.local_time) {
-                FrameRef::Unstarted => continue,
-                FrameRef::Ended => {
-                    finish_count += 1;
-                    continue;
-                }
-                FrameRef::InProgress(i) => i,
+        // Progress through the current loop, which runs backwards in time
+        // during the reverse loops of ping-pong mode.
+        let reversed = |count: u32| ping_pong && count % 2 == 1;
+        let mut progress = if reversed(self.loop_count) {
+            duration - self.local_time
+        } else {
+            self.local_time
+        };
+        let mut start = progress;
+        progress += delta_time;
+        loop {
+            let exhausted = limit.map_or(false, |n| self.loop_count >= n);
+            let (end, wrapped) = if exhausted && (progress >= duration || progress < 0.0) {
+                progress = progress.max(0.0).min(duration);
+                self.finished = true;
+                (progress, false)
+            } else if progress > duration {
+                progress -= duration;
+                (duration, true)
+            } else if progress < 0.0 {
+                progress += duration;
+                (0.0, true)
+            } else {
+                (progress, false)
             };
-            let frame_start_time = track.times[frame_index];
-            let frame_end_time = track.times[frame_index + 1];
-            let frame_delta_time = frame_end_time - frame_start_time;
-            // Interpolation constant in range `[0.0, 1.0]` between `frame[i]`
-            // and `frame[i + 1]`.
-            let s = (self.local_time - frame_start_time) / frame_delta_time;
-
-            match (track.binding, &track.values) {
-                (Binding::Orientation, &Values::Euler(ref values)) => {
-                    let frame_start_value = {
-                        let euler = values[frame_index];
-                        cgmath::Quaternion::from(cgmath::Euler::new(
-                            cgmath::Rad(euler.a),
-                            cgmath::Rad(euler.b),
-                            cgmath::Rad(euler.c),
-                        ))
-                    };
-                    let frame_end_value = {
-                        let euler = values[frame_index + 1];
-                        cgmath::Quaternion::from(cgmath::Euler::new(
-                            cgmath::Rad(euler.a),
-                            cgmath::Rad(euler.b),
-                            cgmath::Rad(euler.c),
-                        ))
-                    };
-                    let update = frame_start_value.slerp(frame_end_value, s);
-                    target.set_orientation(update);
-                }
-                (Binding::Orientation, &Values::Quaternion(ref values)) => {
-                    let frame_start_value: cgmath::Quaternion<f32> = values[frame_index].into();
-                    let frame_end_value: cgmath::Quaternion<f32> = values[frame_index + 1].into();
-                    let update = frame_start_value.slerp(frame_end_value, s);
-                    target.set_orientation(update);
-                }
-                (Binding::Position, &Values::Vector3(ref values)) => {
-                    use cgmath::{EuclideanSpace, InnerSpace};
-                    let frame_start_value: cgmath::Vector3<f32> = values[frame_index].into();
-                    let frame_end_value: cgmath::Vector3<f32> = values[frame_index + 1].into();
-                    let update = frame_start_value.lerp(frame_end_value, s);
-                    target.set_position(cgmath::Point3::from_vec(update));
-                }
-                (Binding::Scale, &Values::Scalar(ref values)) => {
-                    let frame_start_value = values[frame_index];
-                    let frame_end_value = values[frame_index + 1];
-                    let update = frame_start_value * (1.0 - s) + frame_end_value * s;
-                    target.set_scale(update);
+            for &(time, ref name) in &self.clip.markers {
+                let marker = if reversed(self.loop_count) {
+                    duration - time
+                } else {
+                    time
+                };
+                let passed = if start <= end {
+                    (start < marker || (inclusive && start == marker)) && marker <= end
+                } else {
+                    end <= marker && (marker < start || (inclusive && start == marker))
+                };
+                if passed {
+                    events.push(EventKind::Marker(name.clone()));
                 }
-                _ => panic!("Unsupported (binding, value) pair"),
             }
+            if self.finished {
+                events.push(EventKind::Finished);
+            }
+            if !wrapped {
+                break;
+            }
+            self.loop_count += 1;
+            events.push(EventKind::Looped);
+            start = duration - end;
+            // Repeating jumps to the other end of the clip, so markers there
+            // are passed again. Changing direction stays at the same end,
+            // whose markers were just passed.
+            inclusive = !ping_pong;
+        }
+        self.local_time = if reversed(self.loop_count) {
+            duration - progress
+        } else {
+            progress
+        };
+    }
+
+    /// Returns the weight of the action, including the factor of any fade.
+    fn effective_weight(&self) -> f32 {
+        self.weight * self.fade.map_or(1.0, |fade| fade.factor())
+    }
+
+    /// Updates a single animation action and adds its weighted pose to
+    /// `poses`.
+    fn update(
+        &mut self,
+        delta_time: f32,
+        poses: &mut Poses,
+        events: &mut Vec<EventKind>,
+    ) {
+        if !self.enabled {
+            return;
         }
 
-        if finish_count == self.clip.tracks.len() {
-            match self.loop_mode {
-                LoopMode::Once => self.enabled = false,
-                LoopMode::Repeat { limit: None } => self.local_time = 0.0,
-                LoopMode::Repeat { limit: Some(0) } => self.enabled = false,
-                LoopMode::Repeat { limit: Some(n) } => {
-                    self.local_time = 0.0;
-                    self.loop_mode = LoopMode::Repeat { limit: Some(n - 1) };
+        // A fade ending during this update contributes its final weight,
+        // even if that is zero, so that fading out returns to the rest pose.
+        let fading = self.fade.map_or(false, |fade| fade.elapsed < fade.duration);
+        if let Some(ref mut fade) = self.fade {
+            fade.elapsed += delta_time;
+        }
+        if !self.paused && !self.finished {
+            self.advance(delta_time * self.local_time_scale, events);
+        }
+
+        let weight = self.effective_weight().max(0.0);
+        if weight <= 0.0 && !fading {
+            return;
+        }
+
+        for &(ref track, ref target) in &self.clip.tracks {
+            let (frame_index, s) = track.frame_at_time(self.local_time);
+            let pose = match (track.binding, &track.values) {
+                (Binding::Orientation, &Values::Euler(ref values)) => Pose::Quaternion(track.sample(frame_index, s, |i| {
+                    let euler = values[i];
+                    cgmath::Quaternion::from(cgmath::Euler::new(
+                        cgmath::Rad(euler.a),
+                        cgmath::Rad(euler.b),
+                        cgmath::Rad(euler.c),
+                    ))
+                })),
+                (Binding::Orientation, &Values::Quaternion(ref values)) => Pose::Quaternion(track.sample(frame_index, s, |i| cgmath::Quaternion::from(values[i]))),
+                (Binding::Position, &Values::Vector3(ref values)) => Pose::Vector3(track.sample(frame_index, s, |i| cgmath::Vector3::from(values[i]))),
+                (Binding::Scale, &Values::Scalar(ref values)) => {
+                    let scale = track.sample(frame_index, s, |i| values[i]);
+                    Pose::Vector3(cgmath::Vector3::new(scale, scale, scale))
+                }
+                (Binding::Scale, &Values::Vector3(ref values)) => Pose::Vector3(track.sample(frame_index, s, |i| cgmath::Vector3::from(values[i]))),
+                (Binding::Weights, &Values::Weights(ref values)) => Pose::Weights(track.sample(frame_index, s, |i| values[i].clone())),
+                (Binding::Color, &Values::Color(ref values)) => Pose::Vector3(track.sample(frame_index, s, |i| cgmath::Vector3::from(color::to_linear_rgb(values[i])))),
+                (Binding::Opacity, &Values::Scalar(ref values)) => Pose::Scalar(track.sample(frame_index, s, |i| values[i])),
+                // Tracks are validated by `Mixer::action`.
+                _ => continue,
+            };
+            match poses.entry((target.clone(), track.binding)) {
+                Entry::Occupied(mut entry) => {
+                    let &mut (ref mut total_weight, ref mut blended) = entry.get_mut();
+                    *total_weight += weight;
+                    if *total_weight > 0.0 {
+                        blended.blend(pose, weight / *total_weight);
+                    }
                 }
-                LoopMode::PingPong { .. } => {
-                    // TODO
-                    unimplemented!()
+                Entry::Vacant(entry) => {
+                    entry.insert((weight, pose));
                 }
             }
         }
@@ -529,18 +1554,51 @@ impl ActionData {
 }
 
 impl Track {
+    /// Checks that the track has keyframes, that the keyframe times are
+    /// sorted, and that the keyframe values match the times and the binding.
+    ///
+    /// Tracks with [`Interpolation::Cubic`] need three values per keyframe.
+    ///
+    /// [`Interpolation::Cubic`]: enum.Interpolation.html#variant.Cubic
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        if self.times.is_empty() {
+            return Err(ValidationError::EmptyTrack);
+        }
+        for i in 1 .. self.times.len() {
+            // Written to also reject NaN times.
+            if !(self.times[i] >= self.times[i - 1]) {
+                return Err(ValidationError::UnsortedTimes(i));
+            }
+        }
+        let values_per_keyframe = match self.interpolation {
+            Interpolation::Cubic => 3,
+            _ => 1,
+        };
+        let expected = values_per_keyframe * self.times.len();
+        if self.values.len() != expected {
+            return Err(ValidationError::LengthMismatch(expected, self.values.len()));
+        }
+        if !self.values.is_compatible(self.binding) {
+            return Err(ValidationError::IncompatibleValues(self.binding));
+        }
+        Ok(())
+    }
+
+    /// Returns the index of the keyframe at or before time `t`, and the
+    /// interpolation constant in the range `[0.0, 1.0]` between that
+    /// keyframe and the next one.
+    ///
+    /// Times outside the track are clamped to its first or last keyframe.
     fn frame_at_time(
         &self,
         t: f32,
-    ) -> FrameRef {
-        if t < self.times[0] {
-            // The clip hasn't started yet.
-            return FrameRef::Unstarted;
+    ) -> (usize, f32) {
+        let last = self.times.len() - 1;
+        if last == 0 || t <= self.times[0] {
+            return (0, 0.0);
         }
-
-        if t > *self.times.last().unwrap() {
-            // The clip has ended.
-            return FrameRef::Ended;
+        if t >= self.times[last] {
+            return (last - 1, 1.0);
         }
 
         let mut i = 0;
@@ -548,6 +1606,170 @@ impl Track {
             i += 1;
         }
 
-        FrameRef::InProgress(i)
+        let frame_start_time = self.times[i];
+        let frame_delta_time = self.times[i + 1] - frame_start_time;
+        if frame_delta_time > 0.0 {
+            (i, (t - frame_start_time) / frame_delta_time)
+        } else {
+            (i, 0.0)
+        }
+    }
+
+    /// Interpolates between keyframe `frame_index` and the next keyframe.
+    ///
+    /// `value(i)` returns the `i`th entry of the track values, which for
+    /// cubic spline tracks includes the tangents.
+    fn sample<T, F>(
+        &self,
+        frame_index: usize,
+        s: f32,
+        value: F,
+    ) -> T
+    where
+        T: Keyframe,
+        F: Fn(usize) -> T,
+    {
+        let last = self.times.len() - 1;
+        let next_index = cmp::min(frame_index + 1, last);
+        let frame_delta_time = self.times[next_index] - self.times[frame_index];
+        match self.interpolation {
+            Interpolation::Discrete => if s < 1.0 {
+                value(frame_index)
+            } else {
+                value(next_index)
+            },
+            Interpolation::Linear => value(frame_index).lerp(&value(next_index), s),
+            Interpolation::Ease(easing) => value(frame_index).lerp(&value(next_index), easing.apply(s)),
+            Interpolation::Cubic => hermite(
+                &value(3 * frame_index + 1),
+                &value(3 * frame_index + 2),
+                &value(3 * next_index + 1),
+                &value(3 * next_index),
+                frame_delta_time,
+                s,
+            ),
+            Interpolation::CatmullRom => {
+                // Non-uniform Catmull-Rom tangents, one-sided at the ends.
+                let tangent = |i: usize| {
+                    let (prev, next) = (i.saturating_sub(1), cmp::min(i + 1, last));
+                    let span = self.times[next] - self.times[prev];
+                    let k = if span > 0.0 { 1.0 / span } else { 0.0 };
+                    T::combine(&[(k, &value(next)), (-k, &value(prev))])
+                };
+                hermite(
+                    &value(frame_index),
+                    &tangent(frame_index),
+                    &value(next_index),
+                    &tangent(next_index),
+                    frame_delta_time,
+                    s,
+                )
+            }
+        }
+    }
+}
+
+/// Keyframe values that can be interpolated.
+trait Keyframe: Clone {
+    /// Returns the sum of the given values multiplied by their weights.
+    fn combine(terms: &[(f32, &Self)]) -> Self;
+
+    /// Interpolates linearly between `self` and `other`.
+    fn lerp(
+        &self,
+        other: &Self,
+        s: f32,
+    ) -> Self {
+        Self::combine(&[(1.0 - s, self), (s, other)])
+    }
+
+    /// Corrects a value computed by spline interpolation.
+    fn normalize_spline(self) -> Self {
+        self
+    }
+}
+
+impl Keyframe for f32 {
+    fn combine(terms: &[(f32, &Self)]) -> Self {
+        terms.iter().map(|&(k, v)| k * v).sum()
+    }
+}
+
+impl Keyframe for cgmath::Vector3<f32> {
+    fn combine(terms: &[(f32, &Self)]) -> Self {
+        terms
+            .iter()
+            .fold(cgmath::Vector3::new(0.0, 0.0, 0.0), |acc, &(k, v)| acc + v * k)
+    }
+}
+
+impl Keyframe for cgmath::Quaternion<f32> {
+    fn combine(terms: &[(f32, &Self)]) -> Self {
+        terms
+            .iter()
+            .fold(cgmath::Quaternion::new(0.0, 0.0, 0.0, 0.0), |acc, &(k, q)| acc + q * k)
     }
+
+    fn lerp(
+        &self,
+        other: &Self,
+        s: f32,
+    ) -> Self {
+        slerp(*self, *other, s)
+    }
+
+    fn normalize_spline(self) -> Self {
+        use cgmath::InnerSpace;
+        self.normalize()
+    }
+}
+
+impl Keyframe for Vec<f32> {
+    fn combine(terms: &[(f32, &Self)]) -> Self {
+        let len = terms.iter().map(|&(_, v)| v.len()).max().unwrap_or(0);
+        (0 .. len)
+            .map(|i| {
+                terms
+                    .iter()
+                    .map(|&(k, v)| k * v.get(i).cloned().unwrap_or(0.0))
+                    .sum::<f32>()
+            })
+            .collect()
+    }
+}
+
+/// Interpolates spherically between the orientations `a` and `b` along the
+/// shortest arc.
+///
+/// `q` and `-q` represent the same orientation, so `b` is negated if it lies
+/// in the opposite hemisphere to `a`. Besides taking the long way around,
+/// `slerp` is not finite between opposite quaternions.
+pub(crate) fn slerp(
+    a: cgmath::Quaternion<f32>,
+    b: cgmath::Quaternion<f32>,
+    s: f32,
+) -> cgmath::Quaternion<f32> {
+    use cgmath::InnerSpace;
+    let b = if a.dot(b) < 0.0 { -b } else { b };
+    a.slerp(b, s)
+}
+
+/// Evaluates the cubic Hermite spline between `p0` and `p1` with tangents
+/// `m0` and `m1`, given per second over a frame lasting `frame_delta_time`.
+fn hermite<T: Keyframe>(
+    p0: &T,
+    m0: &T,
+    p1: &T,
+    m1: &T,
+    frame_delta_time: f32,
+    s: f32,
+) -> T {
+    let s2 = s * s;
+    let s3 = s2 * s;
+    T::combine(&[
+        (2.0 * s3 - 3.0 * s2 + 1.0, p0),
+        ((s3 - 2.0 * s2 + s) * frame_delta_time, m0),
+        (-2.0 * s3 + 3.0 * s2, p1),
+        ((s3 - s2) * frame_delta_time, m1),
+    ]).normalize_spline()
 }
@@ -226,7 +226,7 @@ impl SourceInternal {
                 let mut boxed: Box<r::Source<Item = i16> + Send> = if let Ok(decoder) = decoder {
                     Box::new(decoder)
                 } else {
-                    eprintln!("Can't recognize audio clip format, can't play sound");
+                    error!("Can't recognize audio clip format, can't play sound");
                     return;
                 };
                 if clip.repeat {
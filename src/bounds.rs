@@ -0,0 +1,217 @@
+//! Bounding volumes and view frustum culling.
+
+use cgmath::{EuclideanSpace, InnerSpace, Matrix, Matrix3, Matrix4, Point3, Transform, Vector3, Vector4};
+use mint;
+use std::f32;
+
+/// Axis-aligned bounding box and bounding sphere enclosing a mesh.
+///
+/// Bounds are computed from the geometry given to `Factory::mesh`, and used
+/// to skip drawing meshes outside the view of the camera or of a shadow
+/// casting light. Dynamic meshes, skinned meshes and meshes with morph
+/// targets can move their vertices anywhere, so they have no bounds and are
+/// never culled.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Bounds {
+    /// Minimum corner of the axis-aligned bounding box.
+    pub min: mint::Point3<f32>,
+    /// Maximum corner of the axis-aligned bounding box.
+    pub max: mint::Point3<f32>,
+    /// Center of the bounding sphere.
+    pub center: mint::Point3<f32>,
+    /// Radius of the bounding sphere.
+    pub radius: f32,
+}
+
+impl Bounds {
+    /// Computes the bounds of a set of points, or `None` if there are no points.
+    pub(crate) fn from_points(points: &[mint::Point3<f32>]) -> Option<Self> {
+        if points.is_empty() {
+            return None;
+        }
+        let mut min = Point3::from(points[0]);
+        let mut max = min;
+        for &p in points {
+            min.x = min.x.min(p.x);
+            min.y = min.y.min(p.y);
+            min.z = min.z.min(p.z);
+            max.x = max.x.max(p.x);
+            max.y = max.y.max(p.y);
+            max.z = max.z.max(p.z);
+        }
+        let center = min.midpoint(max);
+        let radius = points
+            .iter()
+            .map(|&p| (Point3::from(p) - center).magnitude2())
+            .fold(0.0, |a, b| a.max(b))
+            .sqrt();
+        Some(Bounds {
+            min: min.into(),
+            max: max.into(),
+            center: center.into(),
+            radius,
+        })
+    }
+
+    /// Returns the bounds of the volume after it has been moved by `transform`.
+    ///
+    /// The bounding box is recomputed from the transformed corners, hence it
+    /// may grow under rotation.
+    pub(crate) fn transformed(
+        &self,
+        transform: &Matrix4<f32>,
+    ) -> Self {
+        let (lo, hi) = (Point3::from(self.min), Point3::from(self.max));
+        let mut min = Point3::new(f32::INFINITY, f32::INFINITY, f32::INFINITY);
+        let mut max = Point3::new(f32::NEG_INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY);
+        for i in 0 .. 8 {
+            let corner = Point3::new(
+                if i & 1 == 0 { lo.x } else { hi.x },
+                if i & 2 == 0 { lo.y } else { hi.y },
+                if i & 4 == 0 { lo.z } else { hi.z },
+            );
+            let p = transform.transform_point(corner);
+            min.x = min.x.min(p.x);
+            min.y = min.y.min(p.y);
+            min.z = min.z.min(p.z);
+            max.x = max.x.max(p.x);
+            max.y = max.y.max(p.y);
+            max.z = max.z.max(p.z);
+        }
+        // The sphere is stretched by at most the square root of the largest
+        // row sum of the Gram matrix, which is exact unless there is shear.
+        let linear = Matrix3::from_cols(transform.x.truncate(), transform.y.truncate(), transform.z.truncate());
+        let gram = linear.transpose() * linear;
+        let stretch = [gram.x, gram.y, gram.z]
+            .iter()
+            .map(|row| row.x.abs() + row.y.abs() + row.z.abs())
+            .fold(0.0, |a: f32, b| a.max(b))
+            .sqrt();
+        Bounds {
+            min: min.into(),
+            max: max.into(),
+            center: transform.transform_point(self.center.into()).into(),
+            radius: self.radius * stretch,
+        }
+    }
+}
+
+/// The six clipping planes of a view-projection matrix.
+#[derive(Clone, Debug)]
+pub(crate) struct Frustum {
+    /// Planes as `(normal, distance)`, with normals pointing inwards.
+    planes: [Vector4<f32>; 6],
+}
+
+impl Frustum {
+    /// Extracts the clipping planes of `mx_vp`.
+    pub(crate) fn from_matrix(mx_vp: Matrix4<f32>) -> Self {
+        let r = |i| mx_vp.row(i);
+        let mut planes = [
+            r(3) + r(0),
+            r(3) - r(0),
+            r(3) + r(1),
+            r(3) - r(1),
+            r(3) + r(2),
+            r(3) - r(2),
+        ];
+        for plane in &mut planes {
+            let length = plane.truncate().magnitude();
+            *plane = if length > 1.0e-6 {
+                *plane / length
+            } else {
+                // Degenerate plane, e.g. the far plane of an infinite
+                // perspective projection; accept everything.
+                Vector4::new(0.0, 0.0, 0.0, 1.0)
+            };
+        }
+        Frustum { planes }
+    }
+
+    /// Returns `false` if `bounds`, given in world space, lie entirely outside
+    /// the frustum.
+    pub(crate) fn intersects(
+        &self,
+        bounds: &Bounds,
+    ) -> bool {
+        let center = Point3::from(bounds.center).to_vec();
+        let min = Point3::from(bounds.min).to_vec();
+        let max = Point3::from(bounds.max).to_vec();
+        self.planes.iter().all(|plane| {
+            let normal = plane.truncate();
+            if normal.dot(center) + plane.w < -bounds.radius {
+                return false;
+            }
+            // The corner of the box furthest along the plane normal.
+            let corner = Vector3::new(
+                if normal.x >= 0.0 { max.x } else { min.x },
+                if normal.y >= 0.0 { max.y } else { min.y },
+                if normal.z >= 0.0 { max.z } else { min.z },
+            );
+            normal.dot(corner) + plane.w >= 0.0
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Bounds, Frustum};
+    use cgmath::{self, Deg, InnerSpace, Matrix4};
+
+    /// A cube of the given half-size around `center`.
+    fn cube(
+        center: [f32; 3],
+        half_size: f32,
+    ) -> Bounds {
+        let (c, h) = (center, half_size);
+        Bounds::from_points(&[[c[0] - h, c[1] - h, c[2] - h].into(), [c[0] + h, c[1] + h, c[2] + h].into()]).unwrap()
+    }
+
+    #[test]
+    fn frustum_planes_point_inwards() {
+        let frustum = Frustum::from_matrix(cgmath::ortho(-1.0, 1.0, -1.0, 1.0, 1.0, 10.0));
+        for plane in &frustum.planes {
+            // The center of the view volume is inside every plane.
+            let distance = plane.z * -5.5 + plane.w;
+            assert!(distance > 0.0, "{:?}", plane);
+            assert!((plane.truncate().magnitude() - 1.0).abs() < 1.0e-5);
+        }
+    }
+
+    #[test]
+    fn orthographic_culling() {
+        let frustum = Frustum::from_matrix(cgmath::ortho(-1.0, 1.0, -1.0, 1.0, 1.0, 10.0));
+        assert!(frustum.intersects(&cube([0.0, 0.0, -5.0], 0.5)));
+        // Straddling the right plane.
+        assert!(frustum.intersects(&cube([1.2, 0.0, -5.0], 0.5)));
+        assert!(!frustum.intersects(&cube([3.0, 0.0, -5.0], 0.5)));
+        assert!(!frustum.intersects(&cube([0.0, -3.0, -5.0], 0.5)));
+        // Behind the camera and beyond the far plane.
+        assert!(!frustum.intersects(&cube([0.0, 0.0, 5.0], 0.5)));
+        assert!(!frustum.intersects(&cube([0.0, 0.0, -20.0], 0.5)));
+    }
+
+    #[test]
+    fn perspective_culling() {
+        let projection = cgmath::perspective(Deg(90.0), 1.0, 0.1, 100.0);
+        let view = Matrix4::from_translation([0.0, 0.0, -10.0].into());
+        let frustum = Frustum::from_matrix(projection * view);
+        // The camera sits at +10 on the Z axis, looking at the origin, and
+        // sees 10 units to each side there.
+        assert!(frustum.intersects(&cube([0.0, 0.0, 0.0], 1.0)));
+        assert!(frustum.intersects(&cube([9.0, 0.0, 0.0], 1.0)));
+        assert!(!frustum.intersects(&cube([13.0, 0.0, 0.0], 1.0)));
+        assert!(!frustum.intersects(&cube([0.0, 0.0, 20.0], 1.0)));
+    }
+
+    #[test]
+    fn transformed_bounds() {
+        let bounds = cube([1.0, 0.0, 0.0], 1.0);
+        let transform = Matrix4::from_translation([0.0, 2.0, 0.0].into()) * Matrix4::from_nonuniform_scale(2.0, 1.0, 1.0);
+        let moved = bounds.transformed(&transform);
+        assert_eq!(moved.min, [0.0, 1.0, -1.0].into());
+        assert_eq!(moved.max, [4.0, 3.0, 1.0].into());
+        assert_eq!(moved.center, [2.0, 2.0, 0.0].into());
+        assert!(moved.radius >= bounds.radius * 2.0 - 1.0e-5);
+    }
+}
@@ -51,15 +51,22 @@ pub fn to_linear_rgb(c: Color) -> [f32; 3] {
 /// Linear to sRGB conversion.
 ///
 /// Implementation taken from https://en.wikipedia.org/wiki/SRGB
+///
+/// # Examples
+///
+/// ```rust
+/// assert_eq!(three::color::from_linear_rgb([0.5; 3]), 0xBCBCBC);
+/// assert_eq!(three::color::from_linear_rgb(three::color::to_linear_rgb(0x336699)), 0x336699);
+/// ```
 pub fn from_linear_rgb(c: [f32; 3]) -> Color {
     let f = |x: f32| -> u32 {
         let y = if x > 0.0031308 {
             let a = 0.055;
-            (1.0 + a) * x.powf(-2.4) - a
+            (1.0 + a) * x.powf(1.0 / 2.4) - a
         } else {
             12.92 * x
         };
-        (y * 255.0).round() as u32
+        (y.max(0.0).min(1.0) * 255.0).round() as u32
     };
     f(c[0]) << 16 | f(c[1]) << 8 | f(c[2])
 }
@@ -4,7 +4,6 @@ use mint;
 use object;
 
 use input::{Button, Input, MOUSE_LEFT};
-use node::TransformInternal;
 use object::Object;
 
 /// Simple controls for Orbital Camera.
@@ -15,7 +14,7 @@ use object::Object;
 #[derive(Clone, Debug)]
 pub struct Orbit {
     object: object::Base,
-    transform: TransformInternal,
+    transform: Decomposed<Vector3<f32>, Quaternion<f32>>,
     target: Point3<f32>,
     button: Button,
     speed: f32,
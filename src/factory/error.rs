@@ -0,0 +1,115 @@
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[cfg_attr(rustfmt, rustfmt_skip)]
+quick_error! {
+    #[doc = "Error encountered when loading an asset with one of the `Factory::load_*` functions."]
+    #[doc = ""]
+    #[doc = "Each variant but `Gpu` holds the path of the offending file. The path"]
+    #[doc = "is empty for assets loaded from memory."]
+    #[derive(Debug)]
+    pub enum LoadError {
+        #[doc = "The file could not be opened or read."]
+        Io(path: PathBuf, err: io::Error) {
+            description("I/O error")
+            display("Unable to read {}: {}", path.display(), err)
+            cause(err)
+        }
+
+        #[doc = "The file contents could not be decoded."]
+        Decode(path: PathBuf, message: String) {
+            description("Decoding error")
+            display("Unable to decode {}: {}", path.display(), message)
+        }
+
+        #[doc = "The file uses a format or feature which is not supported."]
+        Unsupported(path: PathBuf, feature: String) {
+            description("Unsupported feature")
+            display("Unable to load {}: unsupported {}", path.display(), feature)
+        }
+
+        #[doc = "The decoded data was rejected by the graphics backend."]
+        Gpu(message: String) {
+            description("GPU error")
+            display("Unable to create GPU resource: {}", message)
+        }
+    }
+}
+
+#[cfg_attr(rustfmt, rustfmt_skip)]
+quick_error! {
+    #[doc = "Error encountered when exporting a scene with one of the `Factory::export_*` functions."]
+    #[derive(Debug)]
+    pub enum ExportError {
+        #[doc = "The file could not be written."]
+        Io(path: PathBuf, err: io::Error) {
+            description("I/O error")
+            display("Unable to write {}: {}", path.display(), err)
+            cause(err)
+        }
+
+        #[doc = "Some of the exported data could not be encoded."]
+        Encode(path: PathBuf, message: String) {
+            description("Encoding error")
+            display("Unable to encode {}: {}", path.display(), message)
+        }
+    }
+}
+
+impl LoadError {
+    /// Returns the path of the file that failed to load, which is empty for
+    /// assets loaded from memory and for `Gpu` errors.
+    pub fn path(&self) -> &Path {
+        match *self {
+            LoadError::Io(ref path, _) |
+            LoadError::Decode(ref path, _) |
+            LoadError::Unsupported(ref path, _) => path,
+            LoadError::Gpu(_) => Path::new(""),
+        }
+    }
+
+    pub(crate) fn io<P: AsRef<Path>>(
+        path: P,
+        err: io::Error,
+    ) -> Self {
+        LoadError::Io(path.as_ref().to_owned(), err)
+    }
+
+    pub(crate) fn decode<P: AsRef<Path>, S: ToString>(
+        path: P,
+        message: S,
+    ) -> Self {
+        LoadError::Decode(path.as_ref().to_owned(), message.to_string())
+    }
+
+    pub(crate) fn unsupported<P: AsRef<Path>, S: Into<String>>(
+        path: P,
+        feature: S,
+    ) -> Self {
+        LoadError::Unsupported(path.as_ref().to_owned(), feature.into())
+    }
+}
+
+impl ExportError {
+    /// Returns the path of the file that failed to export.
+    pub fn path(&self) -> &Path {
+        match *self {
+            ExportError::Io(ref path, _) |
+            ExportError::Encode(ref path, _) => path,
+        }
+    }
+
+    pub(crate) fn io<P: AsRef<Path>>(
+        path: P,
+        err: io::Error,
+    ) -> Self {
+        ExportError::Io(path.as_ref().to_owned(), err)
+    }
+
+    pub(crate) fn encode<P: AsRef<Path>, S: ToString>(
+        path: P,
+        message: S,
+    ) -> Self {
+        ExportError::Encode(path.as_ref().to_owned(), message.to_string())
+    }
+}
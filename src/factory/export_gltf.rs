@@ -0,0 +1,778 @@
+use animation;
+use base64;
+use cgmath;
+use color;
+use image;
+use serde_json;
+use std::{f32, fs};
+
+use animation::{Binding, Interpolation, Values};
+use color::Color;
+use hub::{Hub, SubNode};
+use material::{AlphaMode, Material, Pbr};
+use node::NodePointer;
+use object::Object;
+use render::CpuData;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use texture::{FilterMethod, Texture, WrapMode};
+
+use super::ExportError;
+use super::load_gltf::{GLB_CHUNK_BIN, GLB_CHUNK_JSON, GLB_MAGIC};
+
+const ARRAY_BUFFER: u32 = 34_962;
+const ELEMENT_ARRAY_BUFFER: u32 = 34_963;
+const FLOAT: u32 = 5126;
+const UNSIGNED_INT: u32 = 5125;
+const LINE_STRIP: u32 = 3;
+
+const NEAREST: u32 = 9728;
+const LINEAR: u32 = 9729;
+const NEAREST_MIPMAP_LINEAR: u32 = 9986;
+const LINEAR_MIPMAP_LINEAR: u32 = 9987;
+const CLAMP_TO_EDGE: u32 = 33_071;
+const MIRRORED_REPEAT: u32 = 33_648;
+const REPEAT: u32 = 10_497;
+
+const MATERIALS_UNLIT: &str = "KHR_materials_unlit";
+const TEXTURE_TRANSFORM: &str = "KHR_texture_transform";
+
+impl super::Factory {
+    /// Exports `root` and its descendants to glTF 2.0, along with the tracks
+    /// of `clips` that animate the exported objects.
+    ///
+    /// A path with the `.glb` extension produces a binary container, any
+    /// other path a JSON document with its buffer embedded as a base64
+    /// `data:` URI. Textures are embedded as PNG images, which are read
+    /// again from the files textures were loaded from; other textures are
+    /// skipped unless their pixels are kept, see
+    /// [`set_keep_texture_pixels`](#method.set_keep_texture_pixels).
+    ///
+    /// Node transforms and the geometry of meshes are exported along with
+    /// their materials; `Pbr` materials are exported as they are, whereas
+    /// the other materials are approximated. If `root` is a [`Scene`], its
+    /// children become the root nodes of the exported scene.
+    ///
+    /// Lights, cameras, skins, morph targets and sprites are not exported.
+    /// Dynamic meshes are exported with the vertices last set by
+    /// [`mix`](#method.mix) or [`map_vertices`](#method.map_vertices).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # let mut window = three::Window::new("");
+    /// use three::Object;
+    ///
+    /// let mut group = window.factory.group();
+    /// let geometry = three::Geometry::uv_sphere(1.0, 32, 16);
+    /// let material = three::material::Phong { color: three::color::RED, glossiness: 30.0 };
+    /// let mut sphere = window.factory.mesh(geometry, material);
+    /// sphere.set_parent(&group);
+    /// window.factory.export_gltf(&group, &[], "sphere.glb").unwrap();
+    /// ```
+    ///
+    /// [`Scene`]: struct.Scene.html
+    pub fn export_gltf<T: Object, P: AsRef<Path>>(
+        &self,
+        root: &T,
+        clips: &[animation::Clip],
+        path: P,
+    ) -> Result<(), ExportError> {
+        let path = path.as_ref();
+        let binary = path.extension()
+            .map_or(false, |extension| extension.to_string_lossy().to_lowercase() == "glb");
+        let mut exporter = Exporter::new(path);
+        {
+            let mut hub = self.hub.lock().unwrap();
+            hub.process_messages();
+            exporter.export_scene(&hub, &root.as_ref().node)?;
+        }
+        exporter.export_clips(clips);
+        let data = if binary {
+            exporter.into_glb()?
+        } else {
+            exporter.into_gltf()?
+        };
+        let mut file = fs::File::create(path).map_err(|e| ExportError::io(path, e))?;
+        file.write_all(&data).map_err(|e| ExportError::io(path, e))
+    }
+}
+
+/// Accumulates the glTF document and binary buffer of an export.
+struct Exporter<'a> {
+    path: &'a Path,
+    bin: Vec<u8>,
+    buffer_views: Vec<Value>,
+    accessors: Vec<Value>,
+    nodes: Vec<Value>,
+    meshes: Vec<Value>,
+    materials: Vec<Value>,
+    textures: Vec<Value>,
+    images: Vec<Value>,
+    samplers: Vec<Value>,
+    animations: Vec<Value>,
+    roots: Vec<usize>,
+    extensions_used: Vec<&'static str>,
+    /// glTF node indices by exported node.
+    node_indices: HashMap<NodePointer, usize>,
+    /// glTF mesh indices by address of the geometry and material index, as
+    /// mesh instances share their geometry.
+    mesh_indices: HashMap<(usize, usize), usize>,
+    /// The exported materials, in glTF material index order.
+    material_keys: Vec<Material>,
+    /// glTF image indices by address of the pixels.
+    image_indices: HashMap<usize, usize>,
+    /// Images read again from the files textures were loaded from.
+    file_images: HashMap<PathBuf, Arc<image::RgbaImage>>,
+}
+
+impl<'a> Exporter<'a> {
+    fn new(path: &'a Path) -> Self {
+        Exporter {
+            path,
+            bin: Vec::new(),
+            buffer_views: Vec::new(),
+            accessors: Vec::new(),
+            nodes: Vec::new(),
+            meshes: Vec::new(),
+            materials: Vec::new(),
+            textures: Vec::new(),
+            images: Vec::new(),
+            samplers: Vec::new(),
+            animations: Vec::new(),
+            roots: Vec::new(),
+            extensions_used: Vec::new(),
+            node_indices: HashMap::new(),
+            mesh_indices: HashMap::new(),
+            material_keys: Vec::new(),
+            image_indices: HashMap::new(),
+            file_images: HashMap::new(),
+        }
+    }
+
+    fn use_extension(
+        &mut self,
+        name: &'static str,
+    ) {
+        if !self.extensions_used.contains(&name) {
+            self.extensions_used.push(name);
+        }
+    }
+
+    /// Appends `data` to the binary buffer as a new buffer view.
+    fn export_buffer_view(
+        &mut self,
+        data: &[u8],
+        target: Option<u32>,
+    ) -> usize {
+        while self.bin.len() % 4 != 0 {
+            self.bin.push(0);
+        }
+        let mut view = json!({
+            "buffer": 0,
+            "byteOffset": self.bin.len(),
+            "byteLength": data.len(),
+        });
+        if let Some(target) = target {
+            view["target"] = target.into();
+        }
+        self.bin.extend_from_slice(data);
+        self.buffer_views.push(view);
+        self.buffer_views.len() - 1
+    }
+
+    /// Exports floating point data of the given accessor type, such as
+    /// `"VEC3"`, with its bounds if `bounds` is set.
+    fn export_accessor(
+        &mut self,
+        data: &[f32],
+        kind: &str,
+        target: Option<u32>,
+        bounds: bool,
+    ) -> usize {
+        let components = match kind {
+            "SCALAR" => 1,
+            "VEC2" => 2,
+            "VEC3" => 3,
+            _ => 4,
+        };
+        let mut bytes = Vec::with_capacity(4 * data.len());
+        for &x in data {
+            push_u32(&mut bytes, x.to_bits());
+        }
+        let view = self.export_buffer_view(&bytes, target);
+        let mut accessor = json!({
+            "bufferView": view,
+            "componentType": FLOAT,
+            "count": data.len() / components,
+            "type": kind,
+        });
+        if bounds {
+            let mut min = vec![f32::INFINITY; components];
+            let mut max = vec![f32::NEG_INFINITY; components];
+            for element in data.chunks(components) {
+                for (i, &x) in element.iter().enumerate() {
+                    min[i] = min[i].min(x);
+                    max[i] = max[i].max(x);
+                }
+            }
+            accessor["min"] = json!(min);
+            accessor["max"] = json!(max);
+        }
+        self.accessors.push(accessor);
+        self.accessors.len() - 1
+    }
+
+    fn export_indices(
+        &mut self,
+        faces: &[[u32; 3]],
+    ) -> usize {
+        let mut bytes = Vec::with_capacity(12 * faces.len());
+        for face in faces {
+            for &index in face {
+                push_u32(&mut bytes, index);
+            }
+        }
+        let view = self.export_buffer_view(&bytes, Some(ELEMENT_ARRAY_BUFFER));
+        self.accessors.push(json!({
+            "bufferView": view,
+            "componentType": UNSIGNED_INT,
+            "count": 3 * faces.len(),
+            "type": "SCALAR",
+        }));
+        self.accessors.len() - 1
+    }
+
+    /// Exports the node tree below `root`.
+    fn export_scene(
+        &mut self,
+        hub: &Hub,
+        root: &NodePointer,
+    ) -> Result<(), ExportError> {
+        let children = hub.children();
+        self.roots = match hub.nodes[root].sub_node {
+            SubNode::Scene => {
+                let mut roots = Vec::new();
+                for child in children.get(root).into_iter().flat_map(|c| c.iter()) {
+                    roots.push(self.export_node(hub, &children, child)?);
+                }
+                roots
+            }
+            _ => vec![self.export_node(hub, &children, root)?],
+        };
+        Ok(())
+    }
+
+    fn export_node(
+        &mut self,
+        hub: &Hub,
+        children: &HashMap<NodePointer, Vec<NodePointer>>,
+        pointer: &NodePointer,
+    ) -> Result<usize, ExportError> {
+        let node = &hub.nodes[pointer];
+        let index = self.nodes.len();
+        self.nodes.push(Value::Null);
+        self.node_indices.insert(pointer.clone(), index);
+
+        let t = node.transform;
+        let mut json = json!({
+            "translation": [t.disp.x, t.disp.y, t.disp.z],
+            "rotation": [t.rot.v.x, t.rot.v.y, t.rot.v.z, t.rot.s],
+            "scale": [t.scale.x, t.scale.y, t.scale.z],
+        });
+        match node.sub_node {
+            SubNode::Visual(Material::Sprite(_), _) => {}
+            SubNode::Visual(ref material, ref gpu_data) => if let Some(ref data) = gpu_data.cpu_data {
+                if let Some(mesh) = self.export_mesh(data, material)? {
+                    json["mesh"] = mesh.into();
+                }
+            },
+            _ => {}
+        }
+        let mut child_indices = Vec::new();
+        for child in children.get(pointer).into_iter().flat_map(|c| c.iter()) {
+            child_indices.push(self.export_node(hub, children, child)?);
+        }
+        if !child_indices.is_empty() {
+            json["children"] = json!(child_indices);
+        }
+
+        self.nodes[index] = json;
+        Ok(index)
+    }
+
+    /// Exports the geometry of a mesh with one primitive, unless the
+    /// geometry is empty.
+    fn export_mesh(
+        &mut self,
+        data: &Arc<CpuData>,
+        material: &Material,
+    ) -> Result<Option<usize>, ExportError> {
+        let shape = &data.shape;
+        let count = shape.vertices.len();
+        if count == 0 {
+            return Ok(None);
+        }
+        let material_index = self.export_material(material)?;
+        let key = (&**data as *const CpuData as usize, material_index);
+        if let Some(&index) = self.mesh_indices.get(&key) {
+            return Ok(Some(index));
+        }
+
+        let mut attributes = Map::new();
+        let positions: Vec<f32> = shape.vertices.iter().flat_map(|v| vec![v.x, v.y, v.z]).collect();
+        let accessor = self.export_accessor(&positions, "VEC3", Some(ARRAY_BUFFER), true);
+        attributes.insert("POSITION".into(), accessor.into());
+        if shape.normals.len() == count {
+            let normals: Vec<f32> = shape.normals.iter().flat_map(|n| vec![n.x, n.y, n.z]).collect();
+            let accessor = self.export_accessor(&normals, "VEC3", Some(ARRAY_BUFFER), false);
+            attributes.insert("NORMAL".into(), accessor.into());
+        }
+        if shape.tangents.len() == count {
+            let tangents: Vec<f32> = shape.tangents.iter().flat_map(|t| vec![t.x, t.y, t.z, t.w]).collect();
+            let accessor = self.export_accessor(&tangents, "VEC4", Some(ARRAY_BUFFER), false);
+            attributes.insert("TANGENT".into(), accessor.into());
+        }
+        if shape.tex_coords.len() == count {
+            let tex_coords: Vec<f32> = shape.tex_coords.iter().flat_map(|uv| vec![uv.x, uv.y]).collect();
+            let accessor = self.export_accessor(&tex_coords, "VEC2", Some(ARRAY_BUFFER), false);
+            attributes.insert("TEXCOORD_0".into(), accessor.into());
+        }
+        if shape.tex_coords1.len() == count {
+            let tex_coords: Vec<f32> = shape.tex_coords1.iter().flat_map(|uv| vec![uv.x, uv.y]).collect();
+            let accessor = self.export_accessor(&tex_coords, "VEC2", Some(ARRAY_BUFFER), false);
+            attributes.insert("TEXCOORD_1".into(), accessor.into());
+        }
+        if shape.colors.len() == count {
+            let colors: Vec<f32> = shape.colors.iter().flat_map(|c| vec![c.x, c.y, c.z, c.w]).collect();
+            let accessor = self.export_accessor(&colors, "VEC4", Some(ARRAY_BUFFER), false);
+            attributes.insert("COLOR_0".into(), accessor.into());
+        }
+
+        let mut primitive = json!({
+            "attributes": attributes,
+            "material": material_index,
+        });
+        if !data.faces.is_empty() {
+            primitive["indices"] = self.export_indices(&data.faces).into();
+        }
+        if let Material::Line(_) = *material {
+            primitive["mode"] = LINE_STRIP.into();
+        }
+        self.meshes.push(json!({ "primitives": [primitive] }));
+        self.mesh_indices.insert(key, self.meshes.len() - 1);
+        Ok(Some(self.meshes.len() - 1))
+    }
+
+    /// Exports a material, approximating the non-PBR materials by the
+    /// metallic-roughness model.
+    fn export_material(
+        &mut self,
+        material: &Material,
+    ) -> Result<usize, ExportError> {
+        if let Some(index) = self.material_keys.iter().position(|m| m == material) {
+            return Ok(index);
+        }
+        let json = match *material {
+            Material::Pbr(ref params) => self.export_pbr(params)?,
+            Material::Basic(ref params) => self.export_unlit(params.color, params.map.as_ref())?,
+            Material::CustomBasic(ref params) => self.export_unlit(params.color, params.map.as_ref())?,
+            Material::Line(ref params) => self.export_unlit(params.color, None)?,
+            Material::Wireframe(ref params) => self.export_unlit(params.color, None)?,
+            Material::Sprite(ref params) => self.export_unlit(color::WHITE, Some(&params.map))?,
+            Material::Lambert(ref params) => dielectric(params.color, 1.0),
+            // Blinn-Phong exponent to GGX roughness.
+            Material::Phong(ref params) => dielectric(params.color, (2.0 / (params.glossiness + 2.0)).sqrt().min(1.0)),
+        };
+        self.material_keys.push(material.clone());
+        self.materials.push(json);
+        Ok(self.materials.len() - 1)
+    }
+
+    fn export_pbr(
+        &mut self,
+        params: &Pbr,
+    ) -> Result<Value, ExportError> {
+        let base_color = color::to_linear_rgb(params.base_color_factor);
+        let mut pbr = Map::new();
+        pbr.insert(
+            "baseColorFactor".into(),
+            json!([base_color[0], base_color[1], base_color[2], params.base_color_alpha]),
+        );
+        pbr.insert("metallicFactor".into(), params.metallic_factor.into());
+        pbr.insert("roughnessFactor".into(), params.roughness_factor.into());
+        if let Some(ref map) = params.base_color_map {
+            if let Some(info) = self.export_texture(map, params.uv_sets.base_color)? {
+                pbr.insert("baseColorTexture".into(), Value::Object(info));
+            }
+        }
+        if let Some(ref map) = params.metallic_roughness_map {
+            if let Some(info) = self.export_texture(map, params.uv_sets.metallic_roughness)? {
+                pbr.insert("metallicRoughnessTexture".into(), Value::Object(info));
+            }
+        }
+
+        let mut json = Map::new();
+        json.insert("pbrMetallicRoughness".into(), Value::Object(pbr));
+        if let Some(ref map) = params.normal_map {
+            if let Some(mut info) = self.export_texture(map, params.uv_sets.normal)? {
+                info.insert("scale".into(), params.normal_scale.into());
+                json.insert("normalTexture".into(), Value::Object(info));
+            }
+        }
+        if let Some(ref map) = params.occlusion_map {
+            if let Some(mut info) = self.export_texture(map, params.uv_sets.occlusion)? {
+                info.insert("strength".into(), params.occlusion_strength.into());
+                json.insert("occlusionTexture".into(), Value::Object(info));
+            }
+        }
+        if let Some(ref map) = params.emissive_map {
+            if let Some(info) = self.export_texture(map, params.uv_sets.emissive)? {
+                json.insert("emissiveTexture".into(), Value::Object(info));
+            }
+        }
+        json.insert("emissiveFactor".into(), json!(color::to_linear_rgb(params.emissive_factor)));
+        let alpha_mode = match params.alpha_mode {
+            AlphaMode::Opaque => "OPAQUE",
+            AlphaMode::Mask => "MASK",
+            AlphaMode::Blend => "BLEND",
+        };
+        json.insert("alphaMode".into(), alpha_mode.into());
+        if params.alpha_mode == AlphaMode::Mask {
+            json.insert("alphaCutoff".into(), params.alpha_cutoff.into());
+        }
+        json.insert("doubleSided".into(), params.double_sided.into());
+        Ok(Value::Object(json))
+    }
+
+    /// Exports a material which is not affected by lighting.
+    fn export_unlit(
+        &mut self,
+        color: Color,
+        map: Option<&Texture<[f32; 4]>>,
+    ) -> Result<Value, ExportError> {
+        self.use_extension(MATERIALS_UNLIT);
+        let mut json = dielectric(color, 1.0);
+        if let Some(map) = map {
+            if let Some(info) = self.export_texture(map, 0)? {
+                json["pbrMetallicRoughness"]["baseColorTexture"] = Value::Object(info);
+            }
+        }
+        let mut extensions = Map::new();
+        extensions.insert(MATERIALS_UNLIT.into(), json!({}));
+        json["extensions"] = Value::Object(extensions);
+        Ok(json)
+    }
+
+    /// Returns the pixels of a texture, reading them again from the file
+    /// the texture was loaded from unless they are kept on the CPU.
+    fn texture_pixels(
+        &mut self,
+        texture: &Texture<[f32; 4]>,
+    ) -> Option<Arc<image::RgbaImage>> {
+        if let Some(pixels) = texture.pixels() {
+            return Some(pixels.clone());
+        }
+        let path = match texture.path() {
+            Some(path) => path,
+            None => {
+                warn!("Skipping a texture whose pixels are not kept on the CPU");
+                return None;
+            }
+        };
+        if let Some(pixels) = self.file_images.get(path) {
+            return Some(pixels.clone());
+        }
+        match super::decode_texture(path) {
+            Ok(image) => {
+                let pixels = Arc::new(image);
+                self.file_images.insert(path.to_owned(), pixels.clone());
+                Some(pixels)
+            }
+            Err(e) => {
+                warn!("Skipping a texture that could not be read again: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Exports a texture, returning its texture info, unless the pixels of
+    /// the texture are not available.
+    fn export_texture(
+        &mut self,
+        texture: &Texture<[f32; 4]>,
+        tex_coord: u32,
+    ) -> Result<Option<Map<String, Value>>, ExportError> {
+        let pixels = match self.texture_pixels(texture) {
+            Some(pixels) => pixels,
+            None => return Ok(None),
+        };
+        let key = &*pixels as *const image::RgbaImage as usize;
+        let image = match self.image_indices.get(&key).cloned() {
+            Some(index) => index,
+            None => {
+                let mut png = Vec::new();
+                {
+                    let data: &[u8] = &pixels;
+                    image::png::PNGEncoder::new(&mut png)
+                        .encode(data, pixels.width(), pixels.height(), image::ColorType::RGBA(8))
+                        .map_err(|e| ExportError::encode(self.path, format!("PNG image: {}", e)))?;
+                }
+                let view = self.export_buffer_view(&png, None);
+                self.images.push(json!({
+                    "bufferView": view,
+                    "mimeType": "image/png",
+                }));
+                self.image_indices.insert(key, self.images.len() - 1);
+                self.images.len() - 1
+            }
+        };
+
+        let info = texture.sampler_info();
+        let (mag_filter, min_filter) = match info.filter {
+            FilterMethod::Scale => (NEAREST, Some(NEAREST)),
+            FilterMethod::Mipmap => (NEAREST, Some(NEAREST_MIPMAP_LINEAR)),
+            FilterMethod::Bilinear => (LINEAR, Some(LINEAR)),
+            FilterMethod::Trilinear => (LINEAR, Some(LINEAR_MIPMAP_LINEAR)),
+            // glTF has no anisotropic filtering, which the loader picks for
+            // textures without a min filter.
+            FilterMethod::Anisotropic(_) => (LINEAR, None),
+        };
+        let wrap = |mode| match mode {
+            WrapMode::Tile => REPEAT,
+            WrapMode::Mirror => MIRRORED_REPEAT,
+            WrapMode::Clamp | WrapMode::Border => CLAMP_TO_EDGE,
+        };
+        let mut sampler = json!({
+            "magFilter": mag_filter,
+            "wrapS": wrap(info.wrap_mode.0),
+            "wrapT": wrap(info.wrap_mode.1),
+        });
+        if let Some(min_filter) = min_filter {
+            sampler["minFilter"] = min_filter.into();
+        }
+        let sampler = find_or_push(&mut self.samplers, sampler);
+        let index = find_or_push(&mut self.textures, json!({ "source": image, "sampler": sampler }));
+
+        let mut info = Map::new();
+        info.insert("index".into(), index.into());
+        if tex_coord != 0 {
+            info.insert("texCoord".into(), tex_coord.into());
+        }
+        let (offset, rotation, scale) = texture.uv_transform();
+        if offset != [0.0; 2] || rotation != 0.0 || scale != [1.0; 2] {
+            self.use_extension(TEXTURE_TRANSFORM);
+            let mut extensions = Map::new();
+            extensions.insert(
+                TEXTURE_TRANSFORM.into(),
+                json!({
+                    "offset": offset,
+                    "rotation": rotation,
+                    "scale": scale,
+                }),
+            );
+            info.insert("extensions".into(), Value::Object(extensions));
+        }
+        Ok(Some(info))
+    }
+
+    /// Exports the tracks of `clips` animating the exported nodes.
+    ///
+    /// Tracks which glTF cannot express, such as color tracks, are skipped.
+    fn export_clips(
+        &mut self,
+        clips: &[animation::Clip],
+    ) {
+        for clip in clips {
+            let mut channels = Vec::new();
+            let mut samplers = Vec::new();
+            for &(ref track, ref target) in &clip.tracks {
+                let node = match self.node_indices.get(&target.node) {
+                    Some(&node) => node,
+                    None => continue,
+                };
+                let (path, kind, values): (_, _, Vec<f32>) = match (track.binding, &track.values) {
+                    (Binding::Position, &Values::Vector3(ref values)) => {
+                        ("translation", "VEC3", values.iter().flat_map(|v| vec![v.x, v.y, v.z]).collect())
+                    }
+                    (Binding::Orientation, &Values::Quaternion(ref values)) => {
+                        ("rotation", "VEC4", values.iter().flat_map(|q| vec![q.v.x, q.v.y, q.v.z, q.s]).collect())
+                    }
+                    (Binding::Orientation, &Values::Euler(ref values)) => {
+                        let values = values.iter().flat_map(|e| {
+                            let q = cgmath::Quaternion::from(cgmath::Euler::new(
+                                cgmath::Rad(e.a),
+                                cgmath::Rad(e.b),
+                                cgmath::Rad(e.c),
+                            ));
+                            vec![q.v.x, q.v.y, q.v.z, q.s]
+                        });
+                        ("rotation", "VEC4", values.collect())
+                    }
+                    (Binding::Scale, &Values::Vector3(ref values)) => {
+                        ("scale", "VEC3", values.iter().flat_map(|v| vec![v.x, v.y, v.z]).collect())
+                    }
+                    (Binding::Scale, &Values::Scalar(ref values)) => {
+                        ("scale", "VEC3", values.iter().flat_map(|&s| vec![s, s, s]).collect())
+                    }
+                    (binding, _) => {
+                        warn!("Skipping {:?} track, which glTF cannot express", binding);
+                        continue;
+                    }
+                };
+                let interpolation = match track.interpolation {
+                    Interpolation::Discrete => "STEP",
+                    Interpolation::Linear => "LINEAR",
+                    Interpolation::Cubic => "CUBICSPLINE",
+                    other => {
+                        warn!("Approximating {:?} interpolation by linear interpolation", other);
+                        "LINEAR"
+                    }
+                };
+                let input = self.export_accessor(&track.times, "SCALAR", None, true);
+                let output = self.export_accessor(&values, kind, None, false);
+                channels.push(json!({
+                    "sampler": samplers.len(),
+                    "target": { "node": node, "path": path },
+                }));
+                samplers.push(json!({
+                    "input": input,
+                    "output": output,
+                    "interpolation": interpolation,
+                }));
+            }
+            if channels.is_empty() {
+                warn!("Skipping animation clip without exportable tracks");
+                continue;
+            }
+            let mut animation = json!({
+                "channels": channels,
+                "samplers": samplers,
+            });
+            if let Some(ref name) = clip.name {
+                animation["name"] = name.as_str().into();
+            }
+            self.animations.push(animation);
+        }
+    }
+
+    /// Assembles the glTF document, returning it along with the binary
+    /// buffer.
+    ///
+    /// The buffer is embedded as a `data:` URI unless `binary` is set.
+    fn into_document(
+        self,
+        binary: bool,
+    ) -> (Value, Vec<u8>) {
+        let mut root = Map::new();
+        root.insert(
+            "asset".into(),
+            json!({
+                "version": "2.0",
+                "generator": "three-rs",
+            }),
+        );
+        root.insert("scene".into(), 0.into());
+        root.insert("scenes".into(), json!([{ "nodes": self.roots }]));
+        if !self.bin.is_empty() {
+            let mut buffer = json!({ "byteLength": self.bin.len() });
+            if !binary {
+                let uri = format!("data:application/octet-stream;base64,{}", base64::encode(&self.bin));
+                buffer["uri"] = uri.into();
+            }
+            root.insert("buffers".into(), json!([buffer]));
+        }
+        let arrays = vec![
+            ("bufferViews", self.buffer_views),
+            ("accessors", self.accessors),
+            ("nodes", self.nodes),
+            ("meshes", self.meshes),
+            ("materials", self.materials),
+            ("textures", self.textures),
+            ("images", self.images),
+            ("samplers", self.samplers),
+            ("animations", self.animations),
+        ];
+        for (key, array) in arrays {
+            if !array.is_empty() {
+                root.insert(key.into(), Value::Array(array));
+            }
+        }
+        if !self.extensions_used.is_empty() {
+            root.insert("extensionsUsed".into(), json!(self.extensions_used));
+        }
+        (Value::Object(root), self.bin)
+    }
+
+    fn into_gltf(self) -> Result<Vec<u8>, ExportError> {
+        let path = self.path;
+        let (document, _) = self.into_document(false);
+        serde_json::to_vec_pretty(&document).map_err(|e| ExportError::encode(path, e))
+    }
+
+    fn into_glb(self) -> Result<Vec<u8>, ExportError> {
+        let path = self.path;
+        let (document, mut bin) = self.into_document(true);
+        let mut json = serde_json::to_vec(&document).map_err(|e| ExportError::encode(path, e))?;
+        // Chunks are aligned to four bytes.
+        while json.len() % 4 != 0 {
+            json.push(b' ');
+        }
+        while bin.len() % 4 != 0 {
+            bin.push(0);
+        }
+        let length = 12 + 8 + json.len() + if bin.is_empty() { 0 } else { 8 + bin.len() };
+        let mut glb = Vec::with_capacity(length);
+        glb.extend_from_slice(GLB_MAGIC);
+        push_u32(&mut glb, 2);
+        push_u32(&mut glb, length as u32);
+        push_u32(&mut glb, json.len() as u32);
+        push_u32(&mut glb, GLB_CHUNK_JSON);
+        glb.extend_from_slice(&json);
+        if !bin.is_empty() {
+            push_u32(&mut glb, bin.len() as u32);
+            push_u32(&mut glb, GLB_CHUNK_BIN);
+            glb.extend_from_slice(&bin);
+        }
+        Ok(glb)
+    }
+}
+
+/// Returns a metallic-roughness material of the given color without metal.
+fn dielectric(
+    color: Color,
+    roughness: f32,
+) -> Value {
+    let rgb = color::to_linear_rgb(color);
+    json!({
+        "pbrMetallicRoughness": {
+            "baseColorFactor": [rgb[0], rgb[1], rgb[2], 1.0],
+            "metallicFactor": 0.0,
+            "roughnessFactor": roughness,
+        },
+    })
+}
+
+/// Returns the index of `value` in `array`, appending it if necessary.
+fn find_or_push(
+    array: &mut Vec<Value>,
+    value: Value,
+) -> usize {
+    match array.iter().position(|v| *v == value) {
+        Some(index) => index,
+        None => {
+            array.push(value);
+            array.len() - 1
+        }
+    }
+}
+
+fn push_u32(
+    bytes: &mut Vec<u8>,
+    value: u32,
+) {
+    bytes.extend_from_slice(&[value as u8, (value >> 8) as u8, (value >> 16) as u8, (value >> 24) as u8]);
+}
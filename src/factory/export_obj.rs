@@ -0,0 +1,331 @@
+use cgmath;
+use image;
+use std::fs;
+
+use color::Color;
+use geometry::{Geometry, ObjOffsets, Shape};
+use hub::{Hub, SubNode};
+use material::Material;
+use node::NodePointer;
+use object::Object;
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use texture::Texture;
+
+use super::ExportError;
+
+impl super::Factory {
+    /// Exports the meshes of `root` and its descendants in Wavefront OBJ
+    /// format, along with an MTL file of the same name describing their
+    /// materials.
+    ///
+    /// Every mesh is written as its own object and group, with its world
+    /// transform baked into its vertices. Material colors are written as
+    /// they are, `Phong` glossiness as the specular exponent, `Pbr` factors
+    /// with the `Pr` and `Pm` keys of the PBR extension, and textures
+    /// as `map_Kd` paths. Textures that were not loaded from a file are
+    /// written as PNG images next to the MTL file if their pixels are kept,
+    /// see [`set_keep_texture_pixels`](#method.set_keep_texture_pixels).
+    ///
+    /// Sprites are not exported, and neither are the tangents, vertex
+    /// colors and second texture co-ordinates of meshes. As with
+    /// [`export_gltf`](#method.export_gltf), dynamic meshes are exported
+    /// with their current vertices.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # let mut window = three::Window::new("");
+    /// use three::Object;
+    ///
+    /// let mut group = window.factory.group();
+    /// let geometry = three::Geometry::cylinder(1.0, 1.0, 2.0, 16);
+    /// let material = three::material::Lambert { color: three::color::BLUE, flat: false };
+    /// let mut pillar = window.factory.mesh(geometry, material);
+    /// pillar.set_parent(&group);
+    /// pillar.set_position([0.0, 1.0, 0.0]);
+    /// window.factory.export_obj(&group, "pillar.obj").unwrap();
+    /// ```
+    pub fn export_obj<T: Object, P: AsRef<Path>>(
+        &self,
+        root: &T,
+        path: P,
+    ) -> Result<(), ExportError> {
+        let path = path.as_ref();
+        let mut exporter = ObjExporter::new(path);
+        {
+            let mut hub = self.hub.lock().unwrap();
+            hub.process_messages();
+            exporter
+                .export_scene(&hub, &root.as_ref().node)
+                .map_err(|e| ExportError::io(path, e))?;
+        }
+        exporter.finish()
+    }
+}
+
+/// Accumulates the OBJ and MTL files of an export.
+struct ObjExporter<'a> {
+    path: &'a Path,
+    mtl_path: PathBuf,
+    obj: Vec<u8>,
+    mtl: Vec<u8>,
+    offsets: ObjOffsets,
+    mesh_count: usize,
+    /// The exported materials, in MTL order.
+    materials: Vec<Material>,
+    /// Images to write next to the MTL file, with the address of their
+    /// pixels.
+    images: Vec<(usize, PathBuf, Arc<image::RgbaImage>)>,
+}
+
+impl<'a> ObjExporter<'a> {
+    fn new(path: &'a Path) -> Self {
+        let mtl_path = path.with_extension("mtl");
+        let mut obj = Vec::new();
+        if let Some(name) = mtl_path.file_name() {
+            // Writing to memory cannot fail.
+            let _ = writeln!(obj, "mtllib {}", name.to_string_lossy());
+        }
+        ObjExporter {
+            path,
+            mtl_path,
+            obj,
+            mtl: Vec::new(),
+            offsets: ObjOffsets::default(),
+            mesh_count: 0,
+            materials: Vec::new(),
+            images: Vec::new(),
+        }
+    }
+
+    fn export_scene(
+        &mut self,
+        hub: &Hub,
+        root: &NodePointer,
+    ) -> io::Result<()> {
+        let children = hub.children();
+        let transform = world_transform(hub, root);
+        self.export_node(hub, &children, root, &transform)
+    }
+
+    fn export_node(
+        &mut self,
+        hub: &Hub,
+        children: &HashMap<NodePointer, Vec<NodePointer>>,
+        pointer: &NodePointer,
+        transform: &cgmath::Matrix4<f32>,
+    ) -> io::Result<()> {
+        match hub.nodes[pointer].sub_node {
+            SubNode::Visual(Material::Sprite(_), _) => {}
+            SubNode::Visual(ref material, ref gpu_data) => if let Some(ref data) = gpu_data.cpu_data {
+                if !data.shape.vertices.is_empty() {
+                    self.export_mesh(&data.shape, &data.faces, material, transform)?;
+                }
+            },
+            _ => {}
+        }
+        for child in children.get(pointer).into_iter().flat_map(|c| c.iter()) {
+            let child_transform = transform * cgmath::Matrix4::from(hub.nodes[child].transform);
+            self.export_node(hub, children, child, &child_transform)?;
+        }
+        Ok(())
+    }
+
+    fn export_mesh(
+        &mut self,
+        shape: &Shape,
+        faces: &[[u32; 3]],
+        material: &Material,
+        transform: &cgmath::Matrix4<f32>,
+    ) -> io::Result<()> {
+        let material_index = self.export_material(material)?;
+        let name = format!("mesh{}", self.mesh_count);
+        self.mesh_count += 1;
+        writeln!(self.obj, "o {}", name)?;
+        writeln!(self.obj, "g {}", name)?;
+        writeln!(self.obj, "usemtl material{}", material_index)?;
+
+        // Mirroring transforms also flip the winding of the faces.
+        let mut geometry = Geometry {
+            base_shape: Shape {
+                vertices: shape.vertices.clone(),
+                normals: shape.normals.clone(),
+                tex_coords: shape.tex_coords.clone(),
+                ..Shape::empty()
+            },
+            faces: faces.to_vec(),
+            ..Geometry::empty()
+        };
+        geometry.transform(*transform);
+        let world_shape = &geometry.base_shape;
+
+        if let Material::Line(_) = *material {
+            for v in &world_shape.vertices {
+                writeln!(self.obj, "v {} {} {}", v.x, v.y, v.z)?;
+            }
+            let first = self.offsets.vertices + 1;
+            let indices: Vec<String> = (first .. first + world_shape.vertices.len())
+                .map(|i| i.to_string())
+                .collect();
+            writeln!(self.obj, "l {}", indices.join(" "))?;
+            self.offsets.vertices += world_shape.vertices.len();
+            Ok(())
+        } else {
+            world_shape.write_obj(&mut self.obj, &geometry.faces, &mut self.offsets)
+        }
+    }
+
+    /// Writes a material to the MTL file unless it was already written,
+    /// returning its index.
+    fn export_material(
+        &mut self,
+        material: &Material,
+    ) -> io::Result<usize> {
+        if let Some(index) = self.materials.iter().position(|m| m == material) {
+            return Ok(index);
+        }
+        let index = self.materials.len();
+        self.materials.push(material.clone());
+
+        writeln!(self.mtl, "newmtl material{}", index)?;
+        let (color, map) = match *material {
+            Material::Basic(ref params) => {
+                writeln!(self.mtl, "illum 0")?;
+                (params.color, params.map.as_ref())
+            }
+            Material::CustomBasic(ref params) => {
+                writeln!(self.mtl, "illum 0")?;
+                (params.color, params.map.as_ref())
+            }
+            Material::Line(ref params) => {
+                writeln!(self.mtl, "illum 0")?;
+                (params.color, None)
+            }
+            Material::Wireframe(ref params) => {
+                writeln!(self.mtl, "illum 0")?;
+                (params.color, None)
+            }
+            Material::Sprite(ref params) => {
+                writeln!(self.mtl, "illum 0")?;
+                (0xFFFFFF, Some(&params.map))
+            }
+            Material::Lambert(ref params) => {
+                writeln!(self.mtl, "illum 1")?;
+                (params.color, None)
+            }
+            Material::Phong(ref params) => {
+                writeln!(self.mtl, "illum 2")?;
+                writeln!(self.mtl, "Ks 1 1 1")?;
+                writeln!(self.mtl, "Ns {}", params.glossiness)?;
+                (params.color, None)
+            }
+            Material::Pbr(ref params) => {
+                // GGX roughness to Blinn-Phong exponent, for readers unaware
+                // of the PBR extension.
+                let roughness = params.roughness_factor.max(0.01);
+                writeln!(self.mtl, "illum 2")?;
+                writeln!(self.mtl, "Ks 1 1 1")?;
+                writeln!(self.mtl, "Ns {}", 2.0 / (roughness * roughness) - 2.0)?;
+                writeln!(self.mtl, "Pr {}", params.roughness_factor)?;
+                writeln!(self.mtl, "Pm {}", params.metallic_factor)?;
+                if params.base_color_alpha < 1.0 {
+                    writeln!(self.mtl, "d {}", params.base_color_alpha)?;
+                }
+                (params.base_color_factor, params.base_color_map.as_ref())
+            }
+        };
+        let rgb = to_rgb(color);
+        writeln!(self.mtl, "Kd {} {} {}", rgb[0], rgb[1], rgb[2])?;
+        if let Some(map) = map {
+            if let Some(map_path) = self.texture_path(map) {
+                writeln!(self.mtl, "map_Kd {}", map_path.display())?;
+            }
+        }
+        writeln!(self.mtl)?;
+        Ok(index)
+    }
+
+    /// Returns the path of a texture relative to the MTL file, scheduling
+    /// the texture to be written as a PNG image if it was not loaded from a
+    /// file.
+    fn texture_path(
+        &mut self,
+        texture: &Texture<[f32; 4]>,
+    ) -> Option<PathBuf> {
+        let directory = match self.mtl_path.parent() {
+            Some(parent) if parent != Path::new("") => parent.to_owned(),
+            _ => PathBuf::from("."),
+        };
+        if let Some(path) = texture.path() {
+            return Some(match (fs::canonicalize(path), fs::canonicalize(&directory)) {
+                (Ok(path), Ok(directory)) => match path.strip_prefix(&directory) {
+                    Ok(relative) => relative.to_owned(),
+                    Err(_) => path.clone(),
+                },
+                _ => path.to_owned(),
+            });
+        }
+        let pixels = match texture.pixels() {
+            Some(pixels) => pixels,
+            None => {
+                warn!("Skipping a texture whose pixels are not kept on the CPU");
+                return None;
+            }
+        };
+        let key = &**pixels as *const image::RgbaImage as usize;
+        if let Some(&(_, ref name, _)) = self.images.iter().find(|&&(k, _, _)| k == key) {
+            return Some(name.clone());
+        }
+        let stem = self.mtl_path
+            .file_stem()
+            .map_or("texture".into(), |stem| stem.to_string_lossy());
+        let name = PathBuf::from(format!("{}_{}.png", stem, self.images.len()));
+        self.images.push((key, name.clone(), pixels.clone()));
+        Some(name)
+    }
+
+    /// Writes the OBJ and MTL files and the images they reference.
+    fn finish(self) -> Result<(), ExportError> {
+        let directory = self.mtl_path.parent().unwrap_or(Path::new(""));
+        for &(_, ref name, ref pixels) in &self.images {
+            let image_path = directory.join(name);
+            pixels
+                .save(&image_path)
+                .map_err(|e| ExportError::io(&image_path, e))?;
+        }
+        fs::File::create(&self.mtl_path)
+            .and_then(|mut file| file.write_all(&self.mtl))
+            .map_err(|e| ExportError::io(&self.mtl_path, e))?;
+        fs::File::create(self.path)
+            .and_then(|mut file| file.write_all(&self.obj))
+            .map_err(|e| ExportError::io(self.path, e))
+    }
+}
+
+/// Returns the transform of a node relative to the world origin.
+///
+/// Unlike the world transform kept by the hub, this is up to date even if
+/// the node is hidden or not part of a scene.
+fn world_transform(
+    hub: &Hub,
+    pointer: &NodePointer,
+) -> cgmath::Matrix4<f32> {
+    let node = &hub.nodes[pointer];
+    match node.parent {
+        Some(ref parent) => world_transform(hub, parent) * cgmath::Matrix4::from(node.transform),
+        None => cgmath::Matrix4::from(node.transform),
+    }
+}
+
+/// Converts a color to the components written to MTL files, which is how
+/// the OBJ loader reads them back.
+fn to_rgb(color: Color) -> [f32; 3] {
+    [
+        ((color >> 16) & 0xFF) as f32 / 255.0,
+        ((color >> 8) & 0xFF) as f32 / 255.0,
+        (color & 0xFF) as f32 / 255.0,
+    ]
+}
@@ -1,23 +1,26 @@
 use animation;
+use base64;
 use color;
 use geometry;
 use gltf;
-use gltf_importer;
 use image;
 use material;
 use mint;
 use object;
-use std::{fs, io};
+use serde_json;
+use std::{cmp, f32, fs, io, mem};
 
 use camera::Camera;
 use gltf::Gltf;
-use gltf_utils::AccessorIter;
+use gltf_utils::{AccessorIter, Source};
 use object::Object;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use vec_map::VecMap;
 
-use {Geometry, Group, Material, Mesh, Texture};
+use super::{Factory, LoadError, TextureData};
+use super::loading::Upload;
+use {Geometry, Group, Material, Mesh, Skeleton, Texture};
 
 type GltfNodeIndex = usize;
 
@@ -25,54 +28,27 @@ impl super::Factory {
     /// Loads a `glTF` texture.
     fn load_gltf_texture(
         &mut self,
-        texture: &AsRef<gltf::Texture>,
-        buffers: &gltf_importer::Buffers,
-        base: &Path,
-    ) -> Texture<[f32; 4]> {
-        use image::ImageFormat::{JPEG as Jpeg, PNG as Png};
-        let texture = texture.as_ref();
-        let image = match texture.source().data() {
-            gltf::image::Data::View { view, mime_type } => {
-                let format = match mime_type {
-                    "image/png" => Png,
-                    "image/jpeg" => Jpeg,
-                    _ => unreachable!(),
-                };
-                let data = buffers.view(&view).unwrap();
-                if data.starts_with(b"data:") {
-                    // Data URI decoding not yet implemented
-                    unimplemented!()
-                } else {
-                    image::load_from_memory_with_format(&data, format)
-                        .unwrap()
-                        .to_rgba()
-                }
-            }
-            gltf::image::Data::Uri { uri, mime_type } => {
-                let path: PathBuf = base.join(uri);
-                if let Some(ty) = mime_type {
-                    let format = match ty {
-                        "image/png" => Png,
-                        "image/jpeg" => Jpeg,
-                        _ => unreachable!(),
-                    };
-                    let file = fs::File::open(&path).unwrap();
-                    let reader = io::BufReader::new(file);
-                    image::load(reader, format).unwrap().to_rgba()
-                } else {
-                    image::open(&path).unwrap().to_rgba()
-                }
-            }
-        };
-        let (width, height) = (image.width() as u16, image.height() as u16);
+        texture: &gltf::Texture,
+        images: &[TextureData],
+        path: &Path,
+    ) -> Result<Texture<[f32; 4]>, LoadError> {
+        let image = &images[texture.source().index()];
         use {FilterMethod, WrapMode};
-        use gltf::texture::{MagFilter, WrappingMode};
+        use gltf::texture::{MagFilter, MinFilter, WrappingMode};
         let params = texture.sampler();
-        // gfx does not support separate min / mag
-        // filters yet, so for now we'll use `mag_filter` for both.
-        let mag_filter = match params.mag_filter() {
-            None | Some(MagFilter::Nearest) => FilterMethod::Scale,
-            Some(MagFilter::Linear) => FilterMethod::Bilinear,
+        // gfx does not support separate min / mag filters, so the method
+        // is picked from the min filter, falling back to nearest texels when
+        // the mag filter asks for them.
+        let nearest = match params.mag_filter() {
+            Some(MagFilter::Nearest) => true,
+            _ => false,
+        };
+        let filter = match params.min_filter() {
+            Some(MinFilter::Nearest) | Some(MinFilter::Linear) if nearest => FilterMethod::Scale,
+            Some(MinFilter::Nearest) | Some(MinFilter::Linear) => FilterMethod::Bilinear,
+            Some(_) | None if nearest => FilterMethod::Mipmap,
+            Some(_) => FilterMethod::Trilinear,
+            None => FilterMethod::Anisotropic(DEFAULT_ANISOTROPY),
         };
         let wrap_s = match params.wrap_s() {
             WrappingMode::ClampToEdge => WrapMode::Clamp,
@@ -84,62 +60,99 @@ impl super::Factory {
             WrappingMode::MirroredRepeat => WrapMode::Mirror,
             WrappingMode::Repeat => WrapMode::Tile,
         };
-        let sampler = self.sampler(mag_filter, wrap_s, wrap_t);
-        self.load_texture_from_memory(width, height, &image, sampler)
+        let sampler = self.sampler(filter, wrap_s, wrap_t);
+        Self::upload_texture(path, image, sampler, self.keep_texture_pixels, &mut self.backend)
     }
 
-    /// Loads a `glTF` material.
+    /// Loads a `glTF` material, whose textures are among those uploaded.
     fn load_gltf_material(
-        &mut self,
+        &self,
         mat: &gltf::Material,
-        buffers: &gltf_importer::Buffers,
-        base: &Path,
-    ) -> Material {
+        source: &GltfSource,
+        textures: &HashMap<usize, Texture<[f32; 4]>>,
+        path: &Path,
+    ) -> Result<Material, LoadError> {
+        let transform = |slot: &'static str| {
+            mat.index()
+                .and_then(|index| source.texture_transforms.get(&(index, slot)))
+        };
+        // Textures shared between materials are uploaded once, while their
+        // transforms are specific to each material.
+        let texture = |index: usize, slot: &'static str| -> Result<Texture<[f32; 4]>, LoadError> {
+            let mut texture = textures
+                .get(&index)
+                .cloned()
+                .ok_or_else(|| LoadError::decode(path, format!("texture {} was not uploaded", index)))?;
+            if let Some(transform) = transform(slot) {
+                texture.set_uv_transform(transform.offset.into(), transform.rotation, transform.scale.into());
+            }
+            Ok(texture)
+        };
         let pbr = mat.pbr_metallic_roughness();
-        let mut is_basic_material = true;
-        let base_color_map = pbr.base_color_texture()
-            .map(|t| self.load_gltf_texture(&t, buffers, base));
-        let normal_map = mat.normal_texture().map(|t| {
-            is_basic_material = false;
-            self.load_gltf_texture(&t, buffers, base)
-        });
-        let emissive_map = mat.emissive_texture().map(|t| {
-            is_basic_material = false;
-            self.load_gltf_texture(&t, buffers, base)
-        });
-        let metallic_roughness_map = pbr.metallic_roughness_texture().map(|t| {
-            is_basic_material = false;
-            self.load_gltf_texture(&t, buffers, base)
-        });
-        let occlusion_map = mat.occlusion_texture().map(|t| {
-            is_basic_material = false;
-            self.load_gltf_texture(&t, buffers, base)
-        });
+        let base_color_map = match pbr.base_color_texture() {
+            Some(t) => Some(texture(texture_index(t), BASE_COLOR_TEXTURE)?),
+            None => None,
+        };
+        let normal_map = match mat.normal_texture() {
+            Some(t) => Some(texture(texture_index(t), NORMAL_TEXTURE)?),
+            None => None,
+        };
+        let emissive_map = match mat.emissive_texture() {
+            Some(t) => Some(texture(texture_index(t), EMISSIVE_TEXTURE)?),
+            None => None,
+        };
+        let metallic_roughness_map = match pbr.metallic_roughness_texture() {
+            Some(t) => Some(texture(texture_index(t), METALLIC_ROUGHNESS_TEXTURE)?),
+            None => None,
+        };
+        let occlusion_map = match mat.occlusion_texture() {
+            Some(t) => Some(texture(texture_index(t), OCCLUSION_TEXTURE)?),
+            None => None,
+        };
         let (base_color_factor, base_color_alpha) = {
             let x = pbr.base_color_factor();
             (color::from_linear_rgb([x[0], x[1], x[2]]), x[3])
         };
-        if is_basic_material {
-            material::Basic {
-                color: base_color_factor,
-                map: base_color_map,
-            }.into()
-        } else {
-            material::Pbr {
-                base_color_factor,
-                base_color_alpha,
-                metallic_factor: pbr.metallic_factor(),
-                roughness_factor: pbr.roughness_factor(),
-                occlusion_strength: mat.occlusion_texture().map_or(1.0, |t| t.strength()),
-                emissive_factor: color::from_linear_rgb(mat.emissive_factor()),
-                normal_scale: mat.normal_texture().map_or(1.0, |t| t.scale()),
-                base_color_map,
-                normal_map,
-                emissive_map,
-                metallic_roughness_map,
-                occlusion_map,
-            }.into()
-        }
+        let alpha_mode = match mat.alpha_mode() {
+            gltf::material::AlphaMode::Opaque => material::AlphaMode::Opaque,
+            gltf::material::AlphaMode::Mask => material::AlphaMode::Mask,
+            gltf::material::AlphaMode::Blend => material::AlphaMode::Blend,
+        };
+        // `KHR_texture_transform` may override the texture co-ordinate set.
+        let uv_set = |slot, tex_coord: Option<u32>| {
+            transform(slot)
+                .and_then(|t| t.tex_coord)
+                .or(tex_coord)
+                .unwrap_or(0)
+        };
+        let uv_sets = material::PbrUvSets {
+            base_color: uv_set(BASE_COLOR_TEXTURE, pbr.base_color_texture().map(|t| t.tex_coord())),
+            normal: uv_set(NORMAL_TEXTURE, mat.normal_texture().map(|t| t.tex_coord())),
+            emissive: uv_set(EMISSIVE_TEXTURE, mat.emissive_texture().map(|t| t.tex_coord())),
+            metallic_roughness: uv_set(
+                METALLIC_ROUGHNESS_TEXTURE,
+                pbr.metallic_roughness_texture().map(|t| t.tex_coord()),
+            ),
+            occlusion: uv_set(OCCLUSION_TEXTURE, mat.occlusion_texture().map(|t| t.tex_coord())),
+        };
+        Ok(material::Pbr {
+            base_color_factor,
+            base_color_alpha,
+            metallic_factor: pbr.metallic_factor(),
+            roughness_factor: pbr.roughness_factor(),
+            occlusion_strength: mat.occlusion_texture().map_or(1.0, |t| t.strength()),
+            emissive_factor: color::from_linear_rgb(mat.emissive_factor()),
+            normal_scale: mat.normal_texture().map_or(1.0, |t| t.scale()),
+            base_color_map,
+            normal_map,
+            emissive_map,
+            metallic_roughness_map,
+            occlusion_map,
+            uv_sets,
+            alpha_mode,
+            alpha_cutoff: mat.alpha_cutoff(),
+            double_sided: mat.double_sided(),
+        }.into())
     }
 
     /// Loads a `glTF` mesh.
@@ -150,12 +163,25 @@ impl super::Factory {
     fn load_gltf_mesh(
         &mut self,
         mesh: &gltf::Mesh,
-        buffers: &gltf_importer::Buffers,
-        base: &Path,
-    ) -> Vec<Mesh> {
+        source: &GltfSource,
+        textures: &HashMap<usize, Texture<[f32; 4]>>,
+        path: &Path,
+    ) -> Result<Vec<Mesh>, LoadError> {
+        let buffers = &source.buffers;
         let mut primitives = Vec::new();
         for primitive in mesh.primitives() {
             use gltf_utils::PrimitiveIterators;
+            for (semantic, accessor) in primitive.attributes() {
+                use gltf::mesh::Semantic::*;
+                let size = match semantic {
+                    Positions | Normals => mem::size_of::<[f32; 3]>(),
+                    Tangents => mem::size_of::<[f32; 4]>(),
+                    _ => continue,
+                };
+                if accessor.size() != size {
+                    return Err(LoadError::decode(path, format!("accessor {} has an unexpected type", accessor.index())));
+                }
+            }
             let mut faces = vec![];
             if let Some(mut iter) = primitive.indices_u32(buffers) {
                 while let (Some(a), Some(b), Some(c)) = (iter.next(), iter.next(), iter.next()) {
@@ -164,7 +190,7 @@ impl super::Factory {
             }
             let vertices: Vec<mint::Point3<f32>> = primitive
                 .positions(buffers)
-                .unwrap()
+                .ok_or_else(|| LoadError::decode(path, "mesh primitive without positions"))?
                 .map(|x| x.into())
                 .collect();
             let normals = if let Some(iter) = primitive.normals(buffers) {
@@ -182,33 +208,120 @@ impl super::Factory {
             } else {
                 Vec::new()
             };
+            let tex_coords1 = if let Some(iter) = primitive.tex_coords_f32(1, buffers) {
+                iter.map(|x| x.into()).collect()
+            } else {
+                Vec::new()
+            };
+            let colors = if let Some(iter) = primitive.colors_rgba_f32(0, 1.0, buffers) {
+                iter.map(|x| x.into()).collect()
+            } else {
+                Vec::new()
+            };
+            let joint_indices = if let Some(iter) = primitive.joints_u16(0, buffers) {
+                iter.map(|x| [x[0] as i32, x[1] as i32, x[2] as i32, x[3] as i32])
+                    .collect()
+            } else {
+                Vec::new()
+            };
+            let joint_weights = if let Some(iter) = primitive.weights_f32(0, buffers) {
+                iter.collect()
+            } else {
+                Vec::new()
+            };
+            // glTF morph targets hold displacements from the base shape,
+            // whereas `three` shapes hold absolute values.
+            let mut shapes = HashMap::new();
+            for (index, target) in primitive.morph_targets().enumerate() {
+                let target_vertices: Vec<mint::Point3<f32>> = match target.positions() {
+                    Some(accessor) => accessor_iter::<[f32; 3], _>(accessor, buffers, path)?
+                        .zip(vertices.iter())
+                        .map(|(d, v)| [v.x + d[0], v.y + d[1], v.z + d[2]].into())
+                        .collect(),
+                    None => vertices.clone(),
+                };
+                let target_normals: Vec<mint::Vector3<f32>> = match target.normals() {
+                    Some(accessor) => accessor_iter::<[f32; 3], _>(accessor, buffers, path)?
+                        .zip(normals.iter())
+                        .map(|(d, n)| [n.x + d[0], n.y + d[1], n.z + d[2]].into())
+                        .collect(),
+                    None => normals.clone(),
+                };
+                if target_vertices.len() != vertices.len() || target_normals.len() != normals.len() {
+                    return Err(LoadError::decode(path, format!("morph target {} is too short", index)));
+                }
+                let shape = geometry::Shape {
+                    vertices: target_vertices,
+                    normals: target_normals,
+                    ..geometry::Shape::empty()
+                };
+                shapes.insert(index.to_string(), shape);
+            }
+            // Attribute streams are zipped together, and indices are used
+            // both by the GPU and on the CPU, so they must all agree with the
+            // number of vertices.
+            let count = vertices.len();
+            let streams = [
+                ("normals", normals.len()),
+                ("tangents", tangents.len()),
+                ("texture co-ordinates", tex_coords.len()),
+                ("second texture co-ordinates", tex_coords1.len()),
+                ("colors", colors.len()),
+                ("joint indices", joint_indices.len()),
+                ("joint weights", joint_weights.len()),
+            ];
+            for &(name, len) in &streams {
+                if len != 0 && len != count {
+                    return Err(LoadError::decode(
+                        path,
+                        format!("mesh {} has {} {} for {} vertices", mesh.index(), len, name, count),
+                    ));
+                }
+            }
+            if faces.iter().any(|face| face.iter().any(|&i| i as usize >= count)) {
+                return Err(LoadError::decode(path, format!("mesh {} has out of range indices", mesh.index())));
+            }
             let geometry = Geometry {
                 base_shape: geometry::Shape {
                     vertices: vertices,
                     normals: normals,
                     tangents: tangents,
                     tex_coords: tex_coords,
+                    tex_coords1,
+                    colors,
                 },
+                shapes: shapes,
                 faces: faces,
+                joints: geometry::Joints {
+                    indices: joint_indices,
+                    weights: joint_weights,
+                },
                 ..Geometry::empty()
             };
-            let material = self.load_gltf_material(&primitive.material(), buffers, base);
-            primitives.push(self.mesh(geometry, material));
+            let material = self.load_gltf_material(&primitive.material(), source, textures, path)?;
+            let mut primitive_mesh = self.mesh(geometry, material);
+            if let Some(weights) = mesh.weights() {
+                primitive_mesh.set_weights(weights);
+            }
+            primitives.push(primitive_mesh);
         }
-        primitives
+        Ok(primitives)
     }
 
     /// Loads a single `glTF` node.
+    ///
+    /// The meshes of the node are taken from `uploaded` the first time they
+    /// are used, and instanced afterwards.
     fn load_gltf_node(
         &mut self,
-        gltf: &gltf::Gltf,
         the_node: &gltf::Node,
-        buffers: &gltf_importer::Buffers,
-        base: &Path,
+        source: &GltfSource,
         cameras: &mut Vec<Camera>,
+        uploaded: &mut VecMap<Vec<Mesh>>,
         meshes: &mut VecMap<Vec<Mesh>>,
         instances: &mut Vec<Mesh>,
         node_map: &mut HashMap<GltfNodeIndex, object::Base>,
+        mesh_map: &mut HashMap<GltfNodeIndex, Vec<Mesh>>,
     ) -> Group {
         fn clone_child<'a>(
             gltf: &'a Gltf,
@@ -231,28 +344,31 @@ impl super::Factory {
         ];
 
         while let Some(mut item) = stack.pop() {
-            // TODO: Groups do not handle non-uniform scaling, so for now
-            // we'll choose Y to be the scale factor in all directions.
             let (translation, rotation, scale) = item.node.transform().decomposed();
-            item.group.set_transform(translation, rotation, scale[1]);
+            item.group.set_transform(translation, rotation, scale);
 
             if let Some(entry) = item.node.mesh() {
                 let index = entry.index();
                 let has_entry = meshes.contains_key(index);
-                if has_entry {
+                let node_meshes = if has_entry {
                     let mesh = meshes.get(index).unwrap();
+                    let mut node_meshes = Vec::new();
                     for primitive in mesh.iter() {
                         let mut instance = self.mesh_instance(primitive);
                         instance.set_parent(&item.group);
+                        node_meshes.push(instance.clone());
                         instances.push(instance);
                     }
+                    node_meshes
                 } else {
-                    let mut primitives = self.load_gltf_mesh(&entry, buffers, base);
+                    let mut primitives = uploaded.remove(index).unwrap_or_default();
                     for primitive in &mut primitives {
                         primitive.set_parent(&item.group);
                     }
-                    meshes.insert(index, primitives);
-                }
+                    meshes.insert(index, primitives.clone());
+                    primitives
+                };
+                mesh_map.insert(item.node.index(), node_meshes);
             }
 
             if let Some(entry) = item.node.camera() {
@@ -283,7 +399,7 @@ impl super::Factory {
                 let mut child_group = self.group();
                 child_group.set_parent(&item.group);
                 stack.push(Item {
-                    node: clone_child(&gltf, &child),
+                    node: clone_child(&source.gltf, &child),
                     group: child_group,
                 });
             }
@@ -295,13 +411,115 @@ impl super::Factory {
         groups.swap_remove(0)
     }
 
+    /// Creates the `KHR_lights_punctual` lights of the loaded nodes.
+    ///
+    /// glTF lights shine along the negative Z axis of their node, just like
+    /// `three` lights do, hence they are attached to the node unchanged.
+    fn load_gltf_lights(
+        &mut self,
+        gltf: &Gltf,
+        light_defs: &[Option<LightDef>],
+        node_lights: &HashMap<GltfNodeIndex, usize>,
+        node_map: &HashMap<GltfNodeIndex, object::Base>,
+    ) -> Vec<super::GltfLight> {
+        let mut lights = Vec::new();
+        for node in gltf.nodes() {
+            let (group, def) = match (node_map.get(&node.index()), node_lights.get(&node.index())) {
+                (Some(group), Some(&light)) => match light_defs[light] {
+                    Some(ref def) => (group, def),
+                    None => continue,
+                },
+                _ => continue,
+            };
+            let color = color::from_linear_rgb(def.color);
+            let light = match def.kind {
+                LightKind::Directional => {
+                    let mut light = self.directional_light(color, def.intensity);
+                    light.set_parent(group);
+                    super::GltfLight::Directional(light)
+                }
+                LightKind::Point => {
+                    let mut light = self.point_light(color, def.intensity);
+                    light.set_parent(group);
+                    super::GltfLight::Point(light)
+                }
+                LightKind::Spot {
+                    inner_cone_angle,
+                    outer_cone_angle,
+                } => {
+                    let mut light = self.spot_light(color, def.intensity, inner_cone_angle, outer_cone_angle);
+                    light.set_parent(group);
+                    super::GltfLight::Spot(light)
+                }
+            };
+            lights.push(light);
+        }
+        lights
+    }
+
+    /// Loads the skeletons of skinned glTF nodes and binds them to the
+    /// meshes of those nodes.
+    ///
+    /// Each bone is attached to the group of its joint node, so animating the
+    /// joints moves the bones along with them.
+    fn load_gltf_skins(
+        &mut self,
+        gltf: &Gltf,
+        node_map: &HashMap<GltfNodeIndex, object::Base>,
+        mesh_map: &mut HashMap<GltfNodeIndex, Vec<Mesh>>,
+        buffers: &Buffers,
+        path: &Path,
+    ) -> Result<Vec<Skeleton>, LoadError> {
+        let mut skeletons = Vec::new();
+        let mut skeleton_map = HashMap::<usize, usize>::new();
+        for (&node_index, node_meshes) in mesh_map.iter_mut() {
+            let skin = match gltf.nodes().nth(node_index).unwrap().skin() {
+                Some(skin) => skin,
+                None => continue,
+            };
+            let skeleton_index = match skeleton_map.get(&skin.index()) {
+                Some(&index) => index,
+                None => {
+                    let inverse_bind_matrices = match skin.inverse_bind_matrices() {
+                        Some(accessor) => accessor_iter::<[[f32; 4]; 4], _>(accessor, buffers, path)?.collect(),
+                        None => Vec::new(),
+                    };
+                    let mut bones = Vec::new();
+                    for (index, joint) in skin.joints().enumerate() {
+                        let inverse_bind_matrix = inverse_bind_matrices
+                            .get(index)
+                            .cloned()
+                            .unwrap_or([[1.0, 0.0, 0.0, 0.0], [0.0, 1.0, 0.0, 0.0], [0.0, 0.0, 1.0, 0.0], [0.0, 0.0, 0.0, 1.0]]);
+                        let mut bone = self.bone(inverse_bind_matrix.into());
+                        if let Some(group) = node_map.get(&joint.index()) {
+                            bone.set_parent(group);
+                        }
+                        bones.push(bone);
+                    }
+                    skeletons.push(self.skeleton(bones));
+                    skeleton_map.insert(skin.index(), skeletons.len() - 1);
+                    skeletons.len() - 1
+                }
+            };
+            for mesh in node_meshes.iter_mut() {
+                mesh.set_skeleton(&skeletons[skeleton_index]);
+            }
+        }
+        Ok(skeletons)
+    }
+
     /// Loads animations from glTF 2.0.
-    pub fn load_gltf_animations(
+    ///
+    /// Morph target weight channels produce one track per mesh of the target
+    /// node, as each glTF primitive is a separate [`Mesh`](struct.Mesh.html).
+    pub fn load_gltf_animations<S: Source>(
         &mut self,
+        path: &Path,
         gltf: &Gltf,
         node_map: &HashMap<GltfNodeIndex, object::Base>,
-        buffers: &gltf_importer::Buffers,
-    ) -> Vec<animation::Clip> {
+        mesh_map: &HashMap<GltfNodeIndex, Vec<Mesh>>,
+        buffers: &S,
+    ) -> Result<Vec<animation::Clip>, LoadError> {
         use gltf::animation::InterpolationAlgorithm::*;
         let mut clips = Vec::new();
         for animation in gltf.animations() {
@@ -325,81 +543,131 @@ impl super::Factory {
                     CatmullRomSpline => animation::Interpolation::CatmullRom,
                 };
                 use animation::{Binding, Track, Values};
-                let times: Vec<f32> = AccessorIter::new(input, buffers).collect();
-                let (binding, values) = match target.path() {
+                let times: Vec<f32> = accessor_iter(input, buffers, path)?.collect();
+                let (binding, values, targets) = match target.path() {
                     gltf::animation::TrsProperty::Translation => {
-                        let values = AccessorIter::<[f32; 3]>::new(output, buffers)
+                        let values = accessor_iter::<[f32; 3], _>(output, buffers, path)?
                             .map(|v| mint::Vector3::from(v))
                             .collect::<Vec<_>>();
-                        assert_eq!(values.len(), times.len());
-                        (Binding::Position, Values::Vector3(values))
+                        (Binding::Position, Values::Vector3(values), vec![object])
                     }
                     gltf::animation::TrsProperty::Rotation => {
-                        let values = AccessorIter::<[f32; 4]>::new(output, buffers)
+                        let values = accessor_iter::<[f32; 4], _>(output, buffers, path)?
                             .map(|r| mint::Quaternion::from(r))
                             .collect::<Vec<_>>();
-                        assert_eq!(values.len(), times.len());
-                        (Binding::Orientation, Values::Quaternion(values))
+                        (Binding::Orientation, Values::Quaternion(values), vec![object])
                     }
                     gltf::animation::TrsProperty::Scale => {
-                        // TODO: Groups do not handle non-uniform scaling, so for now
-                        // we'll choose Y to be the scale factor in all directions.
-                        let values = AccessorIter::<[f32; 3]>::new(output, buffers)
-                            .map(|s| s[1])
+                        let values = accessor_iter::<[f32; 3], _>(output, buffers, path)?
+                            .map(|s| mint::Vector3::from(s))
+                            .collect::<Vec<_>>();
+                        (Binding::Scale, Values::Vector3(values), vec![object])
+                    }
+                    gltf::animation::TrsProperty::Weights => {
+                        // Each glTF primitive of the node is a separate mesh
+                        // with the same morph targets.
+                        let weights = accessor_iter::<f32, _>(output, buffers, path)?.collect::<Vec<_>>();
+                        let meshes = mesh_map.get(&node.index()).map_or(&[][..], |meshes| &meshes[..]);
+                        let targets = meshes.iter().map(|mesh| mesh.upcast()).collect();
+                        // Cubic spline samplers store an in-tangent, a value,
+                        // and an out-tangent for every keyframe.
+                        let values_per_keyframe = match interpolation {
+                            animation::Interpolation::Cubic => 3,
+                            _ => 1,
+                        };
+                        let keyframe_count = cmp::max(values_per_keyframe * times.len(), 1);
+                        let count = cmp::max(weights.len() / keyframe_count, 1);
+                        let values = weights
+                            .chunks(count)
+                            .map(|chunk| chunk.to_vec())
                             .collect::<Vec<_>>();
-                        assert_eq!(values.len(), times.len());
-                        (Binding::Scale, Values::Scalar(values))
+                        (Binding::Weights, Values::Weights(values), targets)
                     }
-                    gltf::animation::TrsProperty::Weights => unimplemented!(),
                 };
-                tracks.push((
-                    Track {
+                for target in targets {
+                    let track = Track {
                         binding,
                         interpolation,
-                        times,
-                        values,
-                    },
-                    object,
-                ));
+                        times: times.clone(),
+                        values: values.clone(),
+                    };
+                    match track.validate() {
+                        Ok(()) => tracks.push((track, target)),
+                        Err(err) => warn!("Skipping invalid animation channel of {}: {}", path.display(), err),
+                    }
+                }
             }
-            clips.push(animation::Clip { name, tracks });
+            clips.push(animation::Clip {
+                name,
+                tracks,
+                markers: Vec::new(),
+            });
         }
-        clips
+        Ok(clips)
     }
 
-    /// Load a scene from glTF 2.0 format.
-    pub fn load_gltf(
+    /// Creates the nodes of a glTF file imported by
+    /// [`import_gltf`](fn.import_gltf.html), once its textures and meshes are
+    /// uploaded.
+    fn build_gltf(
         &mut self,
-        path_str: &str,
-    ) -> super::Gltf {
-        info!("Loading {}", path_str);
-        let path = Path::new(path_str);
-        let default = Path::new("");
-        let base = path.parent().unwrap_or(&default);
-        let (gltf, buffers) = gltf_importer::import(path).expect("invalid glTF 2.0");
+        path: &Path,
+        source: &GltfSource,
+        mut uploaded: VecMap<Vec<Mesh>>,
+    ) -> Result<super::Gltf, LoadError> {
+        let gltf = &source.gltf;
         let mut cameras = Vec::new();
         let mut meshes = VecMap::new();
         let mut instances = Vec::new();
         let mut node_map = HashMap::new();
-        let mut clips = Vec::new();
-        let group = self.group();
+        let mut mesh_map = HashMap::new();
+        let mut scenes = Vec::new();
 
-        if let Some(scene) = gltf.default_scene() {
+        for scene in gltf.scenes() {
+            let group = self.group();
             for root in scene.nodes() {
+                if node_map.contains_key(&root.index()) {
+                    warn!(
+                        "Node {} is shared between scenes, keeping it in the first one only",
+                        root.index()
+                    );
+                    continue;
+                }
                 let mut node = self.load_gltf_node(
-                    &gltf,
                     &root,
-                    &buffers,
-                    base,
+                    source,
                     &mut cameras,
+                    &mut uploaded,
                     &mut meshes,
                     &mut instances,
                     &mut node_map,
+                    &mut mesh_map,
                 );
                 node.set_parent(&group);
             }
-            clips = self.load_gltf_animations(&gltf, &node_map, &buffers);
+            scenes.push(super::GltfScene {
+                name: scene.name().map(str::to_owned),
+                group,
+            });
+        }
+        let lights = self.load_gltf_lights(gltf, &source.lights, &source.node_lights, &node_map);
+        let skeletons = self.load_gltf_skins(gltf, &node_map, &mut mesh_map, &source.buffers, path)?;
+        let clips = self.load_gltf_animations(path, gltf, &node_map, &mesh_map, &source.buffers)?;
+
+        let group = match gltf.default_scene().or_else(|| gltf.scenes().next()) {
+            Some(scene) => scenes[scene.index()].group.clone(),
+            None => self.group(),
+        };
+        let mut node_names = HashMap::new();
+        for node in gltf.nodes() {
+            if let (Some(name), true) = (node.name(), node_map.contains_key(&node.index())) {
+                node_names.entry(name.to_owned()).or_insert(node.index());
+            }
         }
+        let nodes = node_map
+            .into_iter()
+            .map(|(index, object)| (index, Group::new(object)))
+            .collect();
 
         // Put the instances in any empty spot in the mesh map.
         {
@@ -410,11 +678,584 @@ impl super::Factory {
             meshes.insert(i, instances);
         }
 
-        super::Gltf {
+        Ok(super::Gltf {
             group,
+            scenes,
+            nodes,
+            node_names,
             cameras,
+            lights,
             clips,
             meshes,
+            skeletons,
+        })
+    }
+
+    /// Load a scene from glTF 2.0 format.
+    ///
+    /// Both the JSON (`.gltf`) and binary (`.glb`) containers are supported.
+    /// Buffers and images may be external files or embedded as base64 `data:`
+    /// URIs.
+    pub fn load_gltf(
+        &mut self,
+        path_str: &str,
+    ) -> Result<super::Gltf, LoadError> {
+        info!("Loading {}", path_str);
+        let path = Path::new(path_str);
+        let source = import_gltf(path, &|_| {})?;
+        self.upload_now(GltfUpload::new(path, source))
+    }
+
+    /// Load a scene from glTF 2.0 data held in memory, such as a `.glb` file
+    /// embedded with `include_bytes!`.
+    ///
+    /// External files referenced by the asset are resolved relative to
+    /// `base_dir`. Errors concerning the asset itself are reported with an
+    /// empty path.
+    pub fn load_gltf_from_memory<P: AsRef<Path>>(
+        &mut self,
+        data: &[u8],
+        base_dir: P,
+    ) -> Result<super::Gltf, LoadError> {
+        let path = Path::new("");
+        let source = import_gltf_slice(data, base_dir.as_ref(), path, &|_| {})?;
+        self.upload_now(GltfUpload::new(path, source))
+    }
+}
+
+/// Uploads a glTF file imported by [`import_gltf`](fn.import_gltf.html),
+/// one texture or mesh per step, then creates its nodes.
+pub(crate) struct GltfUpload {
+    path: PathBuf,
+    source: GltfSource,
+    /// Indices of the textures left to upload, in reverse order.
+    pending_textures: Vec<usize>,
+    /// Indices of the meshes left to upload, in reverse order.
+    pending_meshes: Vec<usize>,
+    /// Uploaded textures, by glTF texture index.
+    textures: HashMap<usize, Texture<[f32; 4]>>,
+    /// Uploaded meshes, by glTF mesh index.
+    meshes: VecMap<Vec<Mesh>>,
+    step_count: usize,
+    steps_done: usize,
+}
+
+impl GltfUpload {
+    /// Plans the upload of the textures and meshes used by the nodes of the
+    /// scenes of `source`.
+    pub(crate) fn new(
+        path: &Path,
+        source: GltfSource,
+    ) -> Self {
+        let (pending_textures, pending_meshes) = {
+            let gltf = &source.gltf;
+            let nodes: Vec<gltf::Node> = gltf.nodes().collect();
+            let mut visited = vec![false; nodes.len()];
+            let mut stack: Vec<usize> = gltf.scenes()
+                .flat_map(|scene| scene.nodes().map(|node| node.index()))
+                .collect();
+            let mut mesh_indices = Vec::new();
+            while let Some(index) = stack.pop() {
+                if visited[index] {
+                    continue;
+                }
+                visited[index] = true;
+                if let Some(mesh) = nodes[index].mesh() {
+                    if !mesh_indices.contains(&mesh.index()) {
+                        mesh_indices.push(mesh.index());
+                    }
+                }
+                stack.extend(nodes[index].children().map(|child| child.index()));
+            }
+            mesh_indices.sort();
+
+            let mut texture_indices = Vec::new();
+            for mesh in gltf.meshes().filter(|mesh| mesh_indices.contains(&mesh.index())) {
+                for primitive in mesh.primitives() {
+                    for index in material_textures(&primitive.material()) {
+                        if !texture_indices.contains(&index) {
+                            texture_indices.push(index);
+                        }
+                    }
+                }
+            }
+            texture_indices.sort();
+            texture_indices.reverse();
+            mesh_indices.reverse();
+            (texture_indices, mesh_indices)
+        };
+        GltfUpload {
+            path: path.to_owned(),
+            step_count: pending_textures.len() + pending_meshes.len() + 1,
+            steps_done: 0,
+            source,
+            pending_textures,
+            pending_meshes,
+            textures: HashMap::new(),
+            meshes: VecMap::new(),
+        }
+    }
+}
+
+impl Upload for GltfUpload {
+    type Output = super::Gltf;
+
+    fn step(
+        &mut self,
+        factory: &mut Factory,
+    ) -> Option<Result<super::Gltf, LoadError>> {
+        self.steps_done += 1;
+        if let Some(index) = self.pending_textures.pop() {
+            let texture = self.source.gltf.textures().nth(index).unwrap();
+            match factory.load_gltf_texture(&texture, &self.source.images, &self.path) {
+                Ok(texture) => {
+                    self.textures.insert(index, texture);
+                    None
+                }
+                Err(error) => Some(Err(error)),
+            }
+        } else if let Some(index) = self.pending_meshes.pop() {
+            let mesh = self.source.gltf.meshes().nth(index).unwrap();
+            match factory.load_gltf_mesh(&mesh, &self.source, &self.textures, &self.path) {
+                Ok(primitives) => {
+                    self.meshes.insert(index, primitives);
+                    None
+                }
+                Err(error) => Some(Err(error)),
+            }
+        } else {
+            let meshes = mem::replace(&mut self.meshes, VecMap::new());
+            Some(factory.build_gltf(&self.path, &self.source, meshes))
+        }
+    }
+
+    fn progress(&self) -> f32 {
+        self.steps_done as f32 / self.step_count as f32
+    }
+}
+
+/// Returns the index of the texture referred to by a material.
+fn texture_index<'a, T: AsRef<gltf::Texture<'a>>>(info: T) -> usize {
+    info.as_ref().index()
+}
+
+/// Returns the indices of the textures used by a material.
+fn material_textures(mat: &gltf::Material) -> Vec<usize> {
+    let pbr = mat.pbr_metallic_roughness();
+    pbr.base_color_texture()
+        .map(texture_index)
+        .into_iter()
+        .chain(pbr.metallic_roughness_texture().map(texture_index))
+        .chain(mat.normal_texture().map(texture_index))
+        .chain(mat.occlusion_texture().map(texture_index))
+        .chain(mat.emissive_texture().map(texture_index))
+        .collect()
+}
+
+/// The contents of the buffers of a glTF asset, in glTF buffer index order.
+#[derive(Clone, Debug)]
+struct Buffers(Vec<Vec<u8>>);
+
+impl Buffers {
+    /// Returns the contents of a buffer view.
+    fn view(
+        &self,
+        view: &gltf::buffer::View,
+    ) -> Option<&[u8]> {
+        let buffer = self.0.get(view.buffer().index())?;
+        buffer.get(view.offset() .. view.offset() + view.length())
+    }
+}
+
+impl Source for Buffers {
+    fn source_buffer(
+        &self,
+        buffer: &gltf::Buffer,
+    ) -> &[u8] {
+        &self.0[buffer.index()]
+    }
+}
+
+/// A glTF file read and with its images decoded on a loader thread, ready to
+/// be uploaded to the GPU.
+pub(crate) struct GltfSource {
+    gltf: Gltf,
+    buffers: Buffers,
+    /// Decoded images with their mipmaps, in glTF image index order.
+    images: Vec<TextureData>,
+    /// Lights of the `KHR_lights_punctual` extension, in light index order.
+    lights: Vec<Option<LightDef>>,
+    /// Light indices by glTF node index.
+    node_lights: HashMap<GltfNodeIndex, usize>,
+    /// `KHR_texture_transform` extensions by material index and texture
+    /// property name.
+    texture_transforms: HashMap<(usize, &'static str), TextureTransform>,
+}
+
+/// A light defined by the `KHR_lights_punctual` extension.
+struct LightDef {
+    color: [f32; 3],
+    intensity: f32,
+    kind: LightKind,
+}
+
+enum LightKind {
+    Directional,
+    Point,
+    Spot {
+        inner_cone_angle: f32,
+        outer_cone_angle: f32,
+    },
+}
+
+/// A texture transform defined by the `KHR_texture_transform` extension.
+struct TextureTransform {
+    offset: [f32; 2],
+    rotation: f32,
+    scale: [f32; 2],
+    /// Overrides the texture co-ordinate set of the texture.
+    tex_coord: Option<u32>,
+}
+
+/// The anisotropy used for glTF textures which do not specify a min filter.
+const DEFAULT_ANISOTROPY: u8 = 8;
+
+/// Names of the material properties referring to textures.
+const BASE_COLOR_TEXTURE: &str = "baseColorTexture";
+const METALLIC_ROUGHNESS_TEXTURE: &str = "metallicRoughnessTexture";
+const NORMAL_TEXTURE: &str = "normalTexture";
+const OCCLUSION_TEXTURE: &str = "occlusionTexture";
+const EMISSIVE_TEXTURE: &str = "emissiveTexture";
+
+/// Reads a glTF file along with its buffers and decodes all of its images.
+///
+/// `progress` is called with the fraction of the work done so far.
+pub(crate) fn import_gltf(
+    path: &Path,
+    progress: &Fn(f32),
+) -> Result<GltfSource, LoadError> {
+    let data = super::read_file(path)?;
+    let base = path.parent().unwrap_or(Path::new(""));
+    import_gltf_slice(&data, base, path, progress)
+}
+
+/// Parses a glTF or GLB asset held in memory.
+///
+/// Relative URIs are resolved against `base`, while `path` identifies the
+/// asset in errors.
+fn import_gltf_slice(
+    data: &[u8],
+    base: &Path,
+    path: &Path,
+    progress: &Fn(f32),
+) -> Result<GltfSource, LoadError> {
+    let (json, bin) = if data.starts_with(GLB_MAGIC) {
+        split_glb(data, path)?
+    } else {
+        (data, None)
+    };
+    let gltf = Gltf::from_slice(json)
+        .and_then(|gltf| gltf.validate_minimally())
+        .map_err(|e| LoadError::decode(path, format!("{:?}", e)))?;
+    let root: serde_json::Value = serde_json::from_slice(json).map_err(|e| LoadError::decode(path, e))?;
+    let (lights, node_lights) = parse_lights(&root, path)?;
+    let texture_transforms = parse_texture_transforms(&root);
+
+    let mut buffers = Vec::new();
+    for buffer in gltf.buffers() {
+        let data = match buffer.uri() {
+            "" | "#bin" => match bin {
+                Some(bin) => bin.to_vec(),
+                None => return Err(LoadError::decode(path, "missing GLB binary chunk")),
+            },
+            uri if uri.starts_with("data:") => decode_data_uri(uri, path)?.1,
+            uri => super::read_file(&base.join(uri))?,
+        };
+        if data.len() < buffer.length() {
+            return Err(LoadError::decode(path, format!("buffer {} is too short", buffer.index())));
+        }
+        buffers.push(data);
+    }
+    let buffers = Buffers(buffers);
+    validate_ranges(&gltf, &buffers, path)?;
+    progress(0.5);
+
+    let mut images = Vec::new();
+    let count = gltf.images().count();
+    for image in gltf.images() {
+        images.push(TextureData::new(decode_gltf_image(&image, &buffers, base, path)?));
+        progress(0.5 + 0.5 * images.len() as f32 / count as f32);
+    }
+    Ok(GltfSource {
+        gltf,
+        buffers,
+        images,
+        lights,
+        node_lights,
+        texture_transforms,
+    })
+}
+
+/// Checks that every buffer view lies within its buffer and every accessor
+/// within its buffer view, as reading them out of range would panic.
+fn validate_ranges(
+    gltf: &Gltf,
+    buffers: &Buffers,
+    path: &Path,
+) -> Result<(), LoadError> {
+    for view in gltf.views() {
+        let end = view.offset().checked_add(view.length());
+        match (end, buffers.0.get(view.buffer().index())) {
+            (Some(end), Some(buffer)) if end <= buffer.len() => {}
+            _ => return Err(LoadError::decode(path, format!("buffer view {} is out of range", view.index()))),
+        }
+    }
+    for accessor in gltf.accessors() {
+        if accessor.count() == 0 {
+            return Err(LoadError::decode(path, format!("accessor {} is empty", accessor.index())));
+        }
+        let view = accessor.view();
+        let stride = view.stride().unwrap_or(accessor.size());
+        let end = stride
+            .checked_mul(accessor.count() - 1)
+            .and_then(|x| x.checked_add(accessor.offset()))
+            .and_then(|x| x.checked_add(accessor.size()));
+        match end {
+            Some(end) if stride >= accessor.size() && end <= view.length() => {}
+            _ => return Err(LoadError::decode(path, format!("accessor {} is out of range", accessor.index()))),
+        }
+    }
+    Ok(())
+}
+
+/// Iterates over the elements of an accessor, checking first that they have
+/// the size of `T`.
+fn accessor_iter<'a, T: Copy, S: Source>(
+    accessor: gltf::Accessor<'a>,
+    buffers: &'a S,
+    path: &Path,
+) -> Result<AccessorIter<'a, T>, LoadError> {
+    if accessor.size() != mem::size_of::<T>() {
+        return Err(LoadError::decode(path, format!("accessor {} has an unexpected type", accessor.index())));
+    }
+    Ok(AccessorIter::new(accessor, buffers))
+}
+
+/// Reads the lights of the `KHR_lights_punctual` extension, which the `gltf`
+/// crate does not expose, straight from the JSON document.
+///
+/// Lights of an unknown type are `None`.
+fn parse_lights(
+    root: &serde_json::Value,
+    path: &Path,
+) -> Result<(Vec<Option<LightDef>>, HashMap<GltfNodeIndex, usize>), LoadError> {
+    use serde_json::Value;
+    const EXTENSION: &str = "KHR_lights_punctual";
+
+    let number = |value: &Value, key: &str, default: f32| value.get(key).and_then(Value::as_f64).map_or(default, |x| x as f32);
+
+    let mut lights = Vec::new();
+    let definitions = root.get("extensions")
+        .and_then(|extensions| extensions.get(EXTENSION))
+        .and_then(|extension| extension.get("lights"))
+        .and_then(Value::as_array);
+    for light in definitions.into_iter().flat_map(|array| array.iter()) {
+        let kind = match light.get("type").and_then(Value::as_str) {
+            Some("directional") => LightKind::Directional,
+            Some("point") => LightKind::Point,
+            Some("spot") => {
+                let spot = light.get("spot").cloned().unwrap_or(Value::Null);
+                LightKind::Spot {
+                    inner_cone_angle: number(&spot, "innerConeAngle", 0.0),
+                    outer_cone_angle: number(&spot, "outerConeAngle", f32::consts::FRAC_PI_4),
+                }
+            }
+            other => {
+                warn!("Ignoring {} light of unknown type {:?}", EXTENSION, other);
+                lights.push(None);
+                continue;
+            }
+        };
+        let color = match light.get("color").and_then(Value::as_array) {
+            Some(c) if c.len() == 3 => {
+                let channel = |i: usize| c[i].as_f64().unwrap_or(1.0) as f32;
+                [channel(0), channel(1), channel(2)]
+            }
+            _ => [1.0; 3],
+        };
+        lights.push(Some(LightDef {
+            color,
+            intensity: number(light, "intensity", 1.0),
+            kind,
+        }));
+    }
+
+    let mut node_lights = HashMap::new();
+    let nodes = root.get("nodes").and_then(Value::as_array);
+    for (index, node) in nodes.into_iter().flat_map(|array| array.iter()).enumerate() {
+        let light = node.get("extensions")
+            .and_then(|extensions| extensions.get(EXTENSION))
+            .and_then(|extension| extension.get("light"))
+            .and_then(Value::as_u64);
+        match light {
+            Some(light) if (light as usize) < lights.len() => {
+                node_lights.insert(index, light as usize);
+            }
+            Some(light) => return Err(LoadError::decode(path, format!("node {} refers to missing light {}", index, light))),
+            None => {}
+        }
+    }
+
+    Ok((lights, node_lights))
+}
+
+/// Reads the `KHR_texture_transform` extensions of the material textures,
+/// which the `gltf` crate does not expose, straight from the JSON document.
+fn parse_texture_transforms(root: &serde_json::Value) -> HashMap<(usize, &'static str), TextureTransform> {
+    use serde_json::Value;
+    const EXTENSION: &str = "KHR_texture_transform";
+
+    let pair = |value: Option<&Value>, default: [f32; 2]| match value.and_then(Value::as_array) {
+        Some(v) if v.len() == 2 => [
+            v[0].as_f64().map_or(default[0], |x| x as f32),
+            v[1].as_f64().map_or(default[1], |x| x as f32),
+        ],
+        _ => default,
+    };
+
+    let mut transforms = HashMap::new();
+    let materials = root.get("materials").and_then(Value::as_array);
+    for (index, material) in materials.into_iter().flat_map(|array| array.iter()).enumerate() {
+        let pbr = material.get("pbrMetallicRoughness");
+        let textures = [
+            (BASE_COLOR_TEXTURE, pbr.and_then(|pbr| pbr.get(BASE_COLOR_TEXTURE))),
+            (METALLIC_ROUGHNESS_TEXTURE, pbr.and_then(|pbr| pbr.get(METALLIC_ROUGHNESS_TEXTURE))),
+            (NORMAL_TEXTURE, material.get(NORMAL_TEXTURE)),
+            (OCCLUSION_TEXTURE, material.get(OCCLUSION_TEXTURE)),
+            (EMISSIVE_TEXTURE, material.get(EMISSIVE_TEXTURE)),
+        ];
+        for &(slot, info) in &textures {
+            let extension = match info
+                .and_then(|info| info.get("extensions"))
+                .and_then(|extensions| extensions.get(EXTENSION))
+            {
+                Some(extension) => extension,
+                None => continue,
+            };
+            transforms.insert(
+                (index, slot),
+                TextureTransform {
+                    offset: pair(extension.get("offset"), [0.0, 0.0]),
+                    rotation: extension.get("rotation").and_then(Value::as_f64).map_or(0.0, |x| x as f32),
+                    scale: pair(extension.get("scale"), [1.0, 1.0]),
+                    tex_coord: extension.get("texCoord").and_then(Value::as_u64).map(|x| x as u32),
+                },
+            );
+        }
+    }
+    transforms
+}
+
+/// The first bytes of a GLB container.
+pub(crate) const GLB_MAGIC: &[u8] = b"glTF";
+pub(crate) const GLB_CHUNK_JSON: u32 = 0x4E4F_534A;
+pub(crate) const GLB_CHUNK_BIN: u32 = 0x004E_4942;
+
+/// Splits a GLB container into its JSON chunk and optional binary chunk.
+fn split_glb<'a>(
+    data: &'a [u8],
+    path: &Path,
+) -> Result<(&'a [u8], Option<&'a [u8]>), LoadError> {
+    let read_u32 = |offset: usize| {
+        data.get(offset .. offset + 4)
+            .map(|b| b[0] as u32 | (b[1] as u32) << 8 | (b[2] as u32) << 16 | (b[3] as u32) << 24)
+            .ok_or_else(|| LoadError::decode(path, "truncated GLB container"))
+    };
+    let version = read_u32(4)?;
+    if version != 2 {
+        return Err(LoadError::unsupported(path, format!("GLB version: {}", version)));
+    }
+    let length = cmp::min(read_u32(8)? as usize, data.len());
+    let (mut json, mut bin) = (None, None);
+    let mut offset = 12;
+    while offset + 8 <= length {
+        let chunk_length = read_u32(offset)? as usize;
+        let chunk_type = read_u32(offset + 4)?;
+        let chunk = data.get(offset + 8 .. offset + 8 + chunk_length)
+            .ok_or_else(|| LoadError::decode(path, "truncated GLB chunk"))?;
+        match chunk_type {
+            GLB_CHUNK_JSON if json.is_none() => json = Some(chunk),
+            GLB_CHUNK_BIN if bin.is_none() => bin = Some(chunk),
+            // Unknown chunks must be ignored.
+            _ => {}
         }
+        offset += 8 + chunk_length;
     }
+    let json = json.ok_or_else(|| LoadError::decode(path, "missing GLB JSON chunk"))?;
+    Ok((json, bin))
+}
+
+/// Decodes a base64 `data:` URI into its MIME type, if any, and contents.
+fn decode_data_uri<'a>(
+    uri: &'a str,
+    path: &Path,
+) -> Result<(Option<&'a str>, Vec<u8>), LoadError> {
+    let comma = uri.find(',')
+        .ok_or_else(|| LoadError::decode(path, "malformed data URI"))?;
+    let header = &uri["data:".len() .. comma];
+    if !header.ends_with(";base64") {
+        return Err(LoadError::unsupported(path, "data URI without base64 encoding"));
+    }
+    let mime_type = &header[.. header.len() - ";base64".len()];
+    let data = base64::decode(&uri[comma + 1 ..]).map_err(|e| LoadError::decode(path, e))?;
+    Ok((if mime_type.is_empty() { None } else { Some(mime_type) }, data))
+}
+
+/// Decodes a `glTF` image.
+fn decode_gltf_image(
+    source: &gltf::Image,
+    buffers: &Buffers,
+    base: &Path,
+    path: &Path,
+) -> Result<image::RgbaImage, LoadError> {
+    use image::ImageFormat::{JPEG as Jpeg, PNG as Png};
+    let format = |mime_type: &str| match mime_type {
+        "image/png" => Ok(Png),
+        "image/jpeg" => Ok(Jpeg),
+        _ => Err(LoadError::unsupported(path, format!("image MIME type: {}", mime_type))),
+    };
+    Ok(match source.data() {
+        gltf::image::Data::View { view, mime_type } => {
+            let format = format(mime_type)?;
+            let data = buffers
+                .view(&view)
+                .ok_or_else(|| LoadError::decode(path, "missing image buffer"))?;
+            image::load_from_memory_with_format(data, format)
+                .map_err(|e| LoadError::decode(path, e))?
+                .to_rgba()
+        }
+        gltf::image::Data::Uri { uri, mime_type } if uri.starts_with("data:") => {
+            let (uri_mime_type, data) = decode_data_uri(uri, path)?;
+            let image = match mime_type.or(uri_mime_type) {
+                Some(ty) => image::load_from_memory_with_format(&data, format(ty)?),
+                None => image::load_from_memory(&data),
+            };
+            image.map_err(|e| LoadError::decode(path, e))?.to_rgba()
+        }
+        gltf::image::Data::Uri { uri, mime_type } => {
+            let image_path: PathBuf = base.join(uri);
+            if let Some(ty) = mime_type {
+                let format = format(ty)?;
+                let file = fs::File::open(&image_path).map_err(|e| LoadError::io(&image_path, e))?;
+                let reader = io::BufReader::new(file);
+                image::load(reader, format)
+                    .map_err(|e| LoadError::decode(&image_path, e))?
+                    .to_rgba()
+            } else {
+                image::open(&image_path)
+                    .map_err(|e| LoadError::decode(&image_path, e))?
+                    .to_rgba()
+            }
+        }
+    })
 }
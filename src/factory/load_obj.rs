@@ -0,0 +1,638 @@
+use cgmath;
+use genmesh::{Indexer, LruIndexer, Polygon, Triangulate, Vertices};
+use material;
+use mint;
+use obj;
+use std::{cmp, fs, mem};
+
+use cgmath::{InnerSpace, Zero};
+use color::{self, Color};
+use geometry::{Geometry, Shape};
+use material::AlphaMode;
+use object::Object;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use texture::Texture;
+
+use super::{Factory, LoadError, TextureData};
+use super::loading::Upload;
+use {Group, Material, Mesh};
+
+/// Wavefront OBJ data parsed and triangulated on a loader thread, ready to
+/// be uploaded to the GPU.
+pub(crate) struct ObjData {
+    objects: Vec<ObjObject>,
+    /// Decoded textures of the materials, by path.
+    images: HashMap<PathBuf, TextureData>,
+}
+
+struct ObjObject {
+    name: String,
+    groups: Vec<ParsedGroup>,
+}
+
+struct ParsedGroup {
+    name: String,
+    geometry: Geometry,
+    material: Option<MtlMaterial>,
+}
+
+/// A group of a Wavefront OBJ file, parsed into geometry and a material.
+///
+/// See [`Factory::load_obj_groups`](struct.Factory.html#method.load_obj_groups).
+#[derive(Clone, Debug)]
+pub struct ObjGroup {
+    /// Name of the object (`o`) containing the group.
+    pub object: String,
+
+    /// Name of the group (`g`).
+    pub name: String,
+
+    /// Triangulated geometry of the group.
+    ///
+    /// Smooth normals are generated if the file has no normals for the
+    /// group.
+    pub geometry: Geometry,
+
+    /// Material of the group, mapped from its MTL material.
+    pub material: Material,
+}
+
+/// The subset of an MTL material supported by `three`.
+#[derive(Clone, Debug, Default)]
+struct MtlMaterial {
+    kd: Option<[f32; 3]>,
+    ks: Option<[f32; 3]>,
+    ke: Option<[f32; 3]>,
+    ns: Option<f32>,
+    d: Option<f32>,
+    illum: Option<u32>,
+    /// Roughness of the PBR extension.
+    pr: Option<f32>,
+    /// Metalness of the PBR extension.
+    pm: Option<f32>,
+    map_kd: Option<PathBuf>,
+    map_ke: Option<PathBuf>,
+    /// The normal map and its bump multiplier.
+    normal_map: Option<(PathBuf, f32)>,
+}
+
+impl MtlMaterial {
+    fn maps(&self) -> Vec<&PathBuf> {
+        let normal_map = self.normal_map.as_ref().map(|&(ref path, _)| path);
+        self.map_kd
+            .iter()
+            .chain(self.map_ke.iter())
+            .chain(normal_map)
+            .collect()
+    }
+}
+
+/// Reads and triangulates a Wavefront OBJ file, decoding the textures its
+/// materials reference.
+pub(crate) fn parse_obj(path: &Path) -> Result<ObjData, LoadError> {
+    let obj = obj::load::<Polygon<obj::IndexTuple>>(path).map_err(|e| LoadError::io(path, e))?;
+    let materials = parse_mtllibs(path)?;
+    let mut objects = Vec::new();
+    let mut images = HashMap::new();
+    // Textures which could not be decoded, so that they are only reported
+    // once.
+    let mut missing = HashSet::new();
+
+    for object in obj.object_iter() {
+        let mut groups = Vec::new();
+        for gr in object.group_iter() {
+            let geometry = obj_geometry(path, obj.position(), obj.texture(), obj.normal(), &gr.indices)?;
+            info!(
+                "\tgroup {} with {} vertices and {} faces",
+                gr.name,
+                geometry.base_shape.vertices.len(),
+                geometry.faces.len()
+            );
+            let material = match gr.material {
+                Some(ref mat) => match materials.get(&mat.name) {
+                    Some(material) => Some(material.clone()),
+                    None => {
+                        warn!("Material {} not found in the MTL files", mat.name);
+                        None
+                    }
+                },
+                None => None,
+            };
+            if let Some(ref material) = material {
+                // Textures are only of use to groups with texture co-ordinates.
+                if !geometry.base_shape.tex_coords.is_empty() {
+                    for map in material.maps() {
+                        if images.contains_key(map) || missing.contains(map) {
+                            continue;
+                        }
+                        match super::decode_texture(map) {
+                            Ok(image) => {
+                                images.insert(map.clone(), TextureData::new(image));
+                            }
+                            Err(e) => {
+                                warn!("Skipping texture: {}", e);
+                                missing.insert(map.clone());
+                            }
+                        }
+                    }
+                }
+            }
+            groups.push(ParsedGroup {
+                name: gr.name.clone(),
+                geometry,
+                material,
+            });
+        }
+        objects.push(ObjObject {
+            name: object.name.clone(),
+            groups,
+        });
+    }
+
+    Ok(ObjData { objects, images })
+}
+
+/// Triangulates the polygons of a group into indexed geometry.
+///
+/// Smooth normals are generated unless every vertex has a normal. Fails if a
+/// face refers to a position, texture coordinate or normal that does not
+/// exist.
+fn obj_geometry(
+    path: &Path,
+    positions: &[[f32; 3]],
+    tex_coords: &[[f32; 2]],
+    normals: &[[f32; 3]],
+    polygons: &[Polygon<obj::IndexTuple>],
+) -> Result<Geometry, LoadError> {
+    for (ipos, iuv, inor) in polygons.iter().cloned().vertices() {
+        let out_of_range = ipos >= positions.len() ||
+            iuv.map_or(false, |i| i >= tex_coords.len()) ||
+            inor.map_or(false, |i| i >= normals.len());
+        if out_of_range {
+            return Err(LoadError::decode(path, "face refers to a missing vertex attribute"));
+        }
+    }
+    let mut shape = Shape::empty();
+    let mut position_indices = Vec::new();
+    let (mut num_normals, mut num_uvs) = (0, 0);
+    let mut indices = Vec::new();
+    {
+        // separate scope for LruIndexer
+        let mut lru = LruIndexer::new(10, |_, (ipos, iuv, inor)| {
+            shape.vertices.push(positions[ipos].into());
+            position_indices.push(ipos);
+            shape.tex_coords.push(match iuv {
+                Some(i) => {
+                    num_uvs += 1;
+                    tex_coords[i].into()
+                }
+                None => [0.0, 0.0].into(),
+            });
+            shape.normals.push(match inor {
+                Some(i) => {
+                    num_normals += 1;
+                    normals[i].into()
+                }
+                None => [0.0, 0.0, 1.0].into(),
+            });
+        });
+
+        indices.extend(
+            polygons
+                .iter()
+                .cloned()
+                .triangulate()
+                .vertices()
+                .map(|tuple| lru.index(tuple) as u32),
+        );
+    }
+
+    let faces: Vec<[u32; 3]> = indices
+        .chunks(3)
+        .map(|t| [t[0], t[1], t[2]])
+        .collect();
+    if num_uvs == 0 {
+        shape.tex_coords.clear();
+    }
+    if num_normals < shape.vertices.len() {
+        shape.normals = smooth_normals(&shape.vertices, &position_indices, &faces);
+    }
+    Ok(Geometry {
+        base_shape: shape,
+        faces,
+        ..Geometry::empty()
+    })
+}
+
+/// Averages the area-weighted normals of the faces around each position.
+///
+/// Vertices are compared by OBJ position index rather than by vertex, so
+/// that seams in texture co-ordinates are smoothed over.
+fn smooth_normals(
+    vertices: &[mint::Point3<f32>],
+    position_indices: &[usize],
+    faces: &[[u32; 3]],
+) -> Vec<mint::Vector3<f32>> {
+    let mut sums = HashMap::<usize, cgmath::Vector3<f32>>::new();
+    for face in faces {
+        let a = cgmath::Point3::from(vertices[face[0] as usize]);
+        let b = cgmath::Point3::from(vertices[face[1] as usize]);
+        let c = cgmath::Point3::from(vertices[face[2] as usize]);
+        // The magnitude of the cross product is twice the face area.
+        let normal = (b - a).cross(c - a);
+        for &index in face {
+            *sums
+                .entry(position_indices[index as usize])
+                .or_insert_with(cgmath::Vector3::zero) += normal;
+        }
+    }
+    position_indices
+        .iter()
+        .map(|index| match sums.get(index) {
+            Some(sum) if sum.magnitude2() > 0.0 => sum.normalize().into(),
+            _ => [0.0, 0.0, 1.0].into(),
+        })
+        .collect()
+}
+
+/// Parses the materials of every MTL file referenced by the OBJ file.
+///
+/// MTL files which cannot be opened are skipped with a warning.
+fn parse_mtllibs(path: &Path) -> Result<HashMap<String, MtlMaterial>, LoadError> {
+    let file = fs::File::open(path).map_err(|e| LoadError::io(path, e))?;
+    let mut materials = HashMap::new();
+    for line in BufReader::new(file).lines() {
+        let line = line.map_err(|e| LoadError::io(path, e))?;
+        let mut words = line.split_whitespace();
+        if words.next() != Some("mtllib") {
+            continue;
+        }
+        for name in words {
+            let mtl_path = concat_path(path.parent(), name);
+            match fs::File::open(&mtl_path) {
+                Ok(mtl) => materials.extend(parse_mtl(&mtl_path, mtl)?),
+                Err(e) => warn!("Skipping {}: {}", mtl_path.display(), e),
+            }
+        }
+    }
+    Ok(materials)
+}
+
+/// Parses the materials of an MTL file.
+///
+/// Besides the classic keys this reads `Pr` and `Pm` of the PBR extension,
+/// and `norm`, `bump` and `map_Bump` as normal maps.
+fn parse_mtl(
+    path: &Path,
+    file: fs::File,
+) -> Result<HashMap<String, MtlMaterial>, LoadError> {
+    let mut materials = HashMap::new();
+    let mut current: Option<(String, MtlMaterial)> = None;
+    for line in BufReader::new(file).lines() {
+        let line = line.map_err(|e| LoadError::io(path, e))?;
+        let mut words = line.split_whitespace();
+        let key = match words.next() {
+            Some(key) if !key.starts_with('#') => key,
+            _ => continue,
+        };
+        let args: Vec<&str> = words.collect();
+        if key == "newmtl" {
+            if let Some((name, material)) = current.take() {
+                materials.insert(name, material);
+            }
+            current = Some((args.join(" "), MtlMaterial::default()));
+            continue;
+        }
+        let material = match current {
+            Some((_, ref mut material)) => material,
+            None => continue,
+        };
+        let number = |i: usize| -> Result<f32, LoadError> {
+            args.get(i)
+                .and_then(|arg| arg.parse().ok())
+                .ok_or_else(|| LoadError::decode(path, format!("invalid `{}` statement", key)))
+        };
+        // The green and blue components default to the red one.
+        let color = || -> Result<[f32; 3], LoadError> {
+            let r = number(0)?;
+            Ok([r, number(1).unwrap_or(r), number(2).unwrap_or(r)])
+        };
+        match key {
+            "Kd" => material.kd = Some(color()?),
+            "Ks" => material.ks = Some(color()?),
+            "Ke" => material.ke = Some(color()?),
+            "Ns" => material.ns = Some(number(0)?),
+            "d" => material.d = Some(number(0)?),
+            "Tr" => material.d = Some(1.0 - number(0)?),
+            "illum" => material.illum = Some(number(0)? as u32),
+            "Pr" => material.pr = Some(number(0)?),
+            "Pm" => material.pm = Some(number(0)?),
+            "map_Kd" => material.map_kd = parse_map(path.parent(), &args).map(|(path, _)| path),
+            "map_Ke" => material.map_ke = parse_map(path.parent(), &args).map(|(path, _)| path),
+            "norm" | "bump" | "map_Bump" | "map_bump" => material.normal_map = parse_map(path.parent(), &args),
+            _ => {}
+        }
+    }
+    if let Some((name, material)) = current {
+        materials.insert(name, material);
+    }
+    Ok(materials)
+}
+
+/// Parses the options and file name of a texture map statement, returning
+/// the path of the texture and its `-bm` bump multiplier.
+fn parse_map(
+    base: Option<&Path>,
+    args: &[&str],
+) -> Option<(PathBuf, f32)> {
+    let mut bump = 1.0;
+    let mut i = 0;
+    while i < args.len() && args[i].starts_with('-') {
+        let option = args[i];
+        i += 1;
+        match option {
+            "-bm" => {
+                if let Some(value) = args.get(i).and_then(|arg| arg.parse().ok()) {
+                    bump = value;
+                }
+                i += 1;
+            }
+            // Offset, scale and turbulence take up to three numbers.
+            "-o" | "-s" | "-t" => {
+                let mut count = 0;
+                while count < 3 && args.get(i).map_or(false, |arg| arg.parse::<f32>().is_ok()) {
+                    i += 1;
+                    count += 1;
+                }
+            }
+            "-mm" => i += 2,
+            _ => i += 1,
+        }
+    }
+    if i < args.len() {
+        Some((concat_path(base, &args[i ..].join(" ")).into_owned(), bump))
+    } else {
+        None
+    }
+}
+
+/// Maps an MTL material to the closest `three` material.
+///
+/// * `illum 0` gives a [`Basic`] material.
+/// * Materials using keys of the PBR extension, emission, transparency,
+///   normal maps or diffuse textures give a [`Pbr`] material.
+/// * Materials with a specular exponent and a specular color other than
+///   black give a [`Phong`] material, others a [`Lambert`] material.
+///
+/// [`Basic`]: ../material/struct.Basic.html
+/// [`Lambert`]: ../material/struct.Lambert.html
+/// [`Pbr`]: ../material/struct.Pbr.html
+/// [`Phong`]: ../material/struct.Phong.html
+fn mtl_material(
+    mat: &MtlMaterial,
+    textures: &HashMap<PathBuf, Texture<[f32; 4]>>,
+) -> Material {
+    let cf2u = |c: [f32; 3]| -> Color {
+        c.iter()
+            .fold(0, |u, &v| (u << 8) + cmp::min((v.max(0.0) * 255.0) as u32, 0xFF))
+    };
+    let texture = |path: Option<&PathBuf>| path.and_then(|path| textures.get(path).cloned());
+    let color = mat.kd.map_or(color::WHITE, &cf2u);
+    let alpha = mat.d.unwrap_or(1.0);
+    let base_color_map = texture(mat.map_kd.as_ref());
+    let emissive_map = texture(mat.map_ke.as_ref());
+    let normal_map = texture(mat.normal_map.as_ref().map(|&(ref path, _)| path));
+    let is_pbr = mat.pr.is_some() || mat.pm.is_some() || mat.ke.map_or(false, |ke| ke != [0.0; 3])
+        || alpha < 1.0 || base_color_map.is_some() || emissive_map.is_some() || normal_map.is_some();
+
+    if mat.illum == Some(0) {
+        material::Basic {
+            color,
+            map: base_color_map,
+        }.into()
+    } else if is_pbr {
+        material::Pbr {
+            base_color_factor: color,
+            base_color_alpha: alpha,
+            metallic_factor: mat.pm.unwrap_or(0.0),
+            // Blinn-Phong exponent to GGX roughness.
+            roughness_factor: mat.pr
+                .unwrap_or_else(|| mat.ns.map_or(1.0, |ns| (2.0 / (ns.max(0.0) + 2.0)).sqrt())),
+            emissive_factor: mat.ke.map_or(color::BLACK, &cf2u),
+            normal_scale: mat.normal_map.as_ref().map_or(1.0, |&(_, scale)| scale),
+            base_color_map,
+            normal_map,
+            emissive_map,
+            alpha_mode: if alpha < 1.0 {
+                AlphaMode::Blend
+            } else {
+                AlphaMode::Opaque
+            },
+            ..Default::default()
+        }.into()
+    } else {
+        match (mat.ns, mat.ks) {
+            (Some(_), Some(ks)) if ks == [0.0; 3] => material::Lambert {
+                color,
+                flat: false,
+            }.into(),
+            (Some(glossiness), _) if mat.illum != Some(1) => material::Phong { color, glossiness }.into(),
+            _ => material::Lambert {
+                color,
+                flat: false,
+            }.into(),
+        }
+    }
+}
+
+/// Maps the material of a parsed group, which is a white `Basic` material if
+/// it has none.
+fn group_material(
+    group: &ParsedGroup,
+    textures: &HashMap<PathBuf, Texture<[f32; 4]>>,
+) -> Material {
+    let material = match group.material {
+        Some(ref mat) => mtl_material(mat, textures),
+        None => material::Basic {
+            color: 0xFFFFFF,
+            map: None,
+        }.into(),
+    };
+    info!("\t{:?}", material);
+    material
+}
+
+/// Uploads OBJ data parsed by [`parse_obj`](fn.parse_obj.html), one texture
+/// or mesh per step.
+pub(crate) struct ObjUpload {
+    /// Textures left to upload.
+    images: Vec<(PathBuf, TextureData)>,
+    /// Groups left to upload, in reverse order, with the name of their
+    /// object.
+    groups: Vec<(String, ParsedGroup)>,
+    textures: HashMap<PathBuf, Texture<[f32; 4]>>,
+    objects: HashMap<String, Group>,
+    /// Names of the objects, including those without any groups.
+    object_names: Vec<String>,
+    meshes: Vec<Mesh>,
+    step_count: usize,
+    steps_done: usize,
+}
+
+impl ObjUpload {
+    pub(crate) fn new(data: ObjData) -> Self {
+        let images: Vec<_> = data.images.into_iter().collect();
+        let object_names = data.objects.iter().map(|object| object.name.clone()).collect();
+        let mut groups = Vec::new();
+        for object in data.objects {
+            for group in object.groups {
+                groups.push((object.name.clone(), group));
+            }
+        }
+        groups.reverse();
+        ObjUpload {
+            step_count: images.len() + groups.len() + 1,
+            steps_done: 0,
+            images,
+            groups,
+            textures: HashMap::new(),
+            objects: HashMap::new(),
+            object_names,
+            meshes: Vec::new(),
+        }
+    }
+}
+
+impl Upload for ObjUpload {
+    type Output = (HashMap<String, Group>, Vec<Mesh>);
+
+    fn step(
+        &mut self,
+        factory: &mut Factory,
+    ) -> Option<Result<Self::Output, LoadError>> {
+        self.steps_done += 1;
+        if let Some((path, texture_data)) = self.images.pop() {
+            match factory.cache_texture(path.clone(), texture_data) {
+                Ok(texture) => {
+                    self.textures.insert(path, texture);
+                    None
+                }
+                Err(error) => Some(Err(error)),
+            }
+        } else if let Some((object, group)) = self.groups.pop() {
+            let material = group_material(&group, &self.textures);
+            let mut mesh = factory.mesh(group.geometry, material);
+            let parent = self.objects
+                .entry(object)
+                .or_insert_with(|| factory.group());
+            mesh.set_parent(&*parent);
+            self.meshes.push(mesh);
+            None
+        } else {
+            for name in self.object_names.drain(..) {
+                if !self.objects.contains_key(&name) {
+                    let group = factory.group();
+                    self.objects.insert(name, group);
+                }
+            }
+            let objects = mem::replace(&mut self.objects, HashMap::new());
+            let meshes = mem::replace(&mut self.meshes, Vec::new());
+            Some(Ok((objects, meshes)))
+        }
+    }
+
+    fn progress(&self) -> f32 {
+        self.steps_done as f32 / self.step_count as f32
+    }
+}
+
+impl super::Factory {
+    /// Uploads the textures of OBJ data parsed by
+    /// [`parse_obj`](fn.parse_obj.html) and maps the materials of its groups.
+    fn build_obj_groups(
+        &mut self,
+        data: ObjData,
+    ) -> Result<Vec<ObjGroup>, LoadError> {
+        let mut textures = HashMap::new();
+        for (path, texture_data) in data.images {
+            let texture = self.cache_texture(path.clone(), texture_data)?;
+            textures.insert(path, texture);
+        }
+        let mut groups = Vec::new();
+        for object in data.objects {
+            for gr in object.groups {
+                groups.push(ObjGroup {
+                    object: object.name.clone(),
+                    material: group_material(&gr, &textures),
+                    name: gr.name,
+                    geometry: gr.geometry,
+                });
+            }
+        }
+        Ok(groups)
+    }
+
+    /// Load mesh from Wavefront Obj format.
+    ///
+    /// Each object of the file gives a [`Group`](struct.Group.html), to
+    /// which the meshes of its groups are attached. See
+    /// [`load_obj_groups`](#method.load_obj_groups) for how geometry and
+    /// materials are read.
+    ///
+    /// #### Note
+    /// You must store `Vec<Mesh>` somewhere to keep them alive.
+    pub fn load_obj(
+        &mut self,
+        path_str: &str,
+    ) -> Result<(HashMap<String, Group>, Vec<Mesh>), LoadError> {
+        info!("Loading {}", path_str);
+        let data = parse_obj(Path::new(path_str))?;
+        self.upload_now(ObjUpload::new(data))
+    }
+
+    /// Loads the groups of a Wavefront OBJ file as geometry and materials,
+    /// without creating any meshes, so that they may be processed first.
+    ///
+    /// Polygons are triangulated, and indices are 32-bit so that groups may
+    /// have any number of vertices. Groups without normals are given smooth
+    /// normals.
+    ///
+    /// MTL materials are mapped to `Basic` for `illum 0`, to `Pbr` when
+    /// they use the `Pr`/`Pm` keys of the PBR extension, emission (`Ke`,
+    /// `map_Ke`), transparency (`d`, `Tr`), normal maps (`norm`, `bump`,
+    /// `map_Bump`) or diffuse textures (`map_Kd`), and otherwise to `Phong`
+    /// when they have a specular exponent (`Ns`) and a specular color (`Ks`)
+    /// other than black, or to `Lambert`. Ambient colors are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # let mut window = three::Window::new("");
+    /// for group in window.factory.load_obj_groups("model.obj").unwrap() {
+    ///     let mut geometry = group.geometry;
+    ///     geometry.base_shape.tex_coords.clear();
+    ///     let _mesh = window.factory.mesh(geometry, group.material);
+    /// }
+    /// ```
+    pub fn load_obj_groups(
+        &mut self,
+        path_str: &str,
+    ) -> Result<Vec<ObjGroup>, LoadError> {
+        info!("Loading {}", path_str);
+        let data = parse_obj(Path::new(path_str))?;
+        self.build_obj_groups(data)
+    }
+}
+
+fn concat_path<'a>(
+    base: Option<&Path>,
+    name: &'a str,
+) -> Cow<'a, Path> {
+    match base {
+        Some(base) => Cow::Owned(base.join(name)),
+        None => Cow::Borrowed(Path::new(name)),
+    }
+}
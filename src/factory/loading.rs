@@ -0,0 +1,422 @@
+use std::{mem, panic, thread};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use audio::Clip;
+use super::{load_gltf, load_obj, Factory, Gltf, LoadError};
+use {Group, Mesh, Texture};
+
+/// Fraction of the progress of a load attributed to the work done on the
+/// loader thread. The rest is attributed to uploading the data to the GPU.
+const WORKER_PROGRESS: f32 = 0.9;
+
+/// Number of loader threads of each `Factory`.
+const LOADER_THREADS: usize = 4;
+
+/// Handle to an asset being loaded in the background.
+///
+/// Created by the `Factory::load_*_async` functions. Files are read and
+/// decoded on a small pool of loader threads; the result is then uploaded to the GPU on
+/// the main thread by [`Factory::process_loads`], which [`Window::update`]
+/// calls every frame.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # let mut window = three::Window::new("");
+/// # let camera = window.factory.perspective_camera(60.0, 0.1 .. 10.0);
+/// use three::Object;
+/// let mut loading = window.factory.load_gltf_async("Lantern.gltf");
+/// let mut gltf = None;
+/// while window.update() {
+///     if loading.is_ready() {
+///         let mut result = loading.take().unwrap().unwrap();
+///         result.group.set_parent(&window.scene);
+///         gltf = Some(result);
+///     } else if gltf.is_none() {
+///         println!("Loading: {:.0}%", 100.0 * loading.progress());
+///     }
+///     window.render(&camera);
+/// }
+/// ```
+///
+/// [`Factory::process_loads`]: struct.Factory.html#method.process_loads
+/// [`Window::update`]: window/struct.Window.html#method.update
+pub struct Loading<T> {
+    state: Rc<RefCell<State<T>>>,
+}
+
+struct State<T> {
+    progress: f32,
+    result: Option<Result<T, LoadError>>,
+}
+
+impl<T> Loading<T> {
+    /// Returns `true` if the load has completed, successfully or not, and
+    /// its result has not been taken yet.
+    pub fn is_ready(&self) -> bool {
+        self.state.borrow().result.is_some()
+    }
+
+    /// Returns the progress of the load, between `0.0` and `1.0`.
+    pub fn progress(&self) -> f32 {
+        self.state.borrow().progress
+    }
+
+    /// Takes the result of the load.
+    ///
+    /// Returns `None` if the load is still in progress or its result has
+    /// already been taken.
+    pub fn take(&mut self) -> Option<Result<T, LoadError>> {
+        self.state.borrow_mut().result.take()
+    }
+}
+
+/// Work queued for a loader thread.
+trait Task: Send {
+    fn run(self: Box<Self>);
+}
+
+impl<F: FnOnce() + Send> Task for F {
+    fn run(self: Box<Self>) {
+        (*self)()
+    }
+}
+
+/// A fixed pool of loader threads, fed by a shared queue.
+///
+/// The threads are started by the first background load, and exit once the
+/// `Loader` is dropped and the queue is drained.
+pub(crate) struct Loader {
+    sender: Option<mpsc::Sender<Box<Task>>>,
+}
+
+impl Loader {
+    pub(crate) fn new() -> Self {
+        Loader { sender: None }
+    }
+
+    /// Queues `task` to run on one of the loader threads.
+    fn execute<F: FnOnce() + Send + 'static>(
+        &mut self,
+        task: F,
+    ) {
+        let sender = self.sender.get_or_insert_with(|| {
+            let (sender, receiver) = mpsc::channel::<Box<Task>>();
+            let receiver = Arc::new(Mutex::new(receiver));
+            for i in 0 .. LOADER_THREADS {
+                let receiver = receiver.clone();
+                let spawned = thread::Builder::new()
+                    .name(format!("three-loader-{}", i))
+                    .spawn(move || loop {
+                        let task = match receiver.lock().unwrap().recv() {
+                            Ok(task) => task,
+                            Err(_) => break,
+                        };
+                        // A panicking task reports a disconnected channel to
+                        // its job, and must not take the thread down with it.
+                        let _ = panic::catch_unwind(panic::AssertUnwindSafe(move || task.run()));
+                    });
+                if let Err(e) = spawned {
+                    error!("Unable to start loader thread: {}", e);
+                }
+            }
+            sender
+        });
+        let _ = sender.send(Box::new(task));
+    }
+}
+
+/// Message sent by a loader thread.
+enum Message<D> {
+    Progress(f32),
+    Done(Result<D, LoadError>),
+}
+
+/// The finalisation of a load on the main thread, split into steps such as
+/// uploading a single texture or mesh, so that it can be spread over several
+/// frames by `Factory::process_loads`.
+pub(crate) trait Upload {
+    /// The loaded asset.
+    type Output;
+
+    /// Performs the next step, returning the result of the load after the
+    /// last one.
+    fn step(
+        &mut self,
+        factory: &mut Factory,
+    ) -> Option<Result<Self::Output, LoadError>>;
+
+    /// Returns the fraction of the steps performed so far.
+    fn progress(&self) -> f32;
+}
+
+/// An upload performed in a single step.
+pub(crate) struct Once<F>(Option<F>);
+
+impl<F> Once<F> {
+    pub(crate) fn new(finish: F) -> Self {
+        Once(Some(finish))
+    }
+}
+
+impl<T, F> Upload for Once<F>
+where
+    F: FnOnce(&mut Factory) -> Result<T, LoadError>,
+{
+    type Output = T;
+
+    fn step(
+        &mut self,
+        factory: &mut Factory,
+    ) -> Option<Result<T, LoadError>> {
+        self.0.take().map(|finish| finish(factory))
+    }
+
+    fn progress(&self) -> f32 {
+        if self.0.is_some() {
+            0.0
+        } else {
+            1.0
+        }
+    }
+}
+
+/// The time `Factory::process_loads` may spend on upload steps.
+pub(crate) struct Budget {
+    start: Instant,
+    duration: Duration,
+    steps: usize,
+}
+
+impl Budget {
+    /// Returns `true` once the time is up. At least one step is allowed, so
+    /// that loads make progress however small the budget.
+    fn is_spent(&self) -> bool {
+        self.steps > 0 && self.start.elapsed() >= self.duration
+    }
+}
+
+/// A load pending completion, driven by `Factory::process_loads`.
+pub(crate) trait PendingLoad {
+    /// Receives updates from the loader thread and, once the thread is done,
+    /// performs upload steps until the budget is spent.
+    ///
+    /// Returns `true` once the load has completed.
+    fn poll(
+        &mut self,
+        factory: &mut Factory,
+        budget: &mut Budget,
+    ) -> bool;
+}
+
+struct Job<D, U: Upload, F> {
+    path: PathBuf,
+    receiver: mpsc::Receiver<Message<D>>,
+    start_upload: Option<F>,
+    upload: Option<U>,
+    state: Rc<RefCell<State<U::Output>>>,
+}
+
+impl<D, U, F> Job<D, U, F>
+where
+    U: Upload,
+{
+    fn complete(
+        &mut self,
+        result: Result<U::Output, LoadError>,
+    ) -> bool {
+        let mut state = self.state.borrow_mut();
+        state.progress = 1.0;
+        state.result = Some(result);
+        true
+    }
+}
+
+impl<D, U, F> PendingLoad for Job<D, U, F>
+where
+    U: Upload,
+    F: FnOnce(D) -> U,
+{
+    fn poll(
+        &mut self,
+        factory: &mut Factory,
+        budget: &mut Budget,
+    ) -> bool {
+        while self.upload.is_none() {
+            match self.receiver.try_recv() {
+                Ok(Message::Progress(progress)) => {
+                    self.state.borrow_mut().progress = progress * WORKER_PROGRESS;
+                }
+                Ok(Message::Done(Ok(data))) => {
+                    let start_upload = self.start_upload.take().unwrap();
+                    self.upload = Some(start_upload(data));
+                }
+                Ok(Message::Done(Err(error))) => return self.complete(Err(error)),
+                Err(mpsc::TryRecvError::Empty) => return false,
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    let error = LoadError::decode(&self.path, "loader thread panicked");
+                    return self.complete(Err(error));
+                }
+            }
+        }
+        while !budget.is_spent() {
+            budget.steps += 1;
+            let step = {
+                let upload = self.upload.as_mut().unwrap();
+                let step = upload.step(factory);
+                let progress = WORKER_PROGRESS + (1.0 - WORKER_PROGRESS) * upload.progress();
+                self.state.borrow_mut().progress = progress;
+                step
+            };
+            if let Some(result) = step {
+                return self.complete(result);
+            }
+        }
+        false
+    }
+}
+
+impl Factory {
+    /// Runs `work` on a loader thread, then the upload returned by
+    /// `start_upload` for its output, step by step, once `process_loads`
+    /// picks it up.
+    pub(crate) fn spawn_load<D, U, W, F>(
+        &mut self,
+        path: &Path,
+        work: W,
+        start_upload: F,
+    ) -> Loading<U::Output>
+    where
+        D: Send + 'static,
+        U: Upload + 'static,
+        W: FnOnce(&Path, &Fn(f32)) -> Result<D, LoadError> + Send + 'static,
+        F: FnOnce(D) -> U + 'static,
+    {
+        let (sender, receiver) = mpsc::channel();
+        let thread_path = path.to_owned();
+        self.loader.execute(move || {
+            let progress = |value| {
+                let _ = sender.send(Message::Progress(value));
+            };
+            let result = work(&thread_path, &progress);
+            let _ = sender.send(Message::Done(result));
+        });
+        let state = Rc::new(RefCell::new(State {
+            progress: 0.0,
+            result: None,
+        }));
+        self.loads.push(Box::new(Job {
+            path: path.to_owned(),
+            receiver,
+            start_upload: Some(start_upload),
+            upload: None,
+            state: state.clone(),
+        }));
+        Loading { state }
+    }
+
+    /// Performs every step of an upload at once.
+    pub(crate) fn upload_now<U: Upload>(
+        &mut self,
+        mut upload: U,
+    ) -> Result<U::Output, LoadError> {
+        loop {
+            if let Some(result) = upload.step(self) {
+                return result;
+            }
+        }
+    }
+
+    /// Finalises the background loads whose data is ready, uploading it to
+    /// the GPU.
+    ///
+    /// Uploads are split into small steps, such as uploading a single
+    /// texture or mesh, which are performed until `budget` is spent; at least
+    /// one step is performed per call. This is called by [`Window::update`]
+    /// and needs to be called manually when rendering with [`Headless`].
+    ///
+    /// [`Window::update`]: window/struct.Window.html#method.update
+    /// [`Headless`]: headless/struct.Headless.html
+    pub fn process_loads(
+        &mut self,
+        budget: Duration,
+    ) {
+        let mut budget = Budget {
+            start: Instant::now(),
+            duration: budget,
+            steps: 0,
+        };
+        let loads = mem::replace(&mut self.loads, Vec::new());
+        let mut pending = Vec::with_capacity(loads.len());
+        for mut load in loads {
+            if budget.is_spent() || !load.poll(self, &mut budget) {
+                pending.push(load);
+            }
+        }
+        pending.extend(self.loads.drain(..));
+        self.loads = pending;
+    }
+
+    /// Loads a scene from glTF 2.0 format in the background.
+    ///
+    /// See [`load_gltf`](#method.load_gltf) and [`Loading`](struct.Loading.html).
+    pub fn load_gltf_async<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+    ) -> Loading<Gltf> {
+        info!("Loading {} in the background", path.as_ref().display());
+        let upload_path = path.as_ref().to_owned();
+        self.spawn_load(path.as_ref(), load_gltf::import_gltf, move |source| {
+            load_gltf::GltfUpload::new(&upload_path, source)
+        })
+    }
+
+    /// Loads meshes from Wavefront Obj format in the background.
+    ///
+    /// See [`load_obj`](#method.load_obj) and [`Loading`](struct.Loading.html).
+    pub fn load_obj_async<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+    ) -> Loading<(HashMap<String, Group>, Vec<Mesh>)> {
+        info!("Loading {} in the background", path.as_ref().display());
+        self.spawn_load(
+            path.as_ref(),
+            |path, _| load_obj::parse_obj(path),
+            load_obj::ObjUpload::new,
+        )
+    }
+
+    /// Loads a texture from file in the background.
+    ///
+    /// See [`load_texture`](#method.load_texture) and [`Loading`](struct.Loading.html).
+    pub fn load_texture_async<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+    ) -> Loading<Texture<[f32; 4]>> {
+        let finish_path = path.as_ref().to_owned();
+        self.spawn_load(
+            path.as_ref(),
+            |path, _| super::decode_texture(path).map(super::TextureData::new),
+            move |texture_data| Once::new(move |factory: &mut Factory| factory.cache_texture(finish_path, texture_data)),
+        )
+    }
+
+    /// Loads audio from file in the background.
+    ///
+    /// See [`load_audio`](#method.load_audio) and [`Loading`](struct.Loading.html).
+    pub fn load_audio_async<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+    ) -> Loading<Clip> {
+        self.spawn_load(
+            path.as_ref(),
+            |path, _| super::read_audio(path),
+            |data| Once::new(move |_: &mut Factory| Ok(Clip::new(data))),
+        )
+    }
+}
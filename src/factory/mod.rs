@@ -1,16 +1,25 @@
+mod error;
+mod export_gltf;
+mod export_obj;
 mod load_gltf;
+mod load_obj;
+mod loading;
+
+pub use self::error::{ExportError, LoadError};
+pub use self::load_obj::ObjGroup;
+pub use self::loading::Loading;
 
 use std::{cmp, fs, io, iter, ops};
-use std::borrow::Cow;
 use std::collections::hash_map::{Entry, HashMap};
 use std::io::Read;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 use animation;
+use bounds::Bounds;
 use camera;
 use cgmath::Vector3;
 use color;
-use genmesh::{Polygon, Triangulate};
 use gfx;
 use gfx::format::I8Norm;
 use gfx::traits::{Factory as Factory_, FactoryExt};
@@ -18,21 +27,22 @@ use image;
 use itertools::Either;
 use material;
 use mint;
-use obj;
 use render;
+use rodio;
 use scene;
 
 use audio::{AudioData, Clip, Source};
 use camera::Camera;
 use color::Color;
-use geometry::{Geometry, Shape};
+use geometry::{Geometry, Joints, Shape};
 use hub::{Hub, HubPtr, LightData, SubLight, SubNode};
-use light::{Ambient, Directional, Hemisphere, Point, ShadowMap};
+use light::{Ambient, Directional, Hemisphere, Point, ShadowMap, Spot};
 use material::Material;
 use mesh::{DynamicMesh, Mesh};
 use object::{Group, Object};
-use render::{basic_pipe, BackendFactory, BackendResources, BasicPipelineState, DynamicData, GpuData, ShadowFormat, Vertex};
+use render::{basic_pipe, BackendFactory, BackendResources, BasicPipelineState, ColorFormat, DepthFormat, CpuData, DynamicData, GpuData, MorphData, MorphTarget, RenderTarget, ShadowFormat, Vertex};
 use scene::Scene;
+use skeleton::{Bone, BoneData, InverseBindMatrix, Skeleton, SkeletonData};
 use sprite::Sprite;
 use text::{Font, Text, TextData};
 use texture::{CubeMap, CubeMapPath, FilterMethod, Sampler, Texture, WrapMode};
@@ -45,31 +55,112 @@ const QUAD: [Vertex; 4] = [
     Vertex {
         pos: [-1.0, -1.0, 0.0, 1.0],
         uv: [0.0, 0.0],
+        uv1: [0.0, 0.0],
+        color: [1.0; 4],
         normal: NORMAL_Z,
         tangent: TANGENT_X,
+        joint_indices: [0; 4],
+        joint_weights: [0.0; 4],
     },
     Vertex {
         pos: [1.0, -1.0, 0.0, 1.0],
         uv: [1.0, 0.0],
+        uv1: [0.0, 0.0],
+        color: [1.0; 4],
         normal: NORMAL_Z,
         tangent: TANGENT_X,
+        joint_indices: [0; 4],
+        joint_weights: [0.0; 4],
     },
     Vertex {
         pos: [-1.0, 1.0, 0.0, 1.0],
         uv: [0.0, 1.0],
+        uv1: [0.0, 0.0],
+        color: [1.0; 4],
         normal: NORMAL_Z,
         tangent: TANGENT_X,
+        joint_indices: [0; 4],
+        joint_weights: [0.0; 4],
     },
     Vertex {
         pos: [1.0, 1.0, 0.0, 1.0],
         uv: [1.0, 1.0],
+        uv1: [0.0, 0.0],
+        color: [1.0; 4],
         normal: NORMAL_Z,
         tangent: TANGENT_X,
+        joint_indices: [0; 4],
+        joint_weights: [0.0; 4],
     },
 ];
 
-/// Mapping writer.
-pub type MapVertices<'a> = gfx::mapping::Writer<'a, BackendResources, Vertex>;
+/// Mapping writer, see [`Factory::map_vertices`].
+///
+/// The written vertices are copied back to the CPU-side geometry of the mesh
+/// when the mapping is dropped, so that ray casting and the exporters see
+/// them.
+///
+/// [`Factory::map_vertices`]: struct.Factory.html#method.map_vertices
+pub struct MapVertices<'a> {
+    writer: gfx::mapping::Writer<'a, BackendResources, Vertex>,
+    hub: HubPtr,
+    mesh: &'a DynamicMesh,
+}
+
+impl<'a> ops::Deref for MapVertices<'a> {
+    type Target = [Vertex];
+    fn deref(&self) -> &[Vertex] {
+        &self.writer
+    }
+}
+
+impl<'a> ops::DerefMut for MapVertices<'a> {
+    fn deref_mut(&mut self) -> &mut [Vertex] {
+        &mut self.writer
+    }
+}
+
+impl<'a> Drop for MapVertices<'a> {
+    fn drop(&mut self) {
+        let mut hub = self.hub.lock().unwrap();
+        let data = match hub.get_mut(self.mesh).sub_node {
+            SubNode::Visual(_, ref mut gpu_data) => match gpu_data.cpu_data {
+                Some(ref mut data) => Arc::make_mut(data),
+                None => return,
+            },
+            _ => return,
+        };
+        let shape = &mut data.shape;
+        let i2f = |x: I8Norm| x.0 as f32 / 127.0;
+        for (i, vertex) in self.writer.iter().enumerate().take(shape.vertices.len()) {
+            shape.vertices[i] = [vertex.pos[0], vertex.pos[1], vertex.pos[2]].into();
+            // Normals and tangents are quantized on the GPU, so they are
+            // only copied back when they were changed.
+            if let Some(normal) = shape.normals.get_mut(i) {
+                let old = [f2i(normal.x), f2i(normal.y), f2i(normal.z)];
+                if old != [vertex.normal[0], vertex.normal[1], vertex.normal[2]] {
+                    *normal = [i2f(vertex.normal[0]), i2f(vertex.normal[1]), i2f(vertex.normal[2])].into();
+                }
+            }
+            if let Some(tangent) = shape.tangents.get_mut(i) {
+                let old = [f2i(tangent.x), f2i(tangent.y), f2i(tangent.z), f2i(tangent.w)];
+                if old != vertex.tangent {
+                    let t = vertex.tangent;
+                    *tangent = [i2f(t[0]), i2f(t[1]), i2f(t[2]), i2f(t[3])].into();
+                }
+            }
+            if let Some(uv) = shape.tex_coords.get_mut(i) {
+                *uv = vertex.uv.into();
+            }
+            if let Some(uv) = shape.tex_coords1.get_mut(i) {
+                *uv = vertex.uv1.into();
+            }
+            if let Some(color) = shape.colors.get_mut(i) {
+                *color = vertex.color.into();
+            }
+        }
+    }
+}
 
 /// `Factory` is used to instantiate game objects.
 pub struct Factory {
@@ -78,6 +169,9 @@ pub struct Factory {
     quad_buf: gfx::handle::Buffer<BackendResources, Vertex>,
     texture_cache: HashMap<PathBuf, Texture<[f32; 4]>>,
     default_sampler: gfx::handle::Sampler<BackendResources>,
+    loads: Vec<Box<loading::PendingLoad>>,
+    loader: loading::Loader,
+    keep_texture_pixels: bool,
 }
 
 /// Loaded glTF 2.0 returned by [`Factory::load_gltf`].
@@ -90,15 +184,101 @@ pub struct Gltf {
     /// Imported animation clips.
     pub clips: Vec<animation::Clip>,
 
+    /// Imported lights of the `KHR_lights_punctual` extension, attached to
+    /// their nodes.
+    ///
+    /// Light intensities are used as they are, hence they may need scaling
+    /// since glTF uses physical units.
+    pub lights: Vec<GltfLight>,
+
     /// Imported meshes.
     ///
     /// Must be kept alive in order to be displayed.
     pub meshes: VecMap<Vec<Mesh>>,
 
+    /// Imported skeletons, bound to the skinned meshes.
+    ///
+    /// Their bones are attached to the imported nodes, hence they are animated
+    /// by the imported clips.
+    pub skeletons: Vec<Skeleton>,
+
     /// The root nodes of the default scene.
     ///
-    /// If the glTF contained no default scene then this group will have no
-    /// children.
+    /// If the glTF contained no default scene then this is the group of the
+    /// first scene, and if it contained no scenes at all then this group will
+    /// have no children.
+    pub group: Group,
+
+    /// Every scene, in glTF scene index order.
+    ///
+    /// A node shared by several scenes is only attached to the first of them.
+    pub scenes: Vec<GltfScene>,
+
+    /// The imported nodes, indexed by glTF node index.
+    ///
+    /// Only nodes belonging to a scene are imported.
+    pub nodes: VecMap<Group>,
+
+    /// glTF node indices of the imported nodes, by node name.
+    ///
+    /// If several nodes share a name then the first of them is kept.
+    pub node_names: HashMap<String, usize>,
+}
+
+impl Gltf {
+    /// Returns the imported node with the given name.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # let mut window = three::Window::new("");
+    /// use three::Object;
+    ///
+    /// let gltf = window.factory.load_gltf("House.gltf").unwrap();
+    /// let mut door = gltf.node_by_name("Door").unwrap().clone();
+    /// door.set_position([0.0, 0.0, 1.0]);
+    /// ```
+    pub fn node_by_name(
+        &self,
+        name: &str,
+    ) -> Option<&Group> {
+        self.node_names
+            .get(name)
+            .and_then(|&index| self.nodes.get(index))
+    }
+
+    /// Returns the first scene with the given name.
+    pub fn scene_by_name(
+        &self,
+        name: &str,
+    ) -> Option<&GltfScene> {
+        self.scenes
+            .iter()
+            .find(|scene| scene.name.as_ref().map_or(false, |n| n == name))
+    }
+}
+
+/// A light imported from glTF 2.0, see [`Gltf::lights`].
+///
+/// [`Gltf::lights`]: struct.Gltf.html#structfield.lights
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum GltfLight {
+    /// A `directional` light.
+    Directional(Directional),
+    /// A `point` light.
+    Point(Point),
+    /// A `spot` light.
+    Spot(Spot),
+}
+
+/// A scene of a loaded glTF 2.0 file, see [`Gltf::scenes`].
+///
+/// [`Gltf::scenes`]: struct.Gltf.html#structfield.scenes
+pub struct GltfScene {
+    /// The name of the scene, if any.
+    pub name: Option<String>,
+
+    /// The root nodes of the scene.
     pub group: Group,
 }
 
@@ -116,9 +296,27 @@ impl Factory {
             quad_buf,
             texture_cache: HashMap::new(),
             default_sampler: default_sampler,
+            loads: Vec::new(),
+            loader: loading::Loader::new(),
+            keep_texture_pixels: false,
         }
     }
 
+    /// Sets whether textures created from now on keep a copy of their
+    /// pixels in CPU memory. Disabled by default.
+    ///
+    /// The exporters need the pixels of textures that were not loaded from
+    /// an image file, such as those embedded in glTF files or created with
+    /// [`load_texture_from_memory`](#method.load_texture_from_memory), and
+    /// skip such textures unless this is enabled. Textures loaded from a
+    /// file are read from it again when exported.
+    pub fn set_keep_texture_pixels(
+        &mut self,
+        keep: bool,
+    ) {
+        self.keep_texture_pixels = keep;
+    }
+
     /// Create new empty [`Scene`](struct.Scene.html).
     pub fn scene(&mut self) -> Scene {
         let object = self.hub.lock().unwrap().spawn_scene();
@@ -186,7 +384,10 @@ impl Factory {
         Group::new(self.hub.lock().unwrap().spawn_empty())
     }
 
-    fn mesh_vertices(shape: &Shape) -> Vec<Vertex> {
+    pub(crate) fn mesh_vertices(
+        shape: &Shape,
+        joints: &Joints,
+    ) -> Vec<Vertex> {
         let position_iter = shape.vertices.iter();
         let normal_iter = if shape.normals.is_empty() {
             Either::Left(iter::repeat(NORMAL_Z))
@@ -204,9 +405,8 @@ impl Factory {
             Either::Right(shape.tex_coords.iter().map(|uv| [uv.x, uv.y]))
         };
         let tangent_iter = if shape.tangents.is_empty() {
-            // @alteous:
-            // TODO: Generate tangents if texture co-ordinates are provided.
-            // (Use mikktspace algorithm or otherwise.)
+            // Only normal maps use tangents, which are generated for them by
+            // `generate_tangents`.
             Either::Left(iter::repeat(TANGENT_X))
         } else {
             Either::Right(
@@ -216,52 +416,171 @@ impl Factory {
                     .map(|t| [f2i(t.x), f2i(t.y), f2i(t.z), f2i(t.w)]),
             )
         };
-        izip!(position_iter, normal_iter, tangent_iter, uv_iter)
-            .map(|(position, normal, tangent, tex_coord)| {
+        let uv1_iter = if shape.tex_coords1.is_empty() {
+            Either::Left(iter::repeat([0.0, 0.0]))
+        } else {
+            Either::Right(shape.tex_coords1.iter().map(|uv| [uv.x, uv.y]))
+        };
+        let color_iter = if shape.colors.is_empty() {
+            Either::Left(iter::repeat([1.0; 4]))
+        } else {
+            Either::Right(shape.colors.iter().map(|c| [c.x, c.y, c.z, c.w]))
+        };
+        let joint_indices_iter = if joints.indices.is_empty() {
+            Either::Left(iter::repeat([0; 4]))
+        } else {
+            Either::Right(joints.indices.iter().cloned())
+        };
+        let joint_weights_iter = if joints.weights.is_empty() {
+            Either::Left(iter::repeat([0.0; 4]))
+        } else {
+            Either::Right(joints.weights.iter().cloned())
+        };
+        izip!(
+            position_iter,
+            normal_iter,
+            tangent_iter,
+            uv_iter,
+            uv1_iter,
+            color_iter,
+            joint_indices_iter,
+            joint_weights_iter
+        ).map(|(position, normal, tangent, tex_coord, tex_coord1, color, joint_indices, joint_weights)| {
                 Vertex {
                     pos: [position.x, position.y, position.z, 1.0],
                     normal: normal,
                     uv: tex_coord,
+                    uv1: tex_coord1,
+                    color,
                     tangent: tangent,
+                    joint_indices,
+                    joint_weights,
                 }
             })
             .collect()
     }
 
     /// Create new `Mesh` with desired `Geometry` and `Material`.
+    ///
+    /// Tangents are generated for geometry without any if the material is
+    /// a `Pbr` material with a normal map. See
+    /// [`Geometry::compute_tangents`](struct.Geometry.html#method.compute_tangents).
+    /// The same happens when such a material is given to the mesh later on
+    /// with [`Mesh::set_material`].
+    ///
+    /// Blend shapes with more or fewer vertices or normals than the base
+    /// shape are truncated or padded with those of the base shape, and a
+    /// warning is logged.
+    ///
+    /// [`Mesh::set_material`]: struct.Mesh.html#method.set_material
     pub fn mesh<M: Into<Material>>(
         &mut self,
-        geometry: Geometry,
+        mut geometry: Geometry,
         material: M,
     ) -> Mesh {
-        let vertices = Self::mesh_vertices(&geometry.base_shape);
+        let material = material.into();
+        pad_shapes(&mut geometry);
+        generate_tangents(&mut geometry, &material);
+        let vertices = Self::mesh_vertices(&geometry.base_shape, &geometry.joints);
         let cbuf = self.backend.create_constant_buffer(1);
-        let (vbuf, slice) = if geometry.faces.is_empty() {
+        let (vbuf, slice) = if !geometry.shapes.is_empty() {
+            // Morph targets are blended into the vertex buffer by the renderer.
+            let vbuf = self.backend
+                .create_buffer_immutable(&vertices, gfx::buffer::Role::Vertex, gfx::memory::TRANSFER_DST)
+                .unwrap();
+            let slice = if geometry.faces.is_empty() {
+                gfx::Slice::new_match_vertex_buffer(&vbuf)
+            } else {
+                let faces: &[u32] = gfx::memory::cast_slice(&geometry.faces);
+                gfx::Slice {
+                    start: 0,
+                    end: faces.len() as u32,
+                    base_vertex: 0,
+                    instances: None,
+                    buffer: self.backend.create_index_buffer(faces),
+                }
+            };
+            (vbuf, slice)
+        } else if geometry.faces.is_empty() {
             self.backend.create_vertex_buffer_with_slice(&vertices, ())
         } else {
             let faces: &[u32] = gfx::memory::cast_slice(&geometry.faces);
             self.backend
                 .create_vertex_buffer_with_slice(&vertices, faces)
         };
+        let morph = if geometry.shapes.is_empty() {
+            None
+        } else {
+            let base = &geometry.base_shape;
+            let targets = geometry
+                .morph_targets()
+                .into_iter()
+                .map(|shape| MorphTarget {
+                    positions: shape
+                        .vertices
+                        .iter()
+                        .zip(&base.vertices)
+                        .map(|(t, b)| Vector3::new(t.x - b.x, t.y - b.y, t.z - b.z))
+                        .collect(),
+                    normals: if shape.normals.is_empty() || base.normals.is_empty() {
+                        Vec::new()
+                    } else {
+                        shape
+                            .normals
+                            .iter()
+                            .zip(&base.normals)
+                            .map(|(t, b)| Vector3::new(t.x - b.x, t.y - b.y, t.z - b.z))
+                            .collect()
+                    },
+                })
+                .collect();
+            Some(MorphData {
+                upload: self.backend.create_upload_buffer(vertices.len()).unwrap(),
+                vertices: Arc::new(vertices),
+                targets: Arc::new(targets),
+                pending: None,
+                weights: Vec::new(),
+            })
+        };
+        // Morphed vertices may leave the bounds of the base shape, hence
+        // meshes with morph targets are never culled.
+        let bounds = match morph {
+            Some(_) => None,
+            None => Bounds::from_points(&geometry.base_shape.vertices),
+        };
+        let cpu_data = CpuData {
+            shape: geometry.base_shape,
+            faces: geometry.faces,
+            joints: geometry.joints,
+        };
         Mesh {
             object: self.hub.lock().unwrap().spawn_visual(
-                material.into(),
+                material,
                 GpuData {
                     slice,
                     vertices: vbuf,
                     constants: cbuf,
                     pending: None,
+                    cpu_data: Some(Arc::new(cpu_data)),
+                    bounds,
+                    skeleton: None,
+                    morph,
+                    pending_tangents: None,
                 },
             ),
         }
     }
 
     /// Create a new `DynamicMesh` with desired `Geometry` and `Material`.
+    ///
+    /// Tangents are generated as for [`mesh`](#method.mesh).
     pub fn mesh_dynamic<M: Into<Material>>(
         &mut self,
-        geometry: Geometry,
+        mut geometry: Geometry,
         material: M,
     ) -> DynamicMesh {
+        let material = material.into();
+        generate_tangents(&mut geometry, &material);
         let slice = {
             let data: &[u32] = gfx::memory::cast_slice(&geometry.faces);
             gfx::Slice {
@@ -273,7 +592,7 @@ impl Factory {
             }
         };
         let (num_vertices, vertices, upload_buf) = {
-            let data = Self::mesh_vertices(&geometry.base_shape);
+            let data = Self::mesh_vertices(&geometry.base_shape, &geometry.joints);
             let dest_buf = self.backend
                 .create_buffer_immutable(&data, gfx::buffer::Role::Vertex, gfx::memory::TRANSFER_DST)
                 .unwrap();
@@ -289,15 +608,27 @@ impl Factory {
             (data.len(), dest_buf, upload_buf)
         };
         let constants = self.backend.create_constant_buffer(1);
+        let cpu_data = CpuData {
+            shape: geometry.base_shape.clone(),
+            faces: geometry.faces.clone(),
+            joints: geometry.joints.clone(),
+        };
 
         DynamicMesh {
             object: self.hub.lock().unwrap().spawn_visual(
-                material.into(),
+                material,
                 GpuData {
                     slice,
                     vertices,
                     constants,
                     pending: None,
+                    cpu_data: Some(Arc::new(cpu_data)),
+                    // Vertices may be moved by `map_vertices`, hence
+                    // dynamic meshes are never culled.
+                    bounds: None,
+                    skeleton: None,
+                    morph: None,
+                    pending_tangents: None,
                 },
             ),
             geometry,
@@ -311,16 +642,16 @@ impl Factory {
     /// Create a `Mesh` sharing the geometry with another one.
     /// Rendering a sequence of meshes with the same geometry is faster.
     /// The material is duplicated from the template.
+    ///
+    /// Instances of meshes with morph targets get their own copy of the
+    /// vertices, so that their weights can be set independently.
     pub fn mesh_instance(
         &mut self,
         template: &Mesh,
     ) -> Mesh {
         let mut hub = self.hub.lock().unwrap();
         let gpu_data = match hub.get(&template).sub_node {
-            SubNode::Visual(_, ref gpu) => GpuData {
-                constants: self.backend.create_constant_buffer(1),
-                ..gpu.clone()
-            },
+            SubNode::Visual(_, ref gpu) => instance_gpu_data(&mut self.backend, gpu),
             _ => unreachable!(),
         };
         let material = match hub.get(&template).sub_node {
@@ -334,24 +665,76 @@ impl Factory {
 
     /// Create a `Mesh` sharing the geometry with another one but with a different material.
     /// Rendering a sequence of meshes with the same geometry is faster.
+    ///
+    /// If tangents have to be generated for a normal map of the new
+    /// material, the instance gets a copy of the geometry instead.
     pub fn mesh_instance_with_material<M: Into<Material>>(
         &mut self,
         template: &Mesh,
         material: M,
     ) -> Mesh {
+        let material = material.into();
         let mut hub = self.hub.lock().unwrap();
-        let gpu_data = match hub.get(&template).sub_node {
-            SubNode::Visual(_, ref gpu) => GpuData {
-                constants: self.backend.create_constant_buffer(1),
-                ..gpu.clone()
-            },
+        let mut gpu_data = match hub.get(&template).sub_node {
+            SubNode::Visual(_, ref gpu) => instance_gpu_data(&mut self.backend, gpu),
             _ => unreachable!(),
         };
+        if let Some(uv_set) = tangents_needed(&material, &gpu_data) {
+            upload_tangents(&mut self.backend, &mut gpu_data, uv_set);
+        }
         Mesh {
-            object: hub.spawn_visual(material.into(), gpu_data),
+            object: hub.spawn_visual(material, gpu_data),
         }
     }
 
+    /// Create a new [`Bone`], one component of a [`Skeleton`].
+    ///
+    /// Its joint index is its position in the bones given to
+    /// [`skeleton`](#method.skeleton).
+    ///
+    /// [`Bone`]: struct.Bone.html
+    /// [`Skeleton`]: struct.Skeleton.html
+    pub fn bone(
+        &mut self,
+        inverse_bind_matrix: InverseBindMatrix,
+    ) -> Bone {
+        let data = BoneData { inverse_bind_matrix };
+        let object = self.hub.lock().unwrap().spawn_bone(data);
+        Bone { object }
+    }
+
+    /// Create a new [`Skeleton`] from a set of [`Bone`] instances.
+    ///
+    /// The position of each bone in `bones` is its joint index, as referred
+    /// to by [`Joints::indices`].
+    ///
+    /// [`Bone`]: struct.Bone.html
+    /// [`Skeleton`]: struct.Skeleton.html
+    /// [`Joints::indices`]: geometry/struct.Joints.html#structfield.indices
+    pub fn skeleton(
+        &mut self,
+        bones: Vec<Bone>,
+    ) -> Skeleton {
+        let gpu_buffer = self.backend
+            .create_buffer(
+                4 * cmp::max(bones.len(), 1),
+                gfx::buffer::Role::Constant,
+                gfx::memory::Usage::Dynamic,
+                gfx::memory::SHADER_RESOURCE,
+            )
+            .expect("Failed to create joint buffer");
+        let gpu_buffer_view = self.backend
+            .view_buffer_as_shader_resource(&gpu_buffer)
+            .expect("Failed to create joint buffer view");
+        let data = SkeletonData {
+            bones,
+            gpu_buffer,
+            gpu_buffer_view,
+        };
+        let object = self.hub.lock().unwrap().spawn_skeleton(data);
+        Skeleton { object }
+    }
+
     /// Create new sprite from `Material`.
     pub fn sprite(
         &mut self,
@@ -364,6 +747,11 @@ impl Factory {
                 vertices: self.quad_buf.clone(),
                 constants: self.backend.create_constant_buffer(1),
                 pending: None,
+                cpu_data: None,
+                bounds: None,
+                skeleton: None,
+                morph: None,
+                pending_tangents: None,
             },
         ))
     }
@@ -427,6 +815,28 @@ impl Factory {
         }))
     }
 
+    /// Create new `SpotLight`.
+    ///
+    /// The cone angles are given in radians, measured from the axis of the
+    /// cone. The outer angle is expected to be greater than the inner one.
+    pub fn spot_light(
+        &mut self,
+        color: Color,
+        intensity: f32,
+        inner_cone_angle: f32,
+        outer_cone_angle: f32,
+    ) -> Spot {
+        Spot::new(self.hub.lock().unwrap().spawn_light(LightData {
+            color,
+            intensity,
+            sub_light: SubLight::Spot {
+                inner_cone_angle,
+                outer_cone_angle,
+            },
+            shadow: None,
+        }))
+    }
+
     /// Create a `Sampler` with default properties.
     ///
     /// The default sampler has `Clamp` as its horizontal and vertical
@@ -467,6 +877,52 @@ impl Factory {
         ShadowMap { resource, target }
     }
 
+    /// Create new offscreen `RenderTarget`.
+    ///
+    /// See [`Renderer::render_to`](struct.Renderer.html#method.render_to).
+    ///
+    /// # Examples
+    ///
+    /// Displaying the view of a security camera on a monitor.
+    ///
+    /// ```rust,no_run
+    /// # let mut window = three::Window::new("");
+    /// # let camera = window.factory.perspective_camera(60.0, 0.1 .. 10.0);
+    /// # let security_camera = window.factory.perspective_camera(60.0, 0.1 .. 10.0);
+    /// let target = window.factory.render_target(512, 512);
+    /// let monitor = window.factory.mesh(
+    ///     three::Geometry::plane(1.0, 1.0),
+    ///     three::material::Basic {
+    ///         map: Some(target.texture()),
+    ///         .. Default::default()
+    ///     },
+    /// );
+    /// while window.update() {
+    ///     window.renderer.render_to(&target, &window.scene, &security_camera);
+    ///     window.render(&camera);
+    /// }
+    /// # let _ = monitor;
+    /// ```
+    pub fn render_target(
+        &mut self,
+        width: u16,
+        height: u16,
+    ) -> RenderTarget {
+        let (_, resource, color) = self.backend
+            .create_render_target::<ColorFormat>(width, height)
+            .unwrap();
+        let depth = self.backend
+            .create_depth_stencil_view_only::<DepthFormat>(width, height)
+            .unwrap();
+        let size = [width as u32, height as u32];
+        RenderTarget {
+            color,
+            depth,
+            texture: Texture::new(resource, self.default_sampler.clone(), size),
+            size,
+        }
+    }
+
     /// Create a basic mesh pipeline using a custom shader.
     pub fn basic_pipeline<P: AsRef<Path>>(
         &mut self,
@@ -518,7 +974,11 @@ impl Factory {
         mesh: &'a mut DynamicMesh,
     ) -> MapVertices<'a> {
         self.hub.lock().unwrap().update_mesh(mesh);
-        self.backend.write_mapping(&mesh.dynamic.buffer).unwrap()
+        MapVertices {
+            writer: self.backend.write_mapping(&mesh.dynamic.buffer).unwrap(),
+            hub: self.hub.clone(),
+            mesh,
+        }
     }
 
     /// Interpolate between the shapes of a `DynamicMesh`.
@@ -527,9 +987,13 @@ impl Factory {
         mesh: &DynamicMesh,
         shapes: &[(&str, f32)],
     ) {
-        let f2i = |x: f32| I8Norm(cmp::min(cmp::max((x * 127.) as isize, -128), 127) as i8);
-
-        self.hub.lock().unwrap().update_mesh(mesh);
+        let mut hub = self.hub.lock().unwrap();
+        hub.update_mesh(mesh);
+        // Keep the CPU-side copy in sync for ray casting and exporting.
+        let mut cpu_data = match hub.get_mut(mesh).sub_node {
+            SubNode::Visual(_, ref mut gpu_data) => gpu_data.cpu_data.as_mut().map(Arc::make_mut),
+            _ => None,
+        };
         let shapes: Vec<_> = shapes
             .iter()
             .map(|&(name, k)| (&mesh.geometry.shapes[name], k))
@@ -548,51 +1012,36 @@ impl Factory {
                 let p: [f32; 3] = mesh.geometry.base_shape.vertices[i].into();
                 pos += (1.0 - ksum) * Vector3::from(p);
             }
-            let normal = if mesh.geometry.base_shape.normals.is_empty() {
-                NORMAL_Z
-            } else {
-                let n = mesh.geometry.base_shape.normals[i];
-                [f2i(n.x), f2i(n.y), f2i(n.z), I8Norm(0)]
-            };
-            mapping[i] = Vertex {
-                pos: [pos.x, pos.y, pos.z, 1.0],
-                uv: [0.0, 0.0], //TODO
-                normal,
-                tangent: TANGENT_X, // @alteous: TODO: Provide tangent.
-            };
+            // The other attributes are left as they were uploaded.
+            mapping[i].pos = [pos.x, pos.y, pos.z, 1.0];
+            if let Some(ref mut data) = cpu_data {
+                data.shape.vertices[i] = [pos.x, pos.y, pos.z].into();
+            }
         }
     }
 
     /// Load TrueTypeFont (.ttf) from file.
-    /// #### Panics
-    /// Panics if I/O operations with file fails (e.g. file not found or corrupted)
     pub fn load_font<P: AsRef<Path>>(
         &mut self,
         file_path: P,
-    ) -> Font {
+    ) -> Result<Font, LoadError> {
         use self::io::Read;
         let file_path = file_path.as_ref();
         let mut buffer = Vec::new();
-        let file = fs::File::open(&file_path).expect(&format!(
-            "Can't open font file:\nFile: {}",
-            file_path.display()
-        ));
+        let file = fs::File::open(&file_path).map_err(|e| LoadError::io(file_path, e))?;
         io::BufReader::new(file)
             .read_to_end(&mut buffer)
-            .expect(&format!(
-                "Can't read font file:\nFile: {}",
-                file_path.display()
-            ));
+            .map_err(|e| LoadError::io(file_path, e))?;
         Font::new(buffer, file_path.to_owned(), self.backend.clone())
     }
 
-    fn parse_texture_format(path: &Path) -> image::ImageFormat {
+    fn parse_texture_format(path: &Path) -> Result<image::ImageFormat, LoadError> {
         use image::ImageFormat as F;
         let extension = path.extension()
-            .expect("no extension for an image?")
+            .ok_or_else(|| LoadError::unsupported(path, "image without file extension"))?
             .to_string_lossy()
             .to_lowercase();
-        match extension.as_str() {
+        Ok(match extension.as_str() {
             "png" => F::PNG,
             "jpg" | "jpeg" => F::JPEG,
             "gif" => F::GIF,
@@ -603,57 +1052,55 @@ impl Factory {
             "bmp" => F::BMP,
             "ico" => F::ICO,
             "hdr" => F::HDR,
-            _ => panic!("Unrecognized image extension: {}", extension),
-        }
+            _ => return Err(LoadError::unsupported(path, format!("image extension: {}", extension))),
+        })
+    }
+
+    fn load_image(path: &Path) -> Result<image::DynamicImage, LoadError> {
+        let format = Factory::parse_texture_format(path)?;
+        let file = fs::File::open(path).map_err(|e| LoadError::io(path, e))?;
+        image::load(io::BufReader::new(file), format).map_err(|e| LoadError::decode(path, e))
     }
 
-    fn load_texture_impl(
+    /// Uploads an image to the GPU along with its mipmaps, keeping the
+    /// image in CPU memory if `keep_pixels` is set.
+    fn upload_texture(
         path: &Path,
+        texture_data: &TextureData,
         sampler: Sampler,
+        keep_pixels: bool,
         factory: &mut BackendFactory,
-    ) -> Texture<[f32; 4]> {
+    ) -> Result<Texture<[f32; 4]>, LoadError> {
         use gfx::texture as t;
-        let format = Factory::parse_texture_format(path);
-        let file = fs::File::open(path).unwrap_or_else(|e| panic!("Unable to open {}: {:?}", path.display(), e));
-        let img = image::load(io::BufReader::new(file), format)
-            .unwrap_or_else(|e| panic!("Unable to decode {}: {:?}", path.display(), e))
-            .flipv()
-            .to_rgba();
+        let img = &texture_data.image;
         let (width, height) = img.dimensions();
         let kind = t::Kind::D2(width as t::Size, height as t::Size, t::AaMode::Single);
-        let (_, view) = factory
-            .create_texture_immutable_u8::<gfx::format::Srgba8>(kind, &[&img])
-            .unwrap_or_else(|e| {
-                panic!(
-                    "Unable to create GPU texture for {}: {:?}",
-                    path.display(),
-                    e
-                )
-            });
-        Texture::new(view, sampler.0, [width, height])
+        let view = {
+            let mut data: Vec<&[u8]> = vec![&***img];
+            data.extend(texture_data.mipmaps.iter().map(|level| &**level));
+            let (_, view) = factory
+                .create_texture_immutable_u8::<gfx::format::Srgba8>(kind, &data)
+                .map_err(|e| LoadError::unsupported(path, format!("GPU texture: {:?}", e)))?;
+            view
+        };
+        let texture = Texture::new(view, sampler.0, [width, height]);
+        Ok(if keep_pixels {
+            texture.with_pixels(img.clone())
+        } else {
+            texture
+        })
     }
 
     fn load_cubemap_impl<P: AsRef<Path>>(
         paths: &CubeMapPath<P>,
         sampler: Sampler,
         factory: &mut BackendFactory,
-    ) -> CubeMap<[f32; 4]> {
+    ) -> Result<CubeMap<[f32; 4]>, LoadError> {
         use gfx::texture as t;
-        let images = paths
-            .as_array()
-            .iter()
-            .map(|path| {
-                let format = Factory::parse_texture_format(path.as_ref());
-                let file = fs::File::open(path).unwrap_or_else(|e| {
-                    panic!("Unable to open {}: {:?}", path.as_ref().display(), e)
-                });
-                image::load(io::BufReader::new(file), format)
-                    .unwrap_or_else(|e| {
-                        panic!("Unable to decode {}: {:?}", path.as_ref().display(), e)
-                    })
-                    .to_rgba()
-            })
-            .collect::<Vec<_>>();
+        let mut images = Vec::new();
+        for path in paths.as_array().iter() {
+            images.push(Factory::load_image(path.as_ref())?.to_rgba());
+        }
         let data: [&[u8]; 6] = [
             &images[0],
             &images[1],
@@ -666,74 +1113,39 @@ impl Factory {
         let kind = t::Kind::Cube(size as t::Size);
         let (_, view) = factory
             .create_texture_immutable_u8::<gfx::format::Srgba8>(kind, &data)
-            .unwrap_or_else(|e| {
-                panic!("Unable to create GPU texture for cubemap: {:?}", e);
-            });
-        CubeMap::new(view, sampler.0)
+            .map_err(|e| LoadError::unsupported(paths.as_array()[0].as_ref(), format!("GPU cubemap: {:?}", e)))?;
+        Ok(CubeMap::new(view, sampler.0))
     }
 
     fn request_texture<P: AsRef<Path>>(
         &mut self,
         path: P,
-    ) -> Texture<[f32; 4]> {
-        let sampler = self.default_sampler();
-        match self.texture_cache.entry(path.as_ref().to_owned()) {
-            Entry::Occupied(e) => e.get().clone(),
-            Entry::Vacant(e) => {
-                let tex = Self::load_texture_impl(path.as_ref(), sampler, &mut self.backend);
-                e.insert(tex.clone());
-                tex
-            }
+    ) -> Result<Texture<[f32; 4]>, LoadError> {
+        match self.texture_cache.get(path.as_ref()) {
+            Some(texture) => return Ok(texture.clone()),
+            None => {}
         }
+        let texture_data = TextureData::new(decode_texture(path.as_ref())?);
+        self.cache_texture(path.as_ref().to_owned(), texture_data)
     }
 
-    fn load_obj_material(
+    /// Uploads a texture decoded from `path`, unless the texture cache
+    /// already holds one for that path.
+    fn cache_texture(
         &mut self,
-        mat: &obj::Material,
-        has_normals: bool,
-        has_uv: bool,
-        obj_dir: Option<&Path>,
-    ) -> Material {
-        let cf2u = |c: [f32; 3]| {
-            c.iter()
-                .fold(0, |u, &v| (u << 8) + cmp::min((v * 255.0) as u32, 0xFF))
-        };
-        match *mat {
-            obj::Material {
-                kd: Some(color),
-                ns: Some(glossiness),
-                ..
-            } if has_normals =>
-            {
-                material::Phong {
-                    color: cf2u(color),
-                    glossiness,
-                }.into()
-            }
-            obj::Material {
-                kd: Some(color), ..
-            } if has_normals =>
-            {
-                material::Lambert {
-                    color: cf2u(color),
-                    flat: false,
-                }.into()
+        path: PathBuf,
+        texture_data: TextureData,
+    ) -> Result<Texture<[f32; 4]>, LoadError> {
+        let sampler = self.default_sampler();
+        let keep_pixels = self.keep_texture_pixels;
+        match self.texture_cache.entry(path) {
+            Entry::Occupied(e) => Ok(e.get().clone()),
+            Entry::Vacant(e) => {
+                let tex = Self::upload_texture(e.key(), &texture_data, sampler, keep_pixels, &mut self.backend)?
+                    .with_path(e.key());
+                e.insert(tex.clone());
+                Ok(tex)
             }
-            obj::Material {
-                kd: Some(color),
-                ref map_kd,
-                ..
-            } => material::Basic {
-                color: cf2u(color),
-                map: match (has_uv, map_kd) {
-                    (true, &Some(ref name)) => Some(self.request_texture(&concat_path(obj_dir, name))),
-                    _ => None,
-                },
-            }.into(),
-            _ => material::Basic {
-                color: 0xffffff,
-                map: None,
-            }.into(),
         }
     }
 
@@ -744,15 +1156,20 @@ impl Factory {
         height: u16,
         pixels: &[u8],
         sampler: Sampler,
-    ) -> Texture<[f32; 4]> {
+    ) -> Result<Texture<[f32; 4]>, LoadError> {
         use gfx::texture as t;
         let kind = t::Kind::D2(width, height, t::AaMode::Single);
         let (_, view) = self.backend
             .create_texture_immutable_u8::<gfx::format::Srgba8>(kind, &[pixels])
-            .unwrap_or_else(|e| {
-                panic!("Unable to create GPU texture from memory: {:?}", e);
-            });
-        Texture::new(view, sampler.0, [width as u32, height as u32])
+            .map_err(|e| LoadError::Gpu(format!("{:?}", e)))?;
+        let texture = Texture::new(view, sampler.0, [width as u32, height as u32]);
+        if !self.keep_texture_pixels {
+            return Ok(texture);
+        }
+        Ok(match image::RgbaImage::from_raw(width as u32, height as u32, pixels.to_vec()) {
+            Some(image) => texture.with_pixels(Arc::new(image)),
+            None => texture,
+        })
     }
 
     /// Load texture from file.
@@ -760,7 +1177,7 @@ impl Factory {
     pub fn load_texture<P: AsRef<Path>>(
         &mut self,
         path_str: P,
-    ) -> Texture<[f32; 4]> {
+    ) -> Result<Texture<[f32; 4]>, LoadError> {
         self.request_texture(path_str)
     }
 
@@ -769,136 +1186,199 @@ impl Factory {
     pub fn load_cubemap<P: AsRef<Path>>(
         &mut self,
         paths: &CubeMapPath<P>,
-    ) -> CubeMap<[f32; 4]> {
+    ) -> Result<CubeMap<[f32; 4]>, LoadError> {
         Factory::load_cubemap_impl(paths, self.default_sampler(), &mut self.backend)
     }
 
-    /// Load mesh from Wavefront Obj format.
-    /// #### Note
-    /// You must store `Vec<Mesh>` somewhere to keep them alive.
-    pub fn load_obj(
-        &mut self,
-        path_str: &str,
-    ) -> (HashMap<String, Group>, Vec<Mesh>) {
-        use genmesh::{Indexer, LruIndexer, Vertices};
-
-        info!("Loading {}", path_str);
-        let path = Path::new(path_str);
-        let path_parent = path.parent();
-        let obj = obj::load::<Polygon<obj::IndexTuple>>(path).unwrap();
-
-        let hub_ptr = self.hub.clone();
-        let mut hub = hub_ptr.lock().unwrap();
-        let mut groups = HashMap::new();
-        let mut meshes = Vec::new();
-        let mut vertices = Vec::new();
-        let mut indices = Vec::new();
-
-        for object in obj.object_iter() {
-            let group = Group::new(hub.spawn_empty());
-            for gr in object.group_iter() {
-                let (mut num_normals, mut num_uvs) = (0, 0);
-                {
-                    // separate scope for LruIndexer
-                    let f2i = |x: f32| I8Norm(cmp::min(cmp::max((x * 127.) as isize, -128), 127) as i8);
-                    vertices.clear();
-                    let mut lru = LruIndexer::new(10, |_, (ipos, iuv, inor)| {
-                        let p: [f32; 3] = obj.position()[ipos];
-                        vertices.push(Vertex {
-                            pos: [p[0], p[1], p[2], 1.0],
-                            uv: match iuv {
-                                Some(i) => {
-                                    num_uvs += 1;
-                                    obj.texture()[i]
-                                }
-                                None => [0.0, 0.0],
-                            },
-                            normal: match inor {
-                                Some(id) => {
-                                    num_normals += 1;
-                                    let n: [f32; 3] = obj.normal()[id];
-                                    [f2i(n[0]), f2i(n[1]), f2i(n[2]), I8Norm(0)]
-                                }
-                                None => [I8Norm(0), I8Norm(0), I8Norm(0x7f), I8Norm(0)],
-                            },
-                            tangent: TANGENT_X, // TODO
-                        });
-                    });
-
-                    indices.clear();
-                    indices.extend(
-                        gr.indices
-                            .iter()
-                            .cloned()
-                            .triangulate()
-                            .vertices()
-                            .map(|tuple| lru.index(tuple) as u16),
-                    );
-                };
-
-                info!(
-                    "\tmaterial {} with {} normals and {} uvs",
-                    gr.name,
-                    num_normals,
-                    num_uvs
-                );
-                let material = match gr.material {
-                    Some(ref rc_mat) => self.load_obj_material(&*rc_mat, num_normals != 0, num_uvs != 0, path_parent),
-                    None => material::Basic {
-                        color: 0xFFFFFF,
-                        map: None,
-                    }.into(),
-                };
-                info!("\t{:?}", material);
-
-                let (vbuf, slice) = self.backend
-                    .create_vertex_buffer_with_slice(&vertices, &indices[..]);
-                let cbuf = self.backend.create_constant_buffer(1);
-                let mut mesh = Mesh {
-                    object: hub.spawn_visual(
-                        material,
-                        GpuData {
-                            slice,
-                            vertices: vbuf,
-                            constants: cbuf,
-                            pending: None,
-                        },
-                    ),
-                };
-                mesh.set_parent(&group);
-                meshes.push(mesh);
-            }
+    /// Load audio from file. Supported formats are Flac, Vorbis and WAV;
+    /// other files fail with `LoadError::Decode`.
+    pub fn load_audio<P: AsRef<Path>>(
+        &self,
+        path: P,
+    ) -> Result<Clip, LoadError> {
+        read_audio(path.as_ref()).map(Clip::new)
+    }
+}
+
+/// Copies the GPU data of a mesh for a new instance sharing its geometry.
+///
+/// Meshes with morph targets have their vertices blended in place, so the
+/// instance gets its own vertex buffers, starting with the current weights.
+fn instance_gpu_data(
+    backend: &mut BackendFactory,
+    template: &GpuData,
+) -> GpuData {
+    let mut gpu_data = GpuData {
+        constants: backend.create_constant_buffer(1),
+        ..template.clone()
+    };
+    if let Some(ref mut morph) = gpu_data.morph {
+        gpu_data.vertices = backend
+            .create_buffer_immutable(&morph.vertices[..], gfx::buffer::Role::Vertex, gfx::memory::TRANSFER_DST)
+            .unwrap();
+        morph.upload = backend.create_upload_buffer(morph.vertices.len()).unwrap();
+        if morph.pending.is_none() && !morph.weights.is_empty() {
+            morph.pending = Some(morph.weights.clone());
+        }
+    }
+    gpu_data
+}
+
+/// Pads or truncates the vertices and normals of the blend shapes of
+/// `geometry` to those of its base shape, so that every morph target
+/// displaces every vertex.
+///
+/// Missing values are taken from the base shape, hence are not displaced.
+fn pad_shapes(geometry: &mut Geometry) {
+    let base = &geometry.base_shape;
+    for (name, shape) in geometry.shapes.iter_mut() {
+        if shape.vertices.len() != base.vertices.len() {
+            warn!(
+                "Blend shape {} has {} vertices instead of {}",
+                name,
+                shape.vertices.len(),
+                base.vertices.len()
+            );
+            let len = shape.vertices.len();
+            shape.vertices.truncate(base.vertices.len());
+            shape.vertices.extend(base.vertices.iter().skip(len).cloned());
+        }
+        if shape.normals.is_empty() || base.normals.is_empty() {
+            shape.normals.clear();
+        } else if shape.normals.len() != base.normals.len() {
+            warn!(
+                "Blend shape {} has {} normals instead of {}",
+                name,
+                shape.normals.len(),
+                base.normals.len()
+            );
+            let len = shape.normals.len();
+            shape.normals.truncate(base.normals.len());
+            shape.normals.extend(base.normals.iter().skip(len).cloned());
+        }
+    }
+}
 
-            groups.insert(object.name.clone(), group);
+/// Generates tangents for geometry without any if `material` has a normal
+/// map, which requires them.
+fn generate_tangents(
+    geometry: &mut Geometry,
+    material: &Material,
+) {
+    if let Material::Pbr(ref pbr) = *material {
+        if pbr.normal_map.is_some() && geometry.base_shape.tangents.is_empty() {
+            geometry.compute_tangents_for_set(pbr.uv_sets.normal);
         }
+    }
+}
 
-        (groups, meshes)
+/// Returns the texture co-ordinate set to generate tangents from, if
+/// `material` has a normal map and the mesh of `gpu_data` has no tangents.
+pub(crate) fn tangents_needed(
+    material: &Material,
+    gpu_data: &GpuData,
+) -> Option<u32> {
+    match *material {
+        Material::Pbr(ref pbr) if pbr.normal_map.is_some() => match gpu_data.cpu_data {
+            Some(ref data) if data.shape.tangents.is_empty() => Some(pbr.uv_sets.normal),
+            _ => None,
+        },
+        _ => None,
     }
+}
 
-    /// Load audio from file. Supported formats are Flac, Vorbis and WAV.
-    pub fn load_audio<P: AsRef<Path>>(
-        &self,
-        path: P,
-    ) -> Clip {
-        let mut buffer = Vec::new();
-        let mut file = fs::File::open(&path).expect(&format!(
-            "Can't open audio file:\nFile: {}",
-            path.as_ref().display()
-        ));
-        file.read_to_end(&mut buffer).expect(&format!(
-            "Can't read audio file:\nFile: {}",
-            path.as_ref().display()
-        ));
-        Clip::new(buffer)
+/// Generates tangents for a mesh given a normal mapped material after it was
+/// created, re-uploading its vertices and indices as vertices may be split.
+///
+/// Meshes whose vertex buffer is updated by copies, that is dynamic meshes
+/// and meshes with morph targets, cannot have their vertices replaced.
+pub(crate) fn upload_tangents(
+    backend: &mut BackendFactory,
+    gpu_data: &mut GpuData,
+    uv_set: u32,
+) {
+    if gpu_data.morph.is_some() || gpu_data.vertices.get_info().bind.contains(gfx::memory::TRANSFER_DST) {
+        warn!("Normal map given to a dynamic or morphed mesh without tangents, compute them with `Geometry::compute_tangents`");
+        return;
+    }
+    let cpu_data = match gpu_data.cpu_data {
+        Some(ref data) => data.clone(),
+        None => return,
+    };
+    let mut geometry = Geometry {
+        base_shape: cpu_data.shape.clone(),
+        faces: cpu_data.faces.clone(),
+        joints: cpu_data.joints.clone(),
+        ..Geometry::empty()
+    };
+    geometry.compute_tangents_for_set(uv_set);
+    if geometry.base_shape.tangents.is_empty() {
+        warn!("Normal map given to a mesh without normals or texture co-ordinates to generate tangents from");
+        return;
     }
+    let vertices = Factory::mesh_vertices(&geometry.base_shape, &geometry.joints);
+    let (vbuf, slice) = if geometry.faces.is_empty() {
+        backend.create_vertex_buffer_with_slice(&vertices, ())
+    } else {
+        let faces: &[u32] = gfx::memory::cast_slice(&geometry.faces);
+        backend.create_vertex_buffer_with_slice(&vertices, faces)
+    };
+    gpu_data.vertices = vbuf;
+    gpu_data.slice = slice;
+    gpu_data.cpu_data = Some(Arc::new(CpuData {
+        shape: geometry.base_shape,
+        faces: geometry.faces,
+        joints: geometry.joints,
+    }));
+}
+
+/// Decodes an image file into texture data, flipped to match the texture
+/// co-ordinates used by `three`.
+fn decode_texture(path: &Path) -> Result<image::RgbaImage, LoadError> {
+    Ok(Factory::load_image(path)?.flipv().to_rgba())
 }
 
-fn concat_path<'a>(
-    base: Option<&Path>,
-    name: &'a str,
-) -> Cow<'a, Path> {
-    match base {
-        Some(base) => Cow::Owned(base.join(name)),
-        None => Cow::Borrowed(Path::new(name)),
+/// An image decoded on a loader thread along with its mipmap chain, ready
+/// to be uploaded to the GPU.
+pub(crate) struct TextureData {
+    image: Arc<image::RgbaImage>,
+    /// Mipmap levels, from half the size of the image down to a single
+    /// texel.
+    mipmaps: Vec<image::RgbaImage>,
+}
+
+impl TextureData {
+    /// Generates the mipmap chain of an image.
+    ///
+    /// Resizing is expensive, so this should be called off the main thread
+    /// when loading in the background.
+    pub(crate) fn new(image: image::RgbaImage) -> Self {
+        let mut mipmaps: Vec<image::RgbaImage> = Vec::new();
+        let (mut width, mut height) = image.dimensions();
+        while width > 1 || height > 1 {
+            width = cmp::max(width / 2, 1);
+            height = cmp::max(height / 2, 1);
+            let level = image::imageops::resize(mipmaps.last().unwrap_or(&image), width, height, image::FilterType::Triangle);
+            mipmaps.push(level);
+        }
+        TextureData {
+            image: Arc::new(image),
+            mipmaps,
+        }
     }
 }
+
+fn read_file(path: &Path) -> Result<Vec<u8>, LoadError> {
+    let mut buffer = Vec::new();
+    let mut file = fs::File::open(path).map_err(|e| LoadError::io(path, e))?;
+    file.read_to_end(&mut buffer).map_err(|e| LoadError::io(path, e))?;
+    Ok(buffer)
+}
+
+/// Reads an audio file, checking that its format can be decoded.
+fn read_audio(path: &Path) -> Result<Vec<u8>, LoadError> {
+    let data = read_file(path)?;
+    rodio::Decoder::new(io::Cursor::new(data.clone()))
+        .map_err(|_| LoadError::decode(path, "unrecognized audio format"))?;
+    Ok(data)
+}
@@ -1,9 +1,15 @@
 //! Structures for creating and storing geometric primitives.
 
+use cgmath::{self, InnerSpace, Matrix, SquareMatrix};
 use genmesh::{EmitTriangles, Triangulate, Vertex as GenVertex};
 use genmesh::generators::{self, IndexedPolygon, SharedVertex};
 use mint;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
+use std::io::{self, Write};
+use std::{f32, iter};
+
+use bounds::Bounds;
+use mikktspace;
 
 /// A shape of geometry that is used for mesh blending.
 #[derive(Clone, Debug, Default)]
@@ -16,6 +22,11 @@ pub struct Shape {
     pub tangents: Vec<mint::Vector4<f32>>,
     /// Texture co-ordinates.
     pub tex_coords: Vec<mint::Point2<f32>>,
+    /// Second set of texture co-ordinates, such as for light maps.
+    pub tex_coords1: Vec<mint::Point2<f32>>,
+    /// Linear RGBA vertex colors, multiplied with the base color of a
+    /// [`Pbr`](../material/struct.Pbr.html) material.
+    pub colors: Vec<mint::Vector4<f32>>,
 }
 
 impl Shape {
@@ -23,6 +34,153 @@ impl Shape {
     pub fn empty() -> Self {
         Default::default()
     }
+
+    /// Writes the shape as Wavefront OBJ `v`, `vt` and `vn` elements
+    /// followed by the `f` elements of `faces`.
+    ///
+    /// Texture co-ordinates and normals are only written if there is one
+    /// for each vertex. If `faces` is empty then every three consecutive
+    /// vertices form a triangle.
+    pub(crate) fn write_obj<W: Write>(
+        &self,
+        writer: &mut W,
+        faces: &[[u32; 3]],
+        offsets: &mut ObjOffsets,
+    ) -> io::Result<()> {
+        let count = self.vertices.len();
+        let has_tex_coords = self.tex_coords.len() == count;
+        let has_normals = self.normals.len() == count;
+        for v in &self.vertices {
+            writeln!(writer, "v {} {} {}", v.x, v.y, v.z)?;
+        }
+        if has_tex_coords {
+            for uv in &self.tex_coords {
+                writeln!(writer, "vt {} {}", uv.x, uv.y)?;
+            }
+        }
+        if has_normals {
+            for n in &self.normals {
+                writeln!(writer, "vn {} {} {}", n.x, n.y, n.z)?;
+            }
+        }
+
+        {
+            // OBJ indices start at one and count every element of the file.
+            let base = *offsets;
+            let vertex = |i: u32| {
+                let v = base.vertices + i as usize + 1;
+                let vt = base.tex_coords + i as usize + 1;
+                let vn = base.normals + i as usize + 1;
+                match (has_tex_coords, has_normals) {
+                    (true, true) => format!("{}/{}/{}", v, vt, vn),
+                    (true, false) => format!("{}/{}", v, vt),
+                    (false, true) => format!("{}//{}", v, vn),
+                    (false, false) => format!("{}", v),
+                }
+            };
+            if faces.is_empty() {
+                for i in 0 .. count as u32 / 3 {
+                    writeln!(writer, "f {} {} {}", vertex(3 * i), vertex(3 * i + 1), vertex(3 * i + 2))?;
+                }
+            } else {
+                for face in faces {
+                    writeln!(writer, "f {} {} {}", vertex(face[0]), vertex(face[1]), vertex(face[2]))?;
+                }
+            }
+        }
+
+        offsets.vertices += count;
+        if has_tex_coords {
+            offsets.tex_coords += count;
+        }
+        if has_normals {
+            offsets.normals += count;
+        }
+        Ok(())
+    }
+
+    /// Returns the vertices at `selection`, where `count` is the number of
+    /// vertices of the geometry.
+    fn select(
+        &self,
+        selection: &[u32],
+        count: usize,
+    ) -> Shape {
+        Shape {
+            vertices: select(&self.vertices, selection, count),
+            normals: select(&self.normals, selection, count),
+            tangents: select(&self.tangents, selection, count),
+            tex_coords: select(&self.tex_coords, selection, count),
+            tex_coords1: select(&self.tex_coords1, selection, count),
+            colors: select(&self.colors, selection, count),
+        }
+    }
+
+    /// Appends every attribute of vertex `i` to `out`.
+    fn push_attributes(
+        &self,
+        i: usize,
+        count: usize,
+        out: &mut Vec<f32>,
+    ) {
+        if self.vertices.len() == count {
+            let v = self.vertices[i];
+            out.extend_from_slice(&[v.x, v.y, v.z]);
+        }
+        if self.normals.len() == count {
+            let n = self.normals[i];
+            out.extend_from_slice(&[n.x, n.y, n.z]);
+        }
+        if self.tangents.len() == count {
+            let t = self.tangents[i];
+            out.extend_from_slice(&[t.x, t.y, t.z, t.w]);
+        }
+        if self.tex_coords.len() == count {
+            let uv = self.tex_coords[i];
+            out.extend_from_slice(&[uv.x, uv.y]);
+        }
+        if self.tex_coords1.len() == count {
+            let uv = self.tex_coords1[i];
+            out.extend_from_slice(&[uv.x, uv.y]);
+        }
+        if self.colors.len() == count {
+            let c = self.colors[i];
+            out.extend_from_slice(&[c.x, c.y, c.z, c.w]);
+        }
+    }
+}
+
+/// How [`Geometry::compute_normals`] shades faces.
+///
+/// [`Geometry::compute_normals`]: struct.Geometry.html#method.compute_normals
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Shading {
+    /// Every face has the normal of its plane.
+    Flat,
+    /// Normals are averaged between adjacent faces.
+    Smooth,
+}
+
+/// Number of vertices, texture co-ordinates and normals already written to
+/// a Wavefront OBJ file, since OBJ indices are global to the file.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct ObjOffsets {
+    pub vertices: usize,
+    pub tex_coords: usize,
+    pub normals: usize,
+}
+
+/// Joint indices and weights of the vertices of a skinned mesh.
+///
+/// Each vertex is influenced by up to four bones of a
+/// [`Skeleton`](../struct.Skeleton.html). Vertices whose weights are all zero
+/// are not affected by the skeleton.
+#[derive(Clone, Debug, Default)]
+pub struct Joints {
+    /// Indices of the bones influencing each vertex.
+    pub indices: Vec<[i32; 4]>,
+    /// Weights of the bones influencing each vertex.
+    pub weights: Vec<[f32; 4]>,
 }
 
 /// A collection of vertices, their normals, and faces that defines the
@@ -67,9 +225,19 @@ pub struct Geometry {
     /// The original shape of geometry.
     pub base_shape: Shape,
     /// A map containing blend shapes and their names.
+    ///
+    /// For a [`Mesh`], the shapes are its morph targets, whose weights are set
+    /// with [`Mesh::set_weights`]. The targets are indexed in order of their
+    /// names, where names that are integers are compared by value; the glTF
+    /// loader names each target after its index.
+    ///
+    /// [`Mesh`]: ../struct.Mesh.html
+    /// [`Mesh::set_weights`]: ../struct.Mesh.html#method.set_weights
     pub shapes: HashMap<String, Shape>,
     /// Faces.
     pub faces: Vec<[u32; 3]>,
+    /// Joints of a skinned mesh.
+    pub joints: Joints,
 }
 
 impl Geometry {
@@ -110,6 +278,13 @@ impl Geometry {
         Default::default()
     }
 
+    /// Returns the blend shapes in morph target order.
+    pub(crate) fn morph_targets(&self) -> Vec<&Shape> {
+        let mut names: Vec<&String> = self.shapes.keys().collect();
+        names.sort_by(|a, b| (a.parse::<usize>().ok(), a).cmp(&(b.parse::<usize>().ok(), b)));
+        names.into_iter().map(|name| &self.shapes[name]).collect()
+    }
+
     /// Create `Geometry` from vector of vertices.
     ///
     /// # Examples
@@ -159,6 +334,7 @@ impl Geometry {
                 .triangulate()
                 .map(|t| [t.x as u32, t.y as u32, t.z as u32])
                 .collect(),
+            joints: Joints::default(),
         }
     }
 
@@ -288,4 +464,675 @@ impl Geometry {
             |v| v.normal.into(),
         )
     }
+
+    /// Writes the base shape and faces of the geometry in Wavefront OBJ
+    /// format.
+    ///
+    /// Texture co-ordinates and normals are written if there is one for each
+    /// vertex. Blend shapes and joints are not written.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let geometry = three::Geometry::cylinder(1.0, 1.0, 2.0, 8);
+    /// let mut obj = Vec::new();
+    /// geometry.write_obj(&mut obj).unwrap();
+    /// let obj = String::from_utf8(obj).unwrap();
+    /// assert!(obj.lines().any(|line| line.starts_with("f ")));
+    /// ```
+    pub fn write_obj<W: Write>(
+        &self,
+        mut writer: W,
+    ) -> io::Result<()> {
+        self.base_shape
+            .write_obj(&mut writer, &self.faces, &mut ObjOffsets::default())
+    }
+
+    /// Returns the faces of the geometry, where geometry without faces
+    /// forms a triangle from every three consecutive vertices.
+    pub(crate) fn triangles(&self) -> Vec<[u32; 3]> {
+        if self.faces.is_empty() {
+            let count = self.base_shape.vertices.len() as u32 / 3;
+            (0 .. count).map(|i| [3 * i, 3 * i + 1, 3 * i + 2]).collect()
+        } else {
+            self.faces.clone()
+        }
+    }
+
+    /// Generates the tangents of the base shape with the MikkTSpace
+    /// algorithm, from its positions, normals and texture co-ordinates.
+    ///
+    /// Tangents are required by the normal maps of
+    /// [`Pbr`](../material/struct.Pbr.html) materials, and are generated
+    /// automatically when a mesh with such a material is created from
+    /// geometry without tangents.
+    ///
+    /// Does nothing unless every vertex has a normal and texture
+    /// co-ordinates. Vertices shared by faces with different tangents, such
+    /// as across a mirrored seam in texture space, are split so that each
+    /// face keeps its own, which adds vertices to the geometry and changes
+    /// its faces.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let mut geometry = three::Geometry::with_vertices(vec![
+    ///     [0.0, 0.0, 0.0].into(),
+    ///     [1.0, 0.0, 0.0].into(),
+    ///     [0.0, 1.0, 0.0].into(),
+    /// ]);
+    /// geometry.base_shape.normals = vec![[0.0, 0.0, 1.0].into(); 3];
+    /// geometry.base_shape.tex_coords = vec![[0.0, 0.0].into(), [1.0, 0.0].into(), [0.0, 1.0].into()];
+    /// geometry.compute_tangents();
+    /// let tangent = geometry.base_shape.tangents[0];
+    /// assert_eq!([tangent.x, tangent.y, tangent.z, tangent.w], [1.0, 0.0, 0.0, 1.0]);
+    /// ```
+    ///
+    /// Splitting the vertices of a seam where texture space is mirrored.
+    ///
+    /// ```rust
+    /// let mut geometry = three::Geometry::with_vertices(vec![
+    ///     [0.0, 0.0, 0.0].into(),
+    ///     [1.0, 0.0, 0.0].into(),
+    ///     [0.0, 1.0, 0.0].into(),
+    ///     [-1.0, 0.0, 0.0].into(),
+    /// ]);
+    /// geometry.faces = vec![[0, 1, 2], [0, 2, 3]];
+    /// geometry.base_shape.normals = vec![[0.0, 0.0, 1.0].into(); 4];
+    /// geometry.base_shape.tex_coords = vec![
+    ///     [0.0, 0.0].into(),
+    ///     [1.0, 0.0].into(),
+    ///     [0.0, 1.0].into(),
+    ///     [1.0, 0.0].into(),
+    /// ];
+    /// geometry.compute_tangents();
+    /// assert_eq!(geometry.base_shape.vertices.len(), 6);
+    /// assert_eq!(geometry.faces, vec![[0, 1, 2], [4, 5, 3]]);
+    /// let handedness: Vec<f32> = geometry.base_shape.tangents.iter().map(|t| t.w).collect();
+    /// assert_eq!(handedness, vec![1.0, 1.0, 1.0, -1.0, -1.0, -1.0]);
+    /// ```
+    pub fn compute_tangents(&mut self) {
+        self.compute_tangents_for_set(0);
+    }
+
+    /// Generates tangents from the given set of texture co-ordinates, where
+    /// `1` selects [`Shape::tex_coords1`](struct.Shape.html#structfield.tex_coords1).
+    pub(crate) fn compute_tangents_for_set(
+        &mut self,
+        uv_set: u32,
+    ) {
+        let count = self.base_shape.vertices.len();
+        let (selection, faces, tangents) = {
+            let shape = &self.base_shape;
+            let tex_coords = if uv_set == 1 {
+                &shape.tex_coords1
+            } else {
+                &shape.tex_coords
+            };
+            if count == 0 || shape.normals.len() != count || tex_coords.len() != count {
+                return;
+            }
+            let triangles = self.triangles();
+            let corners = mikktspace::generate(&shape.vertices, &shape.normals, tex_coords, &triangles);
+
+            // Each vertex keeps the tangent of the first face using it, and
+            // is copied for every other tangent the faces give it.
+            let mut selection: Vec<u32> = (0 .. count as u32).collect();
+            let mut tangents: Vec<Option<[f32; 4]>> = vec![None; count];
+            let mut copies = HashMap::<(u32, [u32; 4]), u32>::new();
+            let mut faces = Vec::with_capacity(triangles.len());
+            for (triangle, tangent) in triangles.iter().zip(&corners) {
+                let mut face = *triangle;
+                for corner in 0 .. 3 {
+                    let i = triangle[corner];
+                    let t = tangent[corner];
+                    let current = tangents[i as usize];
+                    match current {
+                        None => tangents[i as usize] = Some(t),
+                        Some(existing) if existing == t => {}
+                        Some(_) => {
+                            let key = (i, [t[0].to_bits(), t[1].to_bits(), t[2].to_bits(), t[3].to_bits()]);
+                            face[corner] = *copies.entry(key).or_insert_with(|| {
+                                selection.push(i);
+                                tangents.push(Some(t));
+                                (selection.len() - 1) as u32
+                            });
+                        }
+                    }
+                }
+                faces.push(face);
+            }
+            let tangents: Vec<mint::Vector4<f32>> = tangents
+                .into_iter()
+                .map(|t| t.unwrap_or([1.0, 0.0, 0.0, 1.0]).into())
+                .collect();
+            (selection, faces, tangents)
+        };
+        if selection.len() > count {
+            *self = self.select_vertices(&selection, faces);
+        }
+        self.base_shape.tangents = tangents;
+    }
+
+    /// Returns the bounds of the base shape, or `None` if it has no vertices.
+    pub fn bounds(&self) -> Option<Bounds> {
+        Bounds::from_points(&self.base_shape.vertices)
+    }
+
+    /// Moves the geometry so that the center of its bounding box lies at the
+    /// origin, returning the former center.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let mut geometry = three::Geometry::with_vertices(vec![
+    ///     [1.0, 1.0, 1.0].into(),
+    ///     [3.0, 1.0, 1.0].into(),
+    ///     [1.0, 3.0, 1.0].into(),
+    /// ]);
+    /// let center = geometry.center();
+    /// assert_eq!([center.x, center.y, center.z], [2.0, 2.0, 1.0]);
+    /// let bounds = geometry.bounds().unwrap();
+    /// assert_eq!([bounds.min.x, bounds.min.y, bounds.min.z], [-1.0, -1.0, 0.0]);
+    /// ```
+    pub fn center(&mut self) -> mint::Point3<f32> {
+        let center = match self.bounds() {
+            Some(bounds) => bounds.center,
+            None => return [0.0; 3].into(),
+        };
+        for shape in iter::once(&mut self.base_shape).chain(self.shapes.values_mut()) {
+            for v in &mut shape.vertices {
+                *v = [v.x - center.x, v.y - center.y, v.z - center.z].into();
+            }
+        }
+        center
+    }
+
+    /// Transforms the geometry, including its blend shapes, by an affine
+    /// matrix.
+    ///
+    /// Normals are transformed by the inverse transpose of the matrix and
+    /// tangents by the matrix itself. If the matrix mirrors the geometry then
+    /// the winding of its faces is flipped, so that they keep facing
+    /// outwards.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let mut geometry = three::Geometry::cuboid(2.0, 2.0, 2.0);
+    /// let stretch: [[f32; 4]; 4] = [
+    ///     [2.0, 0.0, 0.0, 0.0],
+    ///     [0.0, 1.0, 0.0, 0.0],
+    ///     [0.0, 0.0, 1.0, 0.0],
+    ///     [0.0, 0.0, 0.0, 1.0],
+    /// ];
+    /// geometry.transform(stretch);
+    /// let bounds = geometry.bounds().unwrap();
+    /// assert_eq!([bounds.min.x, bounds.max.x], [-2.0, 2.0]);
+    /// ```
+    pub fn transform<M: Into<mint::ColumnMatrix4<f32>>>(
+        &mut self,
+        matrix: M,
+    ) {
+        let matrix = cgmath::Matrix4::from(matrix.into());
+        let linear = cgmath::Matrix3::from_cols(matrix.x.truncate(), matrix.y.truncate(), matrix.z.truncate());
+        let normal_matrix = linear.invert().map_or(linear, |inverse| inverse.transpose());
+        let mirrored = linear.determinant() < 0.0;
+        let unit = |v: cgmath::Vector3<f32>| if v.magnitude2() > 0.0 { v.normalize() } else { v };
+
+        for shape in iter::once(&mut self.base_shape).chain(self.shapes.values_mut()) {
+            for v in &mut shape.vertices {
+                let p = matrix * cgmath::Vector4::new(v.x, v.y, v.z, 1.0);
+                *v = [p.x / p.w, p.y / p.w, p.z / p.w].into();
+            }
+            for n in &mut shape.normals {
+                *n = unit(normal_matrix * cgmath::Vector3::from(*n)).into();
+            }
+            for t in &mut shape.tangents {
+                let d = unit(linear * cgmath::Vector3::new(t.x, t.y, t.z));
+                // Mirroring reverses the handedness of the tangent space.
+                let w = if mirrored { -t.w } else { t.w };
+                *t = [d.x, d.y, d.z, w].into();
+            }
+        }
+        if mirrored {
+            self.flip_winding();
+        }
+    }
+
+    /// Reverses the winding order of the faces, which makes them face the
+    /// other way. Normals are left unchanged.
+    ///
+    /// Geometry without faces is given one face per three vertices first.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let mut geometry = three::Geometry::with_vertices(vec![
+    ///     [0.0, 0.0, 0.0].into(),
+    ///     [1.0, 0.0, 0.0].into(),
+    ///     [0.0, 1.0, 0.0].into(),
+    /// ]);
+    /// geometry.flip_winding();
+    /// assert_eq!(geometry.faces, vec![[0, 2, 1]]);
+    /// ```
+    pub fn flip_winding(&mut self) {
+        self.faces = self.triangles();
+        for face in &mut self.faces {
+            face.swap(1, 2);
+        }
+    }
+
+    /// Computes the normals of the base shape from its faces.
+    ///
+    /// With [`Shading::Smooth`], the normal of a vertex averages the
+    /// area-weighted normals of the faces around its position whose angle
+    /// to the face of the vertex is at most `angle_threshold` degrees, so
+    /// that sharp edges stay sharp. With [`Shading::Flat`], every face
+    /// takes the normal of its plane and `angle_threshold` is unused.
+    ///
+    /// Vertices shared by faces which end up with different normals are
+    /// split, and vertices not used by any face are removed. Normals of
+    /// blend shapes are not recomputed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use three::geometry::Shading;
+    ///
+    /// // Two faces folded by about 55 degrees along their shared edge.
+    /// let mut geometry = three::Geometry {
+    ///     base_shape: three::geometry::Shape {
+    ///         vertices: vec![
+    ///             [0.0, 0.0, 0.0].into(),
+    ///             [1.0, 0.0, 0.0].into(),
+    ///             [1.0, 1.0, 0.0].into(),
+    ///             [0.0, 1.0, 1.0].into(),
+    ///         ],
+    ///         .. three::geometry::Shape::empty()
+    ///     },
+    ///     faces: vec![[0, 1, 2], [0, 2, 3]],
+    ///     .. three::Geometry::empty()
+    /// };
+    /// let mut flat = geometry.clone();
+    /// flat.compute_normals(Shading::Flat, 0.0);
+    /// // The vertices of the shared edge are split.
+    /// assert_eq!(flat.base_shape.vertices.len(), 6);
+    /// geometry.compute_normals(Shading::Smooth, 60.0);
+    /// assert_eq!(geometry.base_shape.vertices.len(), 4);
+    ///
+    /// // The apex of a pyramid is shared by five faces.
+    /// let mut pyramid = three::Geometry {
+    ///     base_shape: three::geometry::Shape {
+    ///         vertices: vec![
+    ///             [0.1, 0.2, 1.0].into(),
+    ///             [1.0, 0.0, 0.0].into(),
+    ///             [0.3, 0.9, 0.0].into(),
+    ///             [-0.8, 0.6, 0.0].into(),
+    ///             [-0.7, -0.7, 0.0].into(),
+    ///             [0.4, -0.9, 0.0].into(),
+    ///         ],
+    ///         .. three::geometry::Shape::empty()
+    ///     },
+    ///     faces: vec![[0, 1, 2], [0, 2, 3], [0, 3, 4], [0, 4, 5], [0, 5, 1]],
+    ///     .. three::Geometry::empty()
+    /// };
+    /// pyramid.compute_normals(Shading::Smooth, 120.0);
+    /// assert_eq!(pyramid.base_shape.vertices.len(), 6);
+    /// ```
+    ///
+    /// [`Shading::Flat`]: enum.Shading.html#variant.Flat
+    /// [`Shading::Smooth`]: enum.Shading.html#variant.Smooth
+    pub fn compute_normals(
+        &mut self,
+        shading: Shading,
+        angle_threshold: f32,
+    ) {
+        let (selection, faces, normals) = {
+            let faces = self.triangles();
+            let vertices = &self.base_shape.vertices;
+            let position = |i: u32| cgmath::Point3::from(vertices[i as usize]);
+            let key = |i: u32| {
+                let p = vertices[i as usize];
+                [p.x.to_bits(), p.y.to_bits(), p.z.to_bits()]
+            };
+            // Face normals, whose magnitudes are twice the face areas.
+            let face_normals: Vec<cgmath::Vector3<f32>> = faces
+                .iter()
+                .map(|f| (position(f[1]) - position(f[0])).cross(position(f[2]) - position(f[0])))
+                .collect();
+            let unit_normals: Vec<cgmath::Vector3<f32>> = face_normals
+                .iter()
+                .map(|&n| if n.magnitude2() > 0.0 { n.normalize() } else { n })
+                .collect();
+            // Faces around each position, since vertices at the same position
+            // may differ by their other attributes.
+            let mut adjacent = HashMap::<[u32; 3], Vec<usize>>::new();
+            for (f, face) in faces.iter().enumerate() {
+                for &i in face {
+                    let around = adjacent.entry(key(i)).or_insert_with(Vec::new);
+                    if around.last() != Some(&f) {
+                        around.push(f);
+                    }
+                }
+            }
+            let min_cos = match shading {
+                Shading::Flat => f32::INFINITY,
+                Shading::Smooth => angle_threshold.to_radians().cos(),
+            };
+
+            let mut selection = Vec::new();
+            let mut normals: Vec<mint::Vector3<f32>> = Vec::new();
+            let mut indices = HashMap::<(u32, [u32; 3]), u32>::new();
+            let mut new_faces = Vec::with_capacity(faces.len());
+            for (f, face) in faces.iter().enumerate() {
+                let mut new_face = [0; 3];
+                for (corner, &i) in face.iter().enumerate() {
+                    // The faces are summed in the same order for every
+                    // corner at this position, so that corners agreeing on
+                    // their faces get bit-identical normals and stay shared.
+                    let mut sum = cgmath::Vector3::new(0.0, 0.0, 0.0);
+                    for &g in &adjacent[&key(i)] {
+                        if g == f || unit_normals[f].dot(unit_normals[g]) >= min_cos {
+                            sum += face_normals[g];
+                        }
+                    }
+                    let normal = if sum.magnitude2() > 0.0 {
+                        sum.normalize()
+                    } else {
+                        cgmath::Vector3::unit_z()
+                    };
+                    let normal_key = [normal.x.to_bits(), normal.y.to_bits(), normal.z.to_bits()];
+                    new_face[corner] = *indices.entry((i, normal_key)).or_insert_with(|| {
+                        selection.push(i);
+                        normals.push(normal.into());
+                        (selection.len() - 1) as u32
+                    });
+                }
+                new_faces.push(new_face);
+            }
+            (selection, new_faces, normals)
+        };
+        *self = self.select_vertices(&selection, faces);
+        self.base_shape.normals = normals;
+    }
+
+    /// Merges vertices whose attributes all differ by at most `epsilon`,
+    /// removing the faces that collapse as a result.
+    ///
+    /// Geometry without faces is given one face per three vertices first.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let mut geometry = three::Geometry::with_vertices(vec![
+    ///     [0.0, 0.0, 0.0].into(),
+    ///     [1.0, 0.0, 0.0].into(),
+    ///     [0.0, 1.0, 0.0].into(),
+    ///     [1.0, 0.0, 0.0].into(),
+    ///     [1.0, 1.0, 0.0].into(),
+    ///     [0.0, 1.0, 0.0001].into(),
+    /// ]);
+    /// geometry.weld_vertices(0.001);
+    /// assert_eq!(geometry.base_shape.vertices.len(), 4);
+    /// assert_eq!(geometry.faces, vec![[0, 1, 2], [1, 3, 2]]);
+    /// ```
+    pub fn weld_vertices(
+        &mut self,
+        epsilon: f32,
+    ) {
+        let (selection, faces) = {
+            let count = self.base_shape.vertices.len();
+            let attributes: Vec<Vec<f32>> = (0 .. count).map(|i| self.vertex_attributes(i)).collect();
+            // Vertices within `epsilon` of each other lie in neighbouring cells.
+            let cell_size = if epsilon > 0.0 { epsilon } else { 1.0 };
+            let mut cells = HashMap::<[i64; 3], Vec<u32>>::new();
+            let mut selection = Vec::<u32>::new();
+            let mut remap = Vec::with_capacity(count);
+            for i in 0 .. count {
+                let p = self.base_shape.vertices[i];
+                let cell = [
+                    (p.x / cell_size).floor() as i64,
+                    (p.y / cell_size).floor() as i64,
+                    (p.z / cell_size).floor() as i64,
+                ];
+                let mut found = None;
+                'search: for dx in -1 .. 2 {
+                    for dy in -1 .. 2 {
+                        for dz in -1 .. 2 {
+                            let neighbour = [cell[0] + dx, cell[1] + dy, cell[2] + dz];
+                            for &j in cells.get(&neighbour).into_iter().flat_map(|c| c.iter()) {
+                                let other = &attributes[selection[j as usize] as usize];
+                                if other.iter().zip(&attributes[i]).all(|(a, b)| (a - b).abs() <= epsilon) {
+                                    found = Some(j);
+                                    break 'search;
+                                }
+                            }
+                        }
+                    }
+                }
+                let index = match found {
+                    Some(j) => j,
+                    None => {
+                        selection.push(i as u32);
+                        let j = (selection.len() - 1) as u32;
+                        cells.entry(cell).or_insert_with(Vec::new).push(j);
+                        j
+                    }
+                };
+                remap.push(index);
+            }
+            let faces = self.triangles()
+                .into_iter()
+                .map(|f| [remap[f[0] as usize], remap[f[1] as usize], remap[f[2] as usize]])
+                .filter(|f| f[0] != f[1] && f[1] != f[2] && f[2] != f[0])
+                .collect();
+            (selection, faces)
+        };
+        *self = self.select_vertices(&selection, faces);
+    }
+
+    /// Returns indexed geometry sharing identical vertices between faces.
+    ///
+    /// See [`weld_vertices`](#method.weld_vertices).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let cube = three::Geometry::cuboid(1.0, 1.0, 1.0);
+    /// let soup = cube.non_indexed();
+    /// assert!(soup.faces.is_empty());
+    /// assert_eq!(soup.base_shape.vertices.len(), 3 * cube.faces.len());
+    /// let indexed = soup.indexed();
+    /// assert_eq!(indexed.base_shape.vertices.len(), cube.base_shape.vertices.len());
+    /// ```
+    pub fn indexed(&self) -> Geometry {
+        let mut geometry = self.clone();
+        geometry.weld_vertices(0.0);
+        geometry
+    }
+
+    /// Returns geometry without faces, where every three consecutive
+    /// vertices form a triangle.
+    ///
+    /// See [`indexed`](#method.indexed).
+    pub fn non_indexed(&self) -> Geometry {
+        let faces = self.triangles();
+        let selection: Vec<u32> = faces.iter().flat_map(|f| f.iter().cloned()).collect();
+        self.select_vertices(&selection, Vec::new())
+    }
+
+    /// Merges several geometries, each transformed by its matrix as with
+    /// [`transform`](#method.transform), into one.
+    ///
+    /// The result has every attribute any of the parts has, where parts
+    /// without it are given the defaults used for rendering. Each blend
+    /// shape keeps its positions and normals, where parts without it
+    /// contribute their base shape.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let cube = three::Geometry::cuboid(1.0, 1.0, 1.0);
+    /// let identity: [[f32; 4]; 4] = [
+    ///     [1.0, 0.0, 0.0, 0.0],
+    ///     [0.0, 1.0, 0.0, 0.0],
+    ///     [0.0, 0.0, 1.0, 0.0],
+    ///     [0.0, 0.0, 0.0, 1.0],
+    /// ];
+    /// let shifted: [[f32; 4]; 4] = [
+    ///     [1.0, 0.0, 0.0, 0.0],
+    ///     [0.0, 1.0, 0.0, 0.0],
+    ///     [0.0, 0.0, 1.0, 0.0],
+    ///     [2.0, 0.0, 0.0, 1.0],
+    /// ];
+    /// let pair = three::Geometry::merge(&[(&cube, identity.into()), (&cube, shifted.into())]);
+    /// assert_eq!(pair.base_shape.vertices.len(), 2 * cube.base_shape.vertices.len());
+    /// assert_eq!(pair.faces.len(), 2 * cube.faces.len());
+    /// let bounds = pair.bounds().unwrap();
+    /// assert_eq!([bounds.min.x, bounds.max.x], [-0.5, 2.5]);
+    /// ```
+    pub fn merge(parts: &[(&Geometry, mint::ColumnMatrix4<f32>)]) -> Geometry {
+        let any = |f: &Fn(&Geometry) -> bool| parts.iter().any(|&(geometry, _)| f(geometry));
+        let with_normals = any(&|g| !g.base_shape.normals.is_empty());
+        let with_tangents = any(&|g| !g.base_shape.tangents.is_empty());
+        let with_tex_coords = any(&|g| !g.base_shape.tex_coords.is_empty());
+        let with_tex_coords1 = any(&|g| !g.base_shape.tex_coords1.is_empty());
+        let with_colors = any(&|g| !g.base_shape.colors.is_empty());
+        let with_joints = any(&|g| !g.joints.indices.is_empty());
+        let names: BTreeSet<&String> = parts
+            .iter()
+            .flat_map(|&(geometry, _)| geometry.shapes.keys())
+            .collect();
+
+        let mut merged = Geometry::empty();
+        for &(geometry, matrix) in parts {
+            let mut part = geometry.clone();
+            part.transform(matrix);
+            let count = part.base_shape.vertices.len();
+            let offset = merged.base_shape.vertices.len() as u32;
+            {
+                let base = &mut part.base_shape;
+                if with_normals {
+                    fill(&mut base.normals, count, [0.0, 0.0, 1.0].into());
+                }
+                if with_tangents {
+                    fill(&mut base.tangents, count, [1.0, 0.0, 0.0, 1.0].into());
+                }
+                if with_tex_coords {
+                    fill(&mut base.tex_coords, count, [0.0, 0.0].into());
+                }
+                if with_tex_coords1 {
+                    fill(&mut base.tex_coords1, count, [0.0, 0.0].into());
+                }
+                if with_colors {
+                    fill(&mut base.colors, count, [1.0; 4].into());
+                }
+            }
+            if with_joints {
+                fill(&mut part.joints.indices, count, [0; 4]);
+                fill(&mut part.joints.weights, count, [0.0; 4]);
+            }
+
+            for &name in &names {
+                let shape = part.shapes.get(name).unwrap_or(&part.base_shape);
+                let target = merged.shapes.entry(name.clone()).or_insert_with(Shape::empty);
+                target.vertices.extend_from_slice(&shape.vertices);
+                if with_normals {
+                    let normals = if shape.normals.len() == count {
+                        &shape.normals
+                    } else {
+                        &part.base_shape.normals
+                    };
+                    target.normals.extend_from_slice(normals);
+                }
+            }
+            merged.faces.extend(
+                part.triangles()
+                    .into_iter()
+                    .map(|f| [f[0] + offset, f[1] + offset, f[2] + offset]),
+            );
+            let base = &mut merged.base_shape;
+            base.vertices.extend(part.base_shape.vertices);
+            base.normals.extend(part.base_shape.normals);
+            base.tangents.extend(part.base_shape.tangents);
+            base.tex_coords.extend(part.base_shape.tex_coords);
+            base.tex_coords1.extend(part.base_shape.tex_coords1);
+            base.colors.extend(part.base_shape.colors);
+            merged.joints.indices.extend(part.joints.indices);
+            merged.joints.weights.extend(part.joints.weights);
+        }
+        merged
+    }
+
+    /// Returns the geometry made of the vertices at `selection`, with the
+    /// given faces indexing into `selection`.
+    fn select_vertices(
+        &self,
+        selection: &[u32],
+        faces: Vec<[u32; 3]>,
+    ) -> Geometry {
+        let count = self.base_shape.vertices.len();
+        Geometry {
+            base_shape: self.base_shape.select(selection, count),
+            shapes: self.shapes
+                .iter()
+                .map(|(name, shape)| (name.clone(), shape.select(selection, count)))
+                .collect(),
+            faces,
+            joints: Joints {
+                indices: select(&self.joints.indices, selection, count),
+                weights: select(&self.joints.weights, selection, count),
+            },
+        }
+    }
+
+    /// Returns every attribute of vertex `i`, for comparing vertices.
+    fn vertex_attributes(
+        &self,
+        i: usize,
+    ) -> Vec<f32> {
+        let count = self.base_shape.vertices.len();
+        let mut attributes = Vec::new();
+        self.base_shape.push_attributes(i, count, &mut attributes);
+        let mut names: Vec<&String> = self.shapes.keys().collect();
+        names.sort();
+        for name in names {
+            self.shapes[name].push_attributes(i, count, &mut attributes);
+        }
+        if self.joints.indices.len() == count {
+            attributes.extend(self.joints.indices[i].iter().map(|&j| j as f32));
+        }
+        if self.joints.weights.len() == count {
+            attributes.extend_from_slice(&self.joints.weights[i]);
+        }
+        attributes
+    }
+}
+
+/// Returns the elements of `values` at `selection`, or nothing unless there
+/// is one element for each of the `count` vertices.
+fn select<T: Copy>(
+    values: &[T],
+    selection: &[u32],
+    count: usize,
+) -> Vec<T> {
+    if values.len() == count {
+        selection.iter().map(|&i| values[i as usize]).collect()
+    } else {
+        Vec::new()
+    }
+}
+
+/// Replaces `values` with `count` defaults unless there is one for each
+/// vertex.
+fn fill<T: Clone>(
+    values: &mut Vec<T>,
+    count: usize,
+    default: T,
+) {
+    if values.len() != count {
+        values.clear();
+        values.resize(count, default);
+    }
 }
@@ -0,0 +1,139 @@
+//! Rendering without a window.
+
+use glutin;
+use mint;
+use render;
+
+use camera::Camera;
+use factory::Factory;
+use render::Renderer;
+use scene::Scene;
+
+/// `Headless` is the windowless counterpart of [`Window`](struct.Window.html).
+///
+/// It owns an offscreen OpenGL context, such as one provided by OSMesa,
+/// and renders into a framebuffer of fixed size. This is useful for
+/// generating thumbnails and for running visual tests on machines without
+/// a display.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # extern crate three;
+/// # fn main() {
+/// use three::Object;
+///
+/// let mut headless = three::Headless::new(256, 256);
+/// let mut camera = headless.factory.perspective_camera(60.0, 0.1 .. 10.0);
+/// camera.set_position([0.0, 0.0, 5.0]);
+/// headless.render(&camera);
+/// # }
+/// ```
+pub struct Headless {
+    context: glutin::HeadlessContext,
+    /// See [`Renderer`](struct.Renderer.html).
+    pub renderer: Renderer,
+    /// See [`Factory`](struct.Factory.html).
+    pub factory: Factory,
+    /// See [`Scene`](struct.Scene.html).
+    pub scene: Scene,
+}
+
+#[cfg_attr(rustfmt, rustfmt_skip)]
+quick_error! {
+    #[doc = "Error encountered when creating a `Headless` renderer."]
+    #[derive(Debug)]
+    pub enum HeadlessError {
+        #[doc = "The platform is unable to provide a headless OpenGL 3.2 context."]
+        Creation(err: glutin::CreationError) {
+            description("Headless context creation error")
+            display("Unable to create headless context: {}", err)
+            cause(err)
+            from()
+        }
+
+        #[doc = "The headless context could not be made current."]
+        Context(err: glutin::ContextError) {
+            description("Headless context error")
+            display("Unable to make headless context current: {}", err)
+            cause(err)
+            from()
+        }
+    }
+}
+
+impl Headless {
+    /// Create a new offscreen rendering context with the given dimensions in pixels.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the platform is unable to provide a headless OpenGL 3.2 context.
+    /// See [`try_new`](#method.try_new) for a non-panicking alternative.
+    pub fn new(
+        width: u16,
+        height: u16,
+    ) -> Self {
+        match Headless::try_new(width, height) {
+            Ok(headless) => headless,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    /// Create a new offscreen rendering context with the given dimensions in
+    /// pixels, or return an error if no OpenGL context is available.
+    ///
+    /// # Examples
+    ///
+    /// Skipping a visual test on a machine without OpenGL.
+    ///
+    /// ```rust
+    /// let headless = match three::Headless::try_new(64, 64) {
+    ///     Ok(headless) => headless,
+    ///     Err(err) => {
+    ///         println!("Skipping: {}", err);
+    ///         return;
+    ///     }
+    /// };
+    /// # let _ = headless;
+    /// ```
+    pub fn try_new(
+        width: u16,
+        height: u16,
+    ) -> Result<Self, HeadlessError> {
+        let context = glutin::HeadlessRendererBuilder::new(width as u32, height as u32)
+            .with_gl(glutin::GlRequest::Specific(glutin::Api::OpenGl, (3, 2)))
+            .with_gl_profile(glutin::GlProfile::Core)
+            .build()?;
+        unsafe {
+            context.make_current()?;
+        }
+        let source_set = render::source::Set::default();
+        let (renderer, mut factory) = Renderer::headless(&context, width, height, &source_set);
+        let scene = factory.scene();
+        Ok(Headless {
+            context,
+            renderer,
+            factory,
+            scene,
+        })
+    }
+
+    /// Render the current scene with specific [`Camera`](struct.Camera.html).
+    pub fn render(
+        &mut self,
+        camera: &Camera,
+    ) {
+        self.renderer.render(&self.scene, camera);
+    }
+
+    /// Get the size of the offscreen framebuffer in pixels.
+    pub fn size(&self) -> mint::Vector2<f32> {
+        let size = self.renderer.size();
+        [size.0 as f32, size.1 as f32].into()
+    }
+
+    /// Returns the underlying `glutin::HeadlessContext`.
+    pub fn glutin_context(&self) -> &glutin::HeadlessContext {
+        &self.context
+    }
+}
@@ -1,17 +1,22 @@
+use animation;
 use audio::{AudioData, Operation as AudioOperation};
 use color::{self, Color};
+use factory;
 use light::{ShadowMap, ShadowProjection};
 use material::{self, Material};
 use mesh::DynamicMesh;
 use node::{NodeInternal, NodePointer};
 use object;
 use render::GpuData;
+use skeleton::{BoneData, SkeletonData};
 use text::{Operation as TextOperation, TextData};
 
-use cgmath::Transform;
+use cgmath;
 use froggy;
 use mint;
 
+use std::collections::HashMap;
+use std::iter;
 use std::sync::{Arc, Mutex};
 use std::sync::{atomic, mpsc};
 
@@ -21,6 +26,7 @@ pub(crate) enum SubLight {
     Directional,
     Hemisphere { ground: Color },
     Point,
+    Spot { inner_cone_angle: f32, outer_cone_angle: f32 },
 }
 
 #[derive(Clone, Debug)]
@@ -44,6 +50,10 @@ pub(crate) enum SubNode {
     Visual(Material, GpuData),
     /// Lighting information for illumination and shadow casting.
     Light(LightData),
+    /// Array of bones deforming skinned meshes.
+    Skeleton(SkeletonData),
+    /// A single bone of a skeleton.
+    Bone(BoneData),
     /// Marks the root object of a `Scene`.
     Scene,
 }
@@ -57,11 +67,22 @@ pub(crate) enum Operation {
     SetTransform(
         Option<mint::Point3<f32>>,
         Option<mint::Quaternion<f32>>,
-        Option<f32>,
+        Option<mint::Vector3<f32>>,
+    ),
+    /// Blends the given parts of the transform with the rest transform of
+    /// the node by the given weight.
+    AnimateTransform(
+        Option<mint::Point3<f32>>,
+        Option<mint::Quaternion<f32>>,
+        Option<mint::Vector3<f32>>,
+        f32,
     ),
     SetMaterial(Material),
+    SetColor(Color),
     SetTexelRange(mint::Point2<i16>, mint::Vector2<u16>),
     SetShadow(ShadowMap, ShadowProjection),
+    SetSkeleton(NodePointer),
+    SetWeights(Vec<f32>),
 }
 
 pub(crate) type HubPtr = Arc<Mutex<Hub>>;
@@ -134,6 +155,20 @@ impl Hub {
         self.spawn(SubNode::Light(data))
     }
 
+    pub(crate) fn spawn_skeleton(
+        &mut self,
+        data: SkeletonData,
+    ) -> object::Base {
+        self.spawn(SubNode::Skeleton(data))
+    }
+
+    pub(crate) fn spawn_bone(
+        &mut self,
+        data: BoneData,
+    ) -> object::Base {
+        self.spawn(SubNode::Bone(data))
+    }
+
     pub(crate) fn spawn_ui_text(
         &mut self,
         text: TextData,
@@ -176,19 +211,51 @@ impl Hub {
                     node.visible = visible;
                 }
                 Operation::SetTransform(pos, rot, scale) => {
+                    // Animations are blended with what the user sets.
+                    for transform in iter::once(&mut node.transform).chain(node.rest_transform.as_mut()) {
+                        if let Some(pos) = pos {
+                            transform.disp = mint::Vector3::from(pos).into();
+                        }
+                        if let Some(rot) = rot {
+                            transform.rot = rot.into();
+                        }
+                        if let Some(scale) = scale {
+                            transform.scale = scale.into();
+                        }
+                    }
+                }
+                Operation::AnimateTransform(pos, rot, scale, weight) => {
+                    let rest = *node.rest_transform.get_or_insert(node.transform);
                     if let Some(pos) = pos {
-                        node.transform.disp = mint::Vector3::from(pos).into();
+                        let disp: cgmath::Vector3<f32> = mint::Vector3::from(pos).into();
+                        node.transform.disp = rest.disp + (disp - rest.disp) * weight;
                     }
                     if let Some(rot) = rot {
-                        node.transform.rot = rot.into();
+                        node.transform.rot = animation::slerp(rest.rot, rot.into(), weight);
                     }
                     if let Some(scale) = scale {
-                        node.transform.scale = scale;
+                        let scale: cgmath::Vector3<f32> = scale.into();
+                        node.transform.scale = rest.scale + (scale - rest.scale) * weight;
                     }
                 }
-                Operation::SetMaterial(material) => if let SubNode::Visual(ref mut mat, _) = node.sub_node {
+                Operation::SetMaterial(material) => if let SubNode::Visual(ref mut mat, ref mut gpu_data) = node.sub_node {
+                    // Normal maps need tangents, which are generated by the
+                    // renderer if the geometry has none.
+                    gpu_data.pending_tangents = factory::tangents_needed(&material, gpu_data);
                     *mat = material;
                 },
+                Operation::SetColor(color) => if let SubNode::Visual(ref mut material, _) = node.sub_node {
+                    match *material {
+                        material::Material::Basic(ref mut params) => params.color = color,
+                        material::Material::CustomBasic(ref mut params) => params.color = color,
+                        material::Material::Line(ref mut params) => params.color = color,
+                        material::Material::Lambert(ref mut params) => params.color = color,
+                        material::Material::Phong(ref mut params) => params.color = color,
+                        material::Material::Pbr(ref mut params) => params.base_color_factor = color,
+                        material::Material::Wireframe(ref mut params) => params.color = color,
+                        material::Material::Sprite(_) => {}
+                    }
+                },
                 Operation::SetTexelRange(base, size) => if let SubNode::Visual(ref mut material, _) = node.sub_node {
                     match *material {
                         material::Material::Sprite(ref mut params) => params.map.set_texel_range(base, size),
@@ -201,6 +268,16 @@ impl Hub {
                 Operation::SetShadow(map, proj) => if let SubNode::Light(ref mut data) = node.sub_node {
                     data.shadow = Some((map, proj));
                 },
+                Operation::SetSkeleton(skeleton) => if let SubNode::Visual(_, ref mut gpu_data) = node.sub_node {
+                    gpu_data.skeleton = Some(skeleton);
+                    // The bounds of the rest pose no longer apply.
+                    gpu_data.bounds = None;
+                },
+                Operation::SetWeights(weights) => if let SubNode::Visual(_, ref mut gpu_data) = node.sub_node {
+                    if let Some(ref mut morph) = gpu_data.morph {
+                        morph.pending = Some(weights);
+                    }
+                },
             }
         }
         self.nodes.sync_pending();
@@ -252,14 +329,14 @@ impl Hub {
                     Some(parent) => (
                         parent.world_visible,
                         parent.scene_id,
-                        parent.world_transform.concat(&item.transform),
+                        parent.world_transform * cgmath::Matrix4::from(item.transform),
                     ),
                     None => {
                         error!("Parent node was created after the child, ignoring");
-                        (false, item.scene_id, item.transform)
+                        (false, item.scene_id, cgmath::Matrix4::from(item.transform))
                     }
                 },
-                None => (true, item.scene_id, item.transform),
+                None => (true, item.scene_id, cgmath::Matrix4::from(item.transform)),
             };
             item.world_visible = visibility;
             item.scene_id = affilation;
@@ -267,6 +344,22 @@ impl Hub {
         }
     }
 
+    /// Returns the children of every node that has any.
+    ///
+    /// Nodes only know their parent, so this walks the whole storage.
+    pub(crate) fn children(&self) -> HashMap<NodePointer, Vec<NodePointer>> {
+        let mut children = HashMap::<NodePointer, Vec<NodePointer>>::new();
+        for item in self.nodes.iter() {
+            if let Some(ref parent) = item.parent {
+                children
+                    .entry(parent.clone())
+                    .or_insert_with(Vec::new)
+                    .push(item.pin());
+            }
+        }
+        children
+    }
+
     pub(crate) fn update_mesh(
         &mut self,
         mesh: &DynamicMesh,
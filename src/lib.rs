@@ -222,13 +222,15 @@
 //!
 //! `three` comes equipped with support for rendering and animating glTF scenes.
 //!
-//! See [`Factory::load_gltf`] to get started.
+//! See [`Factory::load_gltf`] to get started. Scene subtrees and animation
+//! clips may be written back to glTF with [`Factory::export_gltf`].
 //!
 //! ### Wavefront OBJ
 //!
 //! For less complex meshes, `three` supports loading models in OBJ format.
 //!
-//! See [`Factory::load_obj`] for more information.
+//! See [`Factory::load_obj`] for more information. Meshes may be written back
+//! to OBJ with [`Factory::export_obj`] and [`Geometry::write_obj`].
 //!
 //! ## Procedurally generated geometry
 //!
@@ -236,14 +238,24 @@
 //! generated primtives such as cuboids, spheres, and cylinders. See the
 //! documentation on the [`Geometry`] struct for more information.
 //!
+//! Geometry may also be processed on the CPU before it is uploaded, for
+//! instance with [`Geometry::compute_normals`], [`Geometry::weld_vertices`]
+//! and [`Geometry::merge`].
+//!
 //! [`froggy`]: https://crates.io/crates/froggy
 //! [`genmesh`]: https://crates.io/crates/genmesh
 //!
 //! [`Camera`]: camera/struct.Camera.html
 //! [`Factory`]: factory/struct.Factory.html
+//! [`Factory::export_gltf`]: factory/struct.Factory.html#method.export_gltf
+//! [`Factory::export_obj`]: factory/struct.Factory.html#method.export_obj
 //! [`Factory::load_gltf`]: factory/struct.Factory.html#method.load_gltf
 //! [`Factory::load_obj`]: factory/struct.Factory.html#method.load_obj
 //! [`Geometry`]: geometry/struct.Geometry.html
+//! [`Geometry::compute_normals`]: geometry/struct.Geometry.html#method.compute_normals
+//! [`Geometry::merge`]: geometry/struct.Geometry.html#method.merge
+//! [`Geometry::weld_vertices`]: geometry/struct.Geometry.html#method.weld_vertices
+//! [`Geometry::write_obj`]: geometry/struct.Geometry.html#method.write_obj
 //! [`Input`]: input/struct.Input.html
 //! [`Material`]: material/enum.Material.html
 //! [`Mesh`]: mesh/struct.Mesh.html
@@ -253,6 +265,7 @@
 //! [`Scene`]: scene/struct.Scene.html
 //! [`Window`]: window/struct.Window.html
 
+extern crate base64;
 #[macro_use]
 extern crate bitflags;
 extern crate cgmath;
@@ -262,7 +275,6 @@ extern crate genmesh;
 extern crate gfx;
 extern crate gfx_glyph;
 extern crate gltf;
-extern crate gltf_importer;
 extern crate gltf_utils;
 extern crate image;
 extern crate includedir;
@@ -276,6 +288,8 @@ extern crate phf;
 #[macro_use]
 extern crate quick_error;
 extern crate rodio;
+#[macro_use]
+extern crate serde_json;
 extern crate vec_map;
 // OpenGL
 
@@ -291,6 +305,7 @@ mod macros;
 
 pub mod audio;
 pub mod animation;
+mod bounds;
 pub mod camera;
 pub mod color;
 pub mod controls;
@@ -298,15 +313,20 @@ pub mod custom;
 mod data;
 mod factory;
 pub mod geometry;
+#[cfg(feature = "opengl")]
+pub mod headless;
 mod hub;
 mod input;
 pub mod light;
 pub mod material;
 mod mesh;
+mod mikktspace;
 mod node;
 pub mod object;
+mod raycaster;
 pub mod render;
 pub mod scene;
+mod skeleton;
 mod sprite;
 mod text;
 mod texture;
@@ -314,6 +334,9 @@ mod util;
 #[cfg(feature = "opengl")]
 pub mod window;
 
+#[doc(inline)]
+pub use bounds::Bounds;
+
 #[doc(inline)]
 pub use color::Color;
 
@@ -324,11 +347,15 @@ pub use controls::{AXIS_DOWN_UP, AXIS_LEFT_RIGHT, KEY_ESCAPE, KEY_SPACE, MOUSE_L
 pub use controls::{Button, Input, Timer};
 
 #[doc(inline)]
-pub use factory::{Factory, Gltf};
+pub use factory::{ExportError, Factory, Gltf, GltfLight, GltfScene, LoadError, Loading, ObjGroup};
 
 #[doc(inline)]
 pub use geometry::Geometry;
 
+#[cfg(feature = "opengl")]
+#[doc(inline)]
+pub use headless::{Headless, HeadlessError};
+
 #[cfg(feature = "opengl")]
 #[doc(inline)]
 pub use glutin::VirtualKeyCode as Key;
@@ -343,14 +370,20 @@ pub use mesh::{DynamicMesh, Mesh};
 pub use node::{Node, Transform};
 
 #[doc(inline)]
-pub use object::{Group, Object};
+pub use object::{Group, Object, Scale};
+
+#[doc(inline)]
+pub use raycaster::{Intersection, Raycaster};
 
 #[doc(inline)]
-pub use render::Renderer;
+pub use render::{CaptureError, RenderTarget, Renderer};
 
 #[doc(inline)]
 pub use scene::{Background, Scene};
 
+#[doc(inline)]
+pub use skeleton::{Bone, InverseBindMatrix, Skeleton};
+
 #[doc(inline)]
 pub use sprite::Sprite;
 
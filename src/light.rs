@@ -119,3 +119,20 @@ impl Point {
         Point { object }
     }
 }
+
+/// Light originates from a single point, and spreads outward in a cone
+/// pointing along the local negative Z axis.
+///
+/// The light is at full intensity within the inner cone angle, fading out
+/// towards the outer cone angle.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Spot {
+    pub(crate) object: object::Base,
+}
+three_object!(Spot::object);
+
+impl Spot {
+    pub(crate) fn new(object: object::Base) -> Self {
+        Spot { object }
+    }
+}
@@ -161,6 +161,69 @@ pub struct Pbr {
     ///
     /// Default: `None`.
     pub occlusion_map: Option<Texture<[f32; 4]>>,
+
+    /// The texture co-ordinate set used by each texture.
+    ///
+    /// Default: the first set for every texture.
+    pub uv_sets: PbrUvSets,
+
+    /// Specifies how the alpha of the base color is interpreted.
+    ///
+    /// Default: `AlphaMode::Opaque`.
+    pub alpha_mode: AlphaMode,
+
+    /// Alpha below which fragments are discarded when `alpha_mode` is
+    /// `AlphaMode::Mask`.
+    ///
+    /// Default: `0.5`.
+    pub alpha_cutoff: f32,
+
+    /// Specifies whether back faces are rendered, with their normals
+    /// reversed.
+    ///
+    /// Default: `false` (back faces are culled).
+    pub double_sided: bool,
+}
+
+/// Specifies how the alpha of a [`Pbr`](struct.Pbr.html) material is
+/// interpreted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AlphaMode {
+    /// Alpha is ignored and the material is fully opaque.
+    Opaque,
+
+    /// The material is either fully opaque or fully transparent, depending on
+    /// whether its alpha is above `alpha_cutoff`.
+    Mask,
+
+    /// Alpha is blended with the background.
+    ///
+    /// Meshes are drawn in no particular order, hence overlapping
+    /// transparent meshes may not blend correctly.
+    Blend,
+}
+
+/// The texture co-ordinate set used by each texture of a
+/// [`Pbr`](struct.Pbr.html) material.
+///
+/// `0` selects the first set of the mesh geometry, given by
+/// [`Shape::tex_coords`], and `1` selects the second set, given by
+/// [`Shape::tex_coords1`].
+///
+/// [`Shape::tex_coords`]: ../geometry/struct.Shape.html#structfield.tex_coords
+/// [`Shape::tex_coords1`]: ../geometry/struct.Shape.html#structfield.tex_coords1
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct PbrUvSets {
+    /// Set used by `base_color_map`.
+    pub base_color: u32,
+    /// Set used by `normal_map`.
+    pub normal: u32,
+    /// Set used by `emissive_map`.
+    pub emissive: u32,
+    /// Set used by `metallic_roughness_map`.
+    pub metallic_roughness: u32,
+    /// Set used by `occlusion_map`.
+    pub occlusion: u32,
 }
 
 impl Default for Pbr {
@@ -178,6 +241,10 @@ impl Default for Pbr {
             emissive_map: None,
             metallic_roughness_map: None,
             occlusion_map: None,
+            uv_sets: PbrUvSets::default(),
+            alpha_mode: AlphaMode::Opaque,
+            alpha_cutoff: 0.5,
+            double_sided: false,
         }
     }
 }
@@ -4,6 +4,7 @@ use geometry::Geometry;
 use hub::Operation;
 use material::Material;
 use render::DynamicData;
+use skeleton::Skeleton;
 
 use std::hash::{Hash, Hasher};
 
@@ -107,6 +108,12 @@ impl Hash for DynamicMesh {
 
 impl Mesh {
     /// Set mesh material.
+    ///
+    /// If the material is a `Pbr` material with a normal map and the
+    /// geometry has no tangents, they are generated before the mesh is next
+    /// rendered, as for [`Factory::mesh`].
+    ///
+    /// [`Factory::mesh`]: struct.Factory.html#method.mesh
     pub fn set_material(
         &mut self,
         material: Material,
@@ -114,6 +121,38 @@ impl Mesh {
         let msg = Operation::SetMaterial(material);
         let _ = self.object.tx.send((self.object.node.downgrade(), msg));
     }
+
+    /// Bind a skeleton to the mesh.
+    ///
+    /// The vertices of the mesh are then deformed by the bones of `skeleton`
+    /// according to the [`Joints`] of its geometry, and the transform of the
+    /// mesh itself is ignored. Skinned meshes are never frustum culled.
+    ///
+    /// [`Joints`]: geometry/struct.Joints.html
+    pub fn set_skeleton(
+        &mut self,
+        skeleton: &Skeleton,
+    ) {
+        let msg = Operation::SetSkeleton(skeleton.object.node.clone());
+        let _ = self.object.tx.send((self.object.node.downgrade(), msg));
+    }
+
+    /// Set the weights of the morph targets of the mesh.
+    ///
+    /// The morph targets are the blend shapes of the mesh geometry, see
+    /// [`Geometry::shapes`] for their order. Missing weights are taken as
+    /// zero. Instances created with [`Factory::mesh_instance`] share their
+    /// vertices, and hence their morph weights, with the original mesh.
+    ///
+    /// [`Geometry::shapes`]: geometry/struct.Geometry.html#structfield.shapes
+    /// [`Factory::mesh_instance`]: struct.Factory.html#method.mesh_instance
+    pub fn set_weights(
+        &mut self,
+        weights: &[f32],
+    ) {
+        let msg = Operation::SetWeights(weights.to_vec());
+        let _ = self.object.tx.send((self.object.node.downgrade(), msg));
+    }
 }
 
 impl DynamicMesh {
@@ -123,6 +162,14 @@ impl DynamicMesh {
     }
 
     /// Set mesh material.
+    ///
+    /// Unlike [`Mesh::set_material`], tangents are not generated here, since
+    /// the vertices of a dynamic mesh cannot be split. A warning is logged
+    /// if the geometry lacks the tangents needed by a normal map, which
+    /// may be computed with [`Geometry::compute_tangents`] beforehand.
+    ///
+    /// [`Mesh::set_material`]: struct.Mesh.html#method.set_material
+    /// [`Geometry::compute_tangents`]: geometry/struct.Geometry.html#method.compute_tangents
     pub fn set_material(
         &mut self,
         material: Material,
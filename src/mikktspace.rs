@@ -0,0 +1,502 @@
+//! Tangent space generation with the MikkTSpace algorithm.
+//!
+//! This is a Rust port, restricted to triangles, of Morten S. Mikkelsen's
+//! reference implementation, which is distributed under the following
+//! licence:
+//!
+//! Copyright (C) 2011 by Morten S. Mikkelsen
+//!
+//! This software is provided 'as-is', without any express or implied
+//! warranty.  In no event will the authors be held liable for any damages
+//! arising from the use of this software.
+//!
+//! Permission is granted to anyone to use this software for any purpose,
+//! including commercial applications, and to alter it and redistribute it
+//! freely, subject to the following restrictions:
+//!
+//! 1. The origin of this software must not be misrepresented; you must not
+//!    claim that you wrote the original software. If you use this software
+//!    in a product, an acknowledgment in the product documentation would be
+//!    appreciated but is not required.
+//! 2. Altered source versions must be plainly marked as such, and must not be
+//!    misrepresented as being the original software.
+//! 3. This notice may not be removed or altered from any source distribution.
+
+use cgmath::{InnerSpace, Point2, Point3, Vector3, Zero};
+use mint;
+use std::collections::HashMap;
+use std::f32;
+
+/// Tangent spaces whose directions differ by less than this angle, in
+/// degrees, are merged.
+const ANGULAR_THRESHOLD: f32 = 180.0;
+
+/// The triangle is degenerate in texture space, so it joins any group.
+const GROUP_WITH_ANY: u32 = 1;
+/// The triangle preserves the orientation of texture space.
+const ORIENT_PRESERVING: u32 = 2;
+
+struct Triangle {
+    /// The shared vertices of the corners.
+    vertices: [u32; 3],
+    /// The triangle across each edge, where edge `i` starts at corner `i`.
+    neighbours: [Option<usize>; 3],
+    /// The group of each corner.
+    groups: [Option<usize>; 3],
+    os: Vector3<f32>,
+    ot: Vector3<f32>,
+    flags: u32,
+}
+
+impl Triangle {
+    fn corner(
+        &self,
+        vertex: u32,
+    ) -> Option<usize> {
+        self.vertices.iter().position(|&v| v == vertex)
+    }
+}
+
+/// Triangles sharing a vertex, connected through their edges, with the same
+/// orientation of texture space.
+struct Group {
+    triangles: Vec<usize>,
+    vertex: u32,
+    orient_preserving: bool,
+}
+
+#[derive(Clone, Copy)]
+struct TangentSpace {
+    os: Vector3<f32>,
+    orient_preserving: bool,
+}
+
+struct Generator<'a> {
+    vertices: &'a [mint::Point3<f32>],
+    normals: &'a [mint::Vector3<f32>],
+    tex_coords: &'a [mint::Point2<f32>],
+}
+
+impl<'a> Generator<'a> {
+    fn position(
+        &self,
+        i: u32,
+    ) -> Point3<f32> {
+        self.vertices[i as usize].into()
+    }
+
+    fn normal(
+        &self,
+        i: u32,
+    ) -> Vector3<f32> {
+        self.normals[i as usize].into()
+    }
+
+    fn tex_coord(
+        &self,
+        i: u32,
+    ) -> Point2<f32> {
+        self.tex_coords[i as usize].into()
+    }
+
+    /// Returns the index of the first vertex equal to each vertex.
+    fn shared_vertices(&self) -> Vec<u32> {
+        // Adding zero makes negative zero compare equal to zero.
+        let bits = |x: f32| (x + 0.0).to_bits();
+        let mut first = HashMap::new();
+        (0 .. self.vertices.len() as u32)
+            .map(|i| {
+                let (p, n, uv) = (self.position(i), self.normal(i), self.tex_coord(i));
+                let key = [
+                    bits(p.x),
+                    bits(p.y),
+                    bits(p.z),
+                    bits(n.x),
+                    bits(n.y),
+                    bits(n.z),
+                    bits(uv.x),
+                    bits(uv.y),
+                ];
+                *first.entry(key).or_insert(i)
+            })
+            .collect()
+    }
+
+    fn triangle(
+        &self,
+        vertices: [u32; 3],
+    ) -> Triangle {
+        let v1 = self.position(vertices[0]);
+        let v2 = self.position(vertices[1]);
+        let v3 = self.position(vertices[2]);
+        let t21 = self.tex_coord(vertices[1]) - self.tex_coord(vertices[0]);
+        let t31 = self.tex_coord(vertices[2]) - self.tex_coord(vertices[0]);
+        let d1 = v2 - v1;
+        let d2 = v3 - v1;
+        let signed_area = t21.x * t31.y - t21.y * t31.x;
+        let os = d1 * t31.y - d2 * t21.y;
+        let ot = d2 * t21.x - d1 * t31.x;
+
+        let mut triangle = Triangle {
+            vertices,
+            neighbours: [None; 3],
+            groups: [None; 3],
+            os: Vector3::zero(),
+            ot: Vector3::zero(),
+            flags: GROUP_WITH_ANY,
+        };
+        if signed_area > 0.0 {
+            triangle.flags |= ORIENT_PRESERVING;
+        }
+        if not_zero(signed_area) {
+            let sign = if signed_area > 0.0 { 1.0 } else { -1.0 };
+            let (len_os, len_ot) = (os.magnitude(), ot.magnitude());
+            if not_zero(len_os) {
+                triangle.os = os * (sign / len_os);
+            }
+            if not_zero(len_ot) {
+                triangle.ot = ot * (sign / len_ot);
+            }
+            let area = signed_area.abs();
+            if not_zero(len_os / area) && not_zero(len_ot / area) {
+                triangle.flags &= !GROUP_WITH_ANY;
+            }
+        }
+        triangle
+    }
+
+    /// Returns the texture space directions of `triangle` projected onto
+    /// the tangent plane of `vertex`.
+    fn project(
+        &self,
+        triangle: &Triangle,
+        vertex: u32,
+    ) -> (Vector3<f32>, Vector3<f32>) {
+        let n = self.normal(vertex);
+        (
+            normalize(triangle.os - n * n.dot(triangle.os)),
+            normalize(triangle.ot - n * n.dot(triangle.ot)),
+        )
+    }
+
+    /// Averages the tangent spaces of `members` at `vertex`, weighted by the
+    /// angle of their corners.
+    fn eval_tangent_space(
+        &self,
+        triangles: &[Triangle],
+        members: &[usize],
+        vertex: u32,
+    ) -> TangentSpace {
+        let n = self.normal(vertex);
+        let mut os = Vector3::zero();
+        for &f in members {
+            let triangle = &triangles[f];
+            if triangle.flags & GROUP_WITH_ANY != 0 {
+                continue;
+            }
+            let i = match triangle.corner(vertex) {
+                Some(i) => i,
+                None => continue,
+            };
+            let (vos, _) = self.project(triangle, vertex);
+            let p0 = self.position(triangle.vertices[(i + 2) % 3]);
+            let p1 = self.position(triangle.vertices[i]);
+            let p2 = self.position(triangle.vertices[(i + 1) % 3]);
+            let v1 = p0 - p1;
+            let v2 = p2 - p1;
+            let v1 = normalize(v1 - n * n.dot(v1));
+            let v2 = normalize(v2 - n * n.dot(v2));
+            let angle = v1.dot(v2).max(-1.0).min(1.0).acos();
+            os += vos * angle;
+        }
+        TangentSpace {
+            os: normalize(os),
+            orient_preserving: false,
+        }
+    }
+}
+
+fn not_zero(x: f32) -> bool {
+    x.abs() > f32::MIN_POSITIVE
+}
+
+fn normalize(v: Vector3<f32>) -> Vector3<f32> {
+    if not_zero(v.x) || not_zero(v.y) || not_zero(v.z) {
+        v.normalize()
+    } else {
+        v
+    }
+}
+
+/// Connects each triangle to the triangles sharing its edges in the opposite
+/// direction.
+fn build_neighbours(triangles: &mut [Triangle]) {
+    // The corners of each edge, in increasing order, with the triangle,
+    // the edge number and whether the edge runs in that order.
+    let mut edges = HashMap::<(u32, u32), Vec<(usize, usize, bool)>>::new();
+    for (f, triangle) in triangles.iter().enumerate() {
+        for i in 0 .. 3 {
+            let (a, b) = (triangle.vertices[i], triangle.vertices[(i + 1) % 3]);
+            let key = if a < b { (a, b) } else { (b, a) };
+            edges.entry(key).or_insert_with(Vec::new).push((f, i, a < b));
+        }
+    }
+    for list in edges.values() {
+        for (k, &(f, i, forward)) in list.iter().enumerate() {
+            if triangles[f].neighbours[i].is_some() {
+                continue;
+            }
+            let other = list[k + 1 ..]
+                .iter()
+                .find(|&&(t, j, other_forward)| other_forward != forward && triangles[t].neighbours[j].is_none());
+            if let Some(&(t, j, _)) = other {
+                triangles[f].neighbours[i] = Some(t);
+                triangles[t].neighbours[j] = Some(f);
+            }
+        }
+    }
+}
+
+/// Adds triangle `f` and its neighbours around the vertex of `group` to it,
+/// while they share its orientation.
+fn assign_recursive(
+    triangles: &mut [Triangle],
+    groups: &mut [Group],
+    f: usize,
+    group: usize,
+) {
+    let i = match triangles[f].corner(groups[group].vertex) {
+        Some(i) => i,
+        None => return,
+    };
+    if triangles[f].groups[i].is_some() {
+        return;
+    }
+    let orient_preserving = groups[group].orient_preserving;
+    {
+        let triangle = &mut triangles[f];
+        if triangle.flags & GROUP_WITH_ANY != 0 && triangle.groups.iter().all(Option::is_none) {
+            triangle.flags &= !ORIENT_PRESERVING;
+            if orient_preserving {
+                triangle.flags |= ORIENT_PRESERVING;
+            }
+        }
+        if (triangle.flags & ORIENT_PRESERVING != 0) != orient_preserving {
+            return;
+        }
+        triangle.groups[i] = Some(group);
+    }
+    groups[group].triangles.push(f);
+    let left = triangles[f].neighbours[i];
+    let right = triangles[f].neighbours[(i + 2) % 3];
+    for neighbour in left.into_iter().chain(right) {
+        assign_recursive(triangles, groups, neighbour, group);
+    }
+}
+
+/// Groups the corners of the triangles by vertex, orientation and
+/// connectivity.
+fn build_groups(triangles: &mut [Triangle]) -> Vec<Group> {
+    let mut groups = Vec::new();
+    for f in 0 .. triangles.len() {
+        for i in 0 .. 3 {
+            if triangles[f].flags & GROUP_WITH_ANY != 0 || triangles[f].groups[i].is_some() {
+                continue;
+            }
+            let group = groups.len();
+            groups.push(Group {
+                triangles: vec![f],
+                vertex: triangles[f].vertices[i],
+                orient_preserving: triangles[f].flags & ORIENT_PRESERVING != 0,
+            });
+            triangles[f].groups[i] = Some(group);
+            let left = triangles[f].neighbours[i];
+            let right = triangles[f].neighbours[(i + 2) % 3];
+            for neighbour in left.into_iter().chain(right) {
+                assign_recursive(triangles, &mut groups, neighbour, group);
+            }
+        }
+    }
+    groups
+}
+
+/// Generates a tangent for each corner of `faces`, whose `w` component is
+/// the handedness of the bitangent.
+///
+/// Every vertex must have a normal and texture co-ordinates. Corners without
+/// a tangent space, such as those of isolated degenerate triangles, are
+/// given `[1, 0, 0, 1]`.
+pub(crate) fn generate(
+    vertices: &[mint::Point3<f32>],
+    normals: &[mint::Vector3<f32>],
+    tex_coords: &[mint::Point2<f32>],
+    faces: &[[u32; 3]],
+) -> Vec<[[f32; 4]; 3]> {
+    let generator = Generator {
+        vertices,
+        normals,
+        tex_coords,
+    };
+    let shared = generator.shared_vertices();
+    let shared_face = |face: &[u32; 3]| {
+        [
+            shared[face[0] as usize],
+            shared[face[1] as usize],
+            shared[face[2] as usize],
+        ]
+    };
+
+    // Triangles with coincident corners take their tangent spaces from
+    // others sharing their vertices.
+    let (good, degenerate): (Vec<usize>, Vec<usize>) = (0 .. faces.len()).partition(|&f| {
+        let face = shared_face(&faces[f]);
+        let p = [
+            generator.position(face[0]),
+            generator.position(face[1]),
+            generator.position(face[2]),
+        ];
+        p[0] != p[1] && p[0] != p[2] && p[1] != p[2]
+    });
+    let mut triangles: Vec<Triangle> = good.iter()
+        .map(|&f| generator.triangle(shared_face(&faces[f])))
+        .collect();
+    build_neighbours(&mut triangles);
+    let groups = build_groups(&mut triangles);
+
+    // Matches the fallback of `Geometry::generate_tangents`.
+    let default = TangentSpace {
+        os: Vector3::unit_x(),
+        orient_preserving: true,
+    };
+    let mut spaces = vec![[default; 3]; faces.len()];
+    let threshold = ANGULAR_THRESHOLD.to_radians().cos();
+    for (g, group) in groups.iter().enumerate() {
+        // Tangent spaces of the distinct sets of members within the group.
+        let mut subgroups: Vec<(Vec<usize>, TangentSpace)> = Vec::new();
+        for &f in &group.triangles {
+            let triangle = &triangles[f];
+            let index = match triangle.groups.iter().position(|&group| group == Some(g)) {
+                Some(index) => index,
+                None => continue,
+            };
+            let (os, ot) = generator.project(triangle, group.vertex);
+            let mut members: Vec<usize> = group.triangles
+                .iter()
+                .cloned()
+                .filter(|&t| {
+                    let other = &triangles[t];
+                    let (os2, ot2) = generator.project(other, group.vertex);
+                    (triangle.flags | other.flags) & GROUP_WITH_ANY != 0 || f == t ||
+                        (os.dot(os2) > threshold && ot.dot(ot2) > threshold)
+                })
+                .collect();
+            members.sort();
+            let existing = subgroups.iter().position(|&(ref other, _)| *other == members);
+            let space = match existing {
+                Some(k) => subgroups[k].1,
+                None => {
+                    let space = generator.eval_tangent_space(&triangles, &members, group.vertex);
+                    subgroups.push((members, space));
+                    space
+                }
+            };
+            spaces[good[f]][index] = TangentSpace {
+                orient_preserving: group.orient_preserving,
+                ..space
+            };
+        }
+    }
+
+    // The first corner of a good triangle at each shared vertex.
+    let mut sources = HashMap::new();
+    for &g in &good {
+        for (j, &vertex) in shared_face(&faces[g]).iter().enumerate() {
+            sources.entry(vertex).or_insert((g, j));
+        }
+    }
+    for &f in &degenerate {
+        let face = shared_face(&faces[f]);
+        for i in 0 .. 3 {
+            if let Some(&(g, j)) = sources.get(&face[i]) {
+                spaces[f][i] = spaces[g][j];
+            }
+        }
+    }
+
+    spaces
+        .iter()
+        .map(|corners| {
+            let tangent = |space: &TangentSpace| {
+                let w = if space.orient_preserving { 1.0 } else { -1.0 };
+                [space.os.x, space.os.y, space.os.z, w]
+            };
+            [tangent(&corners[0]), tangent(&corners[1]), tangent(&corners[2])]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::generate;
+    use mint;
+
+    /// Generates the tangents of a mesh in the XY plane facing +Z.
+    fn tangents(
+        positions: &[[f32; 2]],
+        tex_coords: &[[f32; 2]],
+        faces: &[[u32; 3]],
+    ) -> Vec<[[f32; 4]; 3]> {
+        let vertices: Vec<mint::Point3<f32>> = positions.iter().map(|p| [p[0], p[1], 0.0].into()).collect();
+        let normals: Vec<mint::Vector3<f32>> = positions.iter().map(|_| [0.0, 0.0, 1.0].into()).collect();
+        let tex_coords: Vec<mint::Point2<f32>> = tex_coords.iter().map(|&uv| uv.into()).collect();
+        generate(&vertices, &normals, &tex_coords, faces)
+    }
+
+    fn assert_tangents(
+        actual: &[[f32; 4]],
+        expected: [f32; 4],
+    ) {
+        for tangent in actual {
+            for (a, b) in tangent.iter().zip(&expected) {
+                assert!((a - b).abs() < 1.0e-5, "{:?} != {:?}", tangent, expected);
+            }
+        }
+    }
+
+    const QUAD: [[f32; 2]; 4] = [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]];
+    const QUAD_FACES: [[u32; 3]; 2] = [[0, 1, 2], [0, 2, 3]];
+
+    #[test]
+    fn quad() {
+        for corners in tangents(&QUAD, &QUAD, &QUAD_FACES) {
+            assert_tangents(&corners, [1.0, 0.0, 0.0, 1.0]);
+        }
+    }
+
+    /// Texture co-ordinates of the quad, mirrored horizontally.
+    fn mirrored() -> Vec<[f32; 2]> {
+        QUAD.iter().map(|uv| [-uv[0], uv[1]]).collect()
+    }
+
+    #[test]
+    fn mirrored_uv_island() {
+        for corners in tangents(&QUAD, &mirrored(), &QUAD_FACES) {
+            assert_tangents(&corners, [-1.0, 0.0, 0.0, -1.0]);
+        }
+    }
+
+    #[test]
+    fn degenerate_triangles() {
+        let mut positions = QUAD.to_vec();
+        let mut tex_coords = mirrored();
+        // An isolated triangle collapsed to a point.
+        positions.extend(&[[2.0, 2.0]; 3]);
+        tex_coords.extend(&[[0.5, 0.5]; 3]);
+        let faces = [[0, 1, 2], [0, 2, 3], [0, 1, 1], [4, 5, 6]];
+        let spaces = tangents(&positions, &tex_coords, &faces);
+        // Corners shared with the quad take their tangents from it.
+        assert_tangents(&spaces[2], [-1.0, 0.0, 0.0, -1.0]);
+        // Others fall back to the default.
+        assert_tangents(&spaces[3], [1.0, 0.0, 0.0, 1.0]);
+    }
+}
@@ -1,4 +1,6 @@
+use bounds::Bounds;
 use cgmath;
+use cgmath::{InnerSpace, One, SquareMatrix, Zero};
 use froggy;
 use mint;
 use scene;
@@ -8,7 +10,31 @@ use material::Material;
 
 /// Pointer to a Node
 pub(crate) type NodePointer = froggy::Pointer<NodeInternal>;
-pub(crate) type TransformInternal = cgmath::Decomposed<cgmath::Vector3<f32>, cgmath::Quaternion<f32>>;
+
+/// Translation, rotation and per-axis scale, applied in reverse order.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct TransformInternal {
+    pub disp: cgmath::Vector3<f32>,
+    pub rot: cgmath::Quaternion<f32>,
+    pub scale: cgmath::Vector3<f32>,
+}
+
+impl TransformInternal {
+    /// The identity transform.
+    pub(crate) fn one() -> Self {
+        TransformInternal {
+            disp: cgmath::Vector3::zero(),
+            rot: cgmath::Quaternion::one(),
+            scale: cgmath::Vector3::new(1.0, 1.0, 1.0),
+        }
+    }
+}
+
+impl From<TransformInternal> for cgmath::Matrix4<f32> {
+    fn from(tf: TransformInternal) -> Self {
+        cgmath::Matrix4::from_translation(tf.disp) * cgmath::Matrix4::from(tf.rot) * cgmath::Matrix4::from_nonuniform_scale(tf.scale.x, tf.scale.y, tf.scale.z)
+    }
+}
 
 // Fat node of the scene graph.
 //
@@ -23,7 +49,13 @@ pub(crate) struct NodeInternal {
     /// The transform relative to the node's parent.
     pub(crate) transform: TransformInternal,
     /// The transform relative to the world origin.
-    pub(crate) world_transform: TransformInternal,
+    ///
+    /// This is kept as a matrix, since composing a rotation with a
+    /// non-uniform scale introduces shear.
+    pub(crate) world_transform: cgmath::Matrix4<f32>,
+    /// The transform the node had before it was first animated, with which
+    /// animations are blended.
+    pub(crate) rest_transform: Option<TransformInternal>,
     /// Pointer to node's parent.
     pub(crate) parent: Option<NodePointer>,
     /// The ID of the scene this node belongs to.
@@ -43,6 +75,15 @@ impl NodeInternal {
                 SubNode::Visual(ref mat, _) => Some(mat.clone()),
                 _ => None,
             },
+            bounds: self.bounds(),
+            world_bounds: self.bounds().map(|bounds| bounds.transformed(&self.world_transform)),
+        }
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        match self.sub_node {
+            SubNode::Visual(_, ref gpu_data) => gpu_data.bounds,
+            _ => None,
         }
     }
 }
@@ -54,8 +95,8 @@ pub struct Transform {
     pub position: mint::Point3<f32>,
     /// Orientation.
     pub orientation: mint::Quaternion<f32>,
-    /// Scale.
-    pub scale: f32,
+    /// Scale along each of the local axes.
+    pub scale: mint::Vector3<f32>,
 }
 
 impl From<TransformInternal> for Transform {
@@ -64,9 +105,53 @@ impl From<TransformInternal> for Transform {
         Transform {
             position: pos.into(),
             orientation: tf.rot.into(),
-            scale: tf.scale,
+            scale: tf.scale.into(),
+        }
+    }
+}
+
+impl From<cgmath::Matrix4<f32>> for Transform {
+    /// Decomposes an affine matrix into translation, rotation and scale.
+    ///
+    /// Shear cannot be represented, so it is dropped: the columns are
+    /// orthonormalized with the Gram-Schmidt process to find the rotation,
+    /// and the scale is the extent of each column along its rotated axis.
+    fn from(mx: cgmath::Matrix4<f32>) -> Self {
+        let (cx, cy, cz) = (mx.x.truncate(), mx.y.truncate(), mx.z.truncate());
+        // Mirroring is represented by a negative scale along X.
+        let sign = if mx.determinant() < 0.0 { -1.0 } else { 1.0 };
+        let x = orthonormal(cx * sign, &[], cgmath::Vector3::unit_x());
+        let y = orthonormal(cy, &[x], cgmath::Vector3::unit_y());
+        let z = x.cross(y);
+        let scale = cgmath::Vector3::new(cx.dot(x), cy.dot(y), cz.dot(z));
+        let rotation = cgmath::Matrix3::from_cols(x, y, z);
+        let pos: mint::Vector3<f32> = mx.w.truncate().into();
+        Transform {
+            position: pos.into(),
+            orientation: cgmath::Quaternion::from(rotation).into(),
+            scale: scale.into(),
+        }
+    }
+}
+
+/// Removes the components of `v` along the unit vectors `axes` and
+/// normalizes the result.
+///
+/// Falls back to a unit vector perpendicular to `axes`, starting with
+/// `fallback`, when `v` is zero or lies in their span.
+fn orthonormal(
+    v: cgmath::Vector3<f32>,
+    axes: &[cgmath::Vector3<f32>],
+    fallback: cgmath::Vector3<f32>,
+) -> cgmath::Vector3<f32> {
+    let candidates = [v, fallback, cgmath::Vector3::unit_x(), cgmath::Vector3::unit_y(), cgmath::Vector3::unit_z()];
+    for &candidate in &candidates {
+        let w = axes.iter().fold(candidate, |w, &axis| w - axis * axis.dot(w));
+        if w.magnitude2() > 1.0e-12 {
+            return w.normalize();
         }
     }
+    unreachable!()
 }
 
 /// General information about scene `Node`.
@@ -75,6 +160,9 @@ pub struct Node {
     /// Relative to parent transform.
     pub transform: Transform,
     /// World transform (relative to the world's origin).
+    ///
+    /// Any shear, which results from rotating a node under a non-uniformly
+    /// scaled parent, is left out.
     pub world_transform: Transform,
     /// Is `Node` visible by cameras or not?
     pub visible: bool,
@@ -82,6 +170,10 @@ pub struct Node {
     pub world_visible: bool,
     /// Material in case this `Node` has it.
     pub material: Option<Material>,
+    /// Bounds of the mesh geometry relative to the node, if any.
+    pub bounds: Option<Bounds>,
+    /// Bounds of the mesh geometry in world space, if any.
+    pub world_bounds: Option<Bounds>,
 }
 
 impl From<SubNode> for NodeInternal {
@@ -89,8 +181,9 @@ impl From<SubNode> for NodeInternal {
         NodeInternal {
             visible: true,
             world_visible: false,
-            transform: cgmath::Transform::one(),
-            world_transform: cgmath::Transform::one(),
+            transform: TransformInternal::one(),
+            world_transform: cgmath::Matrix4::identity(),
+            rest_transform: None,
             parent: None,
             scene_id: None,
             sub_node: sub,
@@ -4,12 +4,44 @@ use std::fmt;
 use std::hash::{Hash, Hasher};
 use std::sync::mpsc;
 
+use cgmath;
 use mint;
 
 use hub::{Message, Operation};
 use node::{Node, NodePointer};
 use scene::Scene;
 
+/// Scale of an object along each of its local axes.
+///
+/// Converts from a single `f32` for uniform scale, and from 3D vectors for
+/// non-uniform scale.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Scale(pub mint::Vector3<f32>);
+
+impl From<f32> for Scale {
+    fn from(scale: f32) -> Self {
+        Scale([scale; 3].into())
+    }
+}
+
+impl From<[f32; 3]> for Scale {
+    fn from(scale: [f32; 3]) -> Self {
+        Scale(scale.into())
+    }
+}
+
+impl From<mint::Vector3<f32>> for Scale {
+    fn from(scale: mint::Vector3<f32>) -> Self {
+        Scale(scale)
+    }
+}
+
+impl From<cgmath::Vector3<f32>> for Scale {
+    fn from(scale: cgmath::Vector3<f32>) -> Self {
+        Scale(scale.into())
+    }
+}
+
 //Note: no local state should be here, only remote links
 /// `Base` represents a concrete entity that can be added to the scene.
 ///
@@ -61,15 +93,16 @@ pub trait Object: AsRef<Base> + AsMut<Base> {
     }
 
     /// Set both position, orientation and scale.
-    fn set_transform<P, Q>(
+    fn set_transform<P, Q, S>(
         &mut self,
         pos: P,
         rot: Q,
-        scale: f32,
+        scale: S,
     ) where
         Self: Sized,
         P: Into<mint::Point3<f32>>,
         Q: Into<mint::Quaternion<f32>>,
+        S: Into<Scale>,
     {
         self.as_mut().set_transform(pos, rot, scale)
     }
@@ -107,11 +140,14 @@ pub trait Object: AsRef<Base> + AsMut<Base> {
         self.as_mut().set_orientation(rot)
     }
 
-    /// Set scale.
-    fn set_scale(
+    /// Set scale along each of the local axes, or uniformly given an `f32`.
+    fn set_scale<S>(
         &mut self,
-        scale: f32,
-    ) {
+        scale: S,
+    ) where
+        Self: Sized,
+        S: Into<Scale>,
+    {
         self.as_mut().set_scale(scale)
     }
 
@@ -189,16 +225,17 @@ impl Base {
     }
 
     /// Set both position, orientation and scale.
-    pub fn set_transform<P, Q>(
+    pub fn set_transform<P, Q, S>(
         &mut self,
         pos: P,
         rot: Q,
-        scale: f32,
+        scale: S,
     ) where
         P: Into<mint::Point3<f32>>,
         Q: Into<mint::Quaternion<f32>>,
+        S: Into<Scale>,
     {
-        let msg = Operation::SetTransform(Some(pos.into()), Some(rot.into()), Some(scale));
+        let msg = Operation::SetTransform(Some(pos.into()), Some(rot.into()), Some(scale.into().0));
         let _ = self.tx.send((self.node.downgrade(), msg));
     }
 
@@ -235,12 +272,14 @@ impl Base {
         let _ = self.tx.send((self.node.downgrade(), msg));
     }
 
-    /// Set scale.
-    pub fn set_scale(
+    /// Set scale along each of the local axes, or uniformly given an `f32`.
+    pub fn set_scale<S>(
         &mut self,
-        scale: f32,
-    ) {
-        let msg = Operation::SetTransform(None, None, Some(scale));
+        scale: S,
+    ) where
+        S: Into<Scale>,
+    {
+        let msg = Operation::SetTransform(None, None, Some(scale.into().0));
         let _ = self.tx.send((self.node.downgrade(), msg));
     }
 
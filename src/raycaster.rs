@@ -0,0 +1,285 @@
+//! Ray casting against the scene graph.
+
+use cgmath::{EuclideanSpace, InnerSpace, Matrix4, Point3, SquareMatrix, Transform, Vector3, Vector4};
+use itertools::Either;
+use mint;
+use object;
+use std::cmp::Ordering;
+use std::f32;
+
+use bounds::Bounds;
+use camera::Camera;
+use hub::SubNode;
+use material::Material;
+use render::CpuData;
+use scene::Scene;
+
+/// A point where a ray meets a triangle of a visible mesh.
+#[derive(Clone, Debug)]
+pub struct Intersection {
+    /// The object that was hit.
+    pub object: object::Base,
+
+    /// The distance from the ray origin to `point`, in world units.
+    pub distance: f32,
+
+    /// The point of intersection in world space.
+    pub point: mint::Point3<f32>,
+
+    /// The index of the hit triangle in the mesh geometry.
+    pub face_index: usize,
+
+    /// Barycentric co-ordinates of `point` within the hit triangle.
+    ///
+    /// The weights of the second and third triangle vertices are `x` and `y`
+    /// respectively; the weight of the first vertex is `1 - x - y`.
+    pub barycentric: mint::Point2<f32>,
+}
+
+/// A ray in world space, used to find which objects lie under a point.
+///
+/// # Examples
+///
+/// Finding the object under the mouse cursor.
+///
+/// ```rust,no_run
+/// # let mut window = three::Window::new("");
+/// # let camera = window.factory.perspective_camera(60.0, 0.1 .. 10.0);
+/// while window.update() {
+///     if window.input.hit(three::MOUSE_LEFT) {
+///         let raycaster = three::Raycaster::from_camera(
+///             &window.scene,
+///             &camera,
+///             window.renderer.aspect_ratio(),
+///             window.input.mouse_pos_ndc(),
+///         );
+///         if let Some(hit) = raycaster.intersect(&window.scene).first() {
+///             println!("Clicked on {:?} at {:?}", hit.object, hit.point);
+///         }
+///     }
+///     window.render(&camera);
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Raycaster {
+    /// The starting point of the ray.
+    pub origin: mint::Point3<f32>,
+
+    /// The normalized direction of the ray.
+    pub direction: mint::Vector3<f32>,
+}
+
+impl Raycaster {
+    /// Creates a ray starting at `origin` and travelling in `direction`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `direction` has zero length or is not finite.
+    pub fn new<P, V>(
+        origin: P,
+        direction: V,
+    ) -> Self
+    where
+        P: Into<mint::Point3<f32>>,
+        V: Into<mint::Vector3<f32>>,
+    {
+        let direction = Vector3::from(direction.into());
+        let length = direction.magnitude();
+        assert!(length > 0.0 && length.is_finite(), "Invalid ray direction {:?}", direction);
+        Raycaster {
+            origin: origin.into(),
+            direction: (direction / length).into(),
+        }
+    }
+
+    /// Creates a ray from the view of `camera` through the given point in
+    /// Normalized Display Coordinates, such as `Input::mouse_pos_ndc`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `camera` belongs to a scene other than `scene`.
+    pub fn from_camera<P>(
+        scene: &Scene,
+        camera: &Camera,
+        aspect_ratio: f32,
+        point: P,
+    ) -> Self
+    where
+        P: Into<mint::Point2<f32>>,
+    {
+        let point = point.into();
+        let world = {
+            let mut hub = scene.hub.lock().unwrap();
+            hub.process_messages();
+            hub.update_graph();
+            let scene_id = hub.nodes[&scene.object.node].scene_id;
+            let node = &hub.nodes[&camera.object.node];
+            match node.scene_id {
+                Some(id) if Some(id) == scene_id => node.world_transform,
+                Some(_) => panic!("Camera does not belong to this scene"),
+                None => Matrix4::from(node.transform),
+            }
+        };
+        let mx_proj: [[f32; 4]; 4] = camera.matrix(aspect_ratio).into();
+        let mx_view = world.invert().unwrap();
+        let mx_inv_vp = (Matrix4::from(mx_proj) * mx_view).invert().unwrap();
+        let unproject = |z: f32| {
+            let p = mx_inv_vp * Vector4::new(point.x, point.y, z, 1.0);
+            Point3::from_homogeneous(p)
+        };
+        // The far plane of an infinite projection lies at infinity, so the
+        // direction is taken between the near plane and the middle of the
+        // depth range instead.
+        let near = unproject(-1.0);
+        let middle = unproject(0.0);
+        Raycaster::new(near, middle - near)
+    }
+
+    /// Intersects the ray with every visible mesh in `scene`.
+    ///
+    /// The intersections are sorted from nearest to furthest. Lines and
+    /// sprites are ignored.
+    ///
+    /// Dynamic meshes are tested against the vertices last set by
+    /// `Factory::mix` or `Factory::map_vertices`.
+    pub fn intersect(
+        &self,
+        scene: &Scene,
+    ) -> Vec<Intersection> {
+        let origin = Point3::from(self.origin);
+        let direction = Vector3::from(self.direction);
+        let mut intersections = Vec::new();
+
+        let mut hub = scene.hub.lock().unwrap();
+        hub.process_messages();
+        hub.update_graph();
+        let scene_id = hub.nodes[&scene.object.node].scene_id;
+        let tx = hub.message_tx.clone();
+
+        for item in hub.nodes.iter() {
+            if !item.world_visible || item.scene_id != scene_id {
+                continue;
+            }
+            let (cpu_data, bounds) = match item.sub_node {
+                SubNode::Visual(Material::Line(_), _) | SubNode::Visual(Material::Sprite(_), _) => continue,
+                SubNode::Visual(_, ref gpu_data) => match gpu_data.cpu_data {
+                    Some(ref data) => (data, gpu_data.bounds),
+                    None => continue,
+                },
+                _ => continue,
+            };
+            let world = item.world_transform;
+            let local = match world.invert() {
+                Some(inverse) => inverse,
+                None => continue,
+            };
+            let local_origin = local.transform_point(origin);
+            let local_direction = local.transform_vector(direction);
+            if let Some(ref bounds) = bounds {
+                if !intersects_box(bounds, local_origin, local_direction) {
+                    continue;
+                }
+            }
+            for (face_index, t, u, v) in intersect_triangles(cpu_data, local_origin, local_direction) {
+                let point = world.transform_point(local_origin + local_direction * t);
+                let distance = (point - origin).magnitude();
+                if !distance.is_finite() {
+                    continue;
+                }
+                intersections.push(Intersection {
+                    object: object::Base {
+                        node: item.pin(),
+                        tx: tx.clone(),
+                    },
+                    distance,
+                    point: point.into(),
+                    face_index,
+                    barycentric: [u, v].into(),
+                });
+            }
+        }
+
+        intersections.sort_by(|a, b| {
+            a.distance
+                .partial_cmp(&b.distance)
+                .unwrap_or(Ordering::Equal)
+        });
+        intersections
+    }
+}
+
+/// Returns `(face_index, t, u, v)` for every triangle hit by the given ray,
+/// using the Möller–Trumbore algorithm.
+fn intersect_triangles(
+    data: &CpuData,
+    origin: Point3<f32>,
+    direction: Vector3<f32>,
+) -> Vec<(usize, f32, f32, f32)> {
+    const EPSILON: f32 = 1.0e-7;
+    let position = |i: u32| Point3::from(data.shape.vertices[i as usize]);
+    let faces = if data.faces.is_empty() {
+        Either::Left((0 .. data.shape.vertices.len() as u32 / 3).map(|i| [3 * i, 3 * i + 1, 3 * i + 2]))
+    } else {
+        Either::Right(data.faces.iter().cloned())
+    };
+    let direction_length = direction.magnitude();
+    let mut hits = Vec::new();
+    for (face_index, face) in faces.enumerate() {
+        let (a, b, c) = (position(face[0]), position(face[1]), position(face[2]));
+        let edge1 = b - a;
+        let edge2 = c - a;
+        let p = direction.cross(edge2);
+        let det = edge1.dot(p);
+        // `det` scales with the lengths of the edges and of the direction,
+        // which is not normalized in the local space of the mesh.
+        if det.abs() < EPSILON * edge1.magnitude() * edge2.magnitude() * direction_length {
+            // The ray is parallel to the triangle.
+            continue;
+        }
+        let inv_det = 1.0 / det;
+        let s = origin - a;
+        let u = s.dot(p) * inv_det;
+        if u < 0.0 || u > 1.0 {
+            continue;
+        }
+        let q = s.cross(edge1);
+        let v = direction.dot(q) * inv_det;
+        if v < 0.0 || u + v > 1.0 {
+            continue;
+        }
+        let t = edge2.dot(q) * inv_det;
+        if t > EPSILON {
+            hits.push((face_index, t, u, v));
+        }
+    }
+    hits
+}
+
+/// Returns `false` if the given ray misses the bounding box of `bounds`,
+/// using the slab method.
+fn intersects_box(
+    bounds: &Bounds,
+    origin: Point3<f32>,
+    direction: Vector3<f32>,
+) -> bool {
+    let (min, max) = (Point3::from(bounds.min), Point3::from(bounds.max));
+    let (mut t_near, mut t_far) = (0.0f32, f32::INFINITY);
+    for axis in 0 .. 3 {
+        if direction[axis] == 0.0 {
+            // The ray is parallel to this slab.
+            if origin[axis] < min[axis] || origin[axis] > max[axis] {
+                return false;
+            }
+            continue;
+        }
+        let inv = 1.0 / direction[axis];
+        let t0 = (min[axis] - origin[axis]) * inv;
+        let t1 = (max[axis] - origin[axis]) * inv;
+        t_near = t_near.max(t0.min(t1));
+        t_far = t_far.min(t0.max(t1));
+        if t_near > t_far {
+            return false;
+        }
+    }
+    true
+}
@@ -1,9 +1,10 @@
 //! The renderer.
 
-use cgmath::{Matrix4, SquareMatrix, Transform as Transform_, Vector3};
+use cgmath::{EuclideanSpace, InnerSpace, Matrix4, Point3, SquareMatrix, Transform, Vector3};
 use color;
 use froggy;
 use gfx;
+use gfx::format::Formatted;
 use gfx::memory::Typed;
 use gfx::traits::{Device, Factory as Factory_, FactoryExt};
 #[cfg(feature = "opengl")]
@@ -12,24 +13,29 @@ use gfx_device_gl as back;
 use gfx_window_glutin;
 #[cfg(feature = "opengl")]
 use glutin;
+use image;
 use mint;
 
 pub mod source;
 
-use std::{io, mem, str};
-use std::collections::HashMap;
+use std::{cmp, io, mem, str};
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
+use std::sync::Arc;
 
 pub use self::back::CommandBuffer as BackendCommandBuffer;
 pub use self::back::Factory as BackendFactory;
 pub use self::back::Resources as BackendResources;
 pub use self::source::Source;
 
+use bounds::{Bounds, Frustum};
 use camera::Camera;
-use factory::Factory;
-use hub::{SubLight, SubNode};
+use factory::{self, Factory};
+use geometry::{Joints, Shape};
+use hub::{Hub, SubLight, SubNode};
 use light::{ShadowMap, ShadowProjection};
-use material::Material;
+use material::{AlphaMode, Material};
+use node::NodePointer;
 use scene::{Background, Scene};
 use text::Font;
 use texture::Texture;
@@ -85,13 +91,31 @@ quick_error! {
     }
 }
 
+#[cfg_attr(rustfmt, rustfmt_skip)]
+quick_error! {
+    #[doc = "Error encountered when reading back a frame with `Renderer::capture`."]
+    #[derive(Debug)]
+    pub enum CaptureError {
+        #[doc = "The renderer draws into a window, whose default framebuffer"]
+        #[doc = "cannot be read back. Use `Renderer::capture_scene` instead."]
+        Window {
+            description("Window framebuffer read-back")
+            display("Unable to read back the framebuffer of a window")
+        }
+    }
+}
+
 #[cfg_attr(rustfmt, rustfmt_skip)]
 gfx_defines! {
     vertex Vertex {
         pos: [f32; 4] = "a_Position",
         uv: [f32; 2] = "a_TexCoord",
+        uv1: [f32; 2] = "a_TexCoord1",
+        color: [f32; 4] = "a_Color",
         normal: [gfx::format::I8Norm; 4] = "a_Normal",
         tangent: [gfx::format::I8Norm; 4] = "a_Tangent",
+        joint_indices: [i32; 4] = "a_JointIndices",
+        joint_weights: [f32; 4] = "a_JointWeights",
     }
 
     constant Locals {
@@ -127,6 +151,7 @@ gfx_defines! {
         tex_map: gfx::TextureSampler<[f32; 4]> = "t_Map",
         shadow_map0: gfx::TextureSampler<f32> = "t_Shadow0",
         shadow_map1: gfx::TextureSampler<f32> = "t_Shadow1",
+        joint_transforms: gfx::ShaderResource<[f32; 4]> = "b_JointTransforms",
         out_color: gfx::BlendTarget<ColorFormat> =
             ("Target0", gfx::state::MASK_ALL, gfx::preset::blend::REPLACE),
         out_depth: gfx::DepthStencilTarget<DepthFormat> =
@@ -139,6 +164,7 @@ gfx_defines! {
         vbuf: gfx::VertexBuffer<Vertex> = (),
         cb_locals: gfx::ConstantBuffer<Locals> = "b_Locals",
         cb_globals: gfx::ConstantBuffer<Globals> = "b_Globals",
+        joint_transforms: gfx::ShaderResource<[f32; 4]> = "b_JointTransforms",
         target: gfx::DepthTarget<ShadowFormat> =
             gfx::preset::depth::LESS_EQUAL_WRITE,
     }
@@ -168,6 +194,13 @@ gfx_defines! {
         normal_scale: f32 = "u_NormalScale",
         occlusion_strength: f32 = "u_OcclusionStrength",
         pbr_flags: i32 = "u_PbrFlags",
+        alpha_cutoff: f32 = "u_AlphaCutoff",
+        _padding2: [f32; 2] = "_padding2",
+        base_color_transform: [[f32; 4]; 4] = "u_BaseColorTransform",
+        normal_transform: [[f32; 4]; 4] = "u_NormalTransform",
+        emissive_transform: [[f32; 4]; 4] = "u_EmissiveTransform",
+        metallic_roughness_transform: [[f32; 4]; 4] = "u_MetallicRoughnessTransform",
+        occlusion_transform: [[f32; 4]; 4] = "u_OcclusionTransform",
     }
 
     pipeline pbr_pipe {
@@ -188,7 +221,10 @@ gfx_defines! {
 
         occlusion_map: gfx::TextureSampler<[f32; 4]> = "u_OcclusionSampler",
 
-        color_target: gfx::RenderTarget<ColorFormat> = "Target0",
+        joint_transforms: gfx::ShaderResource<[f32; 4]> = "b_JointTransforms",
+
+        color_target: gfx::BlendTarget<ColorFormat> =
+            ("Target0", gfx::state::MASK_ALL, gfx::preset::blend::REPLACE),
         depth_target: gfx::DepthTarget<DepthFormat> = gfx::preset::depth::LESS_EQUAL_WRITE,
     }
 }
@@ -200,6 +236,88 @@ pub(crate) struct GpuData {
     pub vertices: gfx::handle::Buffer<back::Resources, Vertex>,
     pub constants: gfx::handle::Buffer<back::Resources, Locals>,
     pub pending: Option<DynamicData>,
+    /// CPU-side copy of the triangles, if any, used for ray casting.
+    pub cpu_data: Option<Arc<CpuData>>,
+    /// Local-space bounds of the geometry, used for frustum culling.
+    ///
+    /// Nodes without bounds are never culled.
+    pub bounds: Option<Bounds>,
+    /// The skeleton deforming this mesh, if any.
+    pub skeleton: Option<NodePointer>,
+    /// Morph targets of the mesh, if any.
+    pub morph: Option<MorphData>,
+    /// Texture co-ordinate set to generate tangents from before the next
+    /// draw, if a normal map was given to the mesh after it was created.
+    pub pending_tangents: Option<u32>,
+}
+
+/// Morph targets of a mesh, blended on the CPU when their weights change.
+#[derive(Clone, Debug)]
+pub(crate) struct MorphData {
+    /// Vertices of the base shape.
+    pub vertices: Arc<Vec<Vertex>>,
+    /// Displacements of each morph target from the base shape.
+    pub targets: Arc<Vec<MorphTarget>>,
+    /// Staging buffer for the blended vertices.
+    pub upload: gfx::handle::Buffer<back::Resources, Vertex>,
+    /// Weights yet to be applied to the vertex buffer, if any.
+    pub pending: Option<Vec<f32>>,
+    /// Weights last applied to the vertex buffer.
+    pub weights: Vec<f32>,
+}
+
+/// Position and normal displacements of a single morph target.
+#[derive(Clone, Debug)]
+pub(crate) struct MorphTarget {
+    pub positions: Vec<Vector3<f32>>,
+    /// Empty if the target does not displace normals.
+    pub normals: Vec<Vector3<f32>>,
+}
+
+impl MorphData {
+    /// Blends the morph targets into `out` with the given weights.
+    fn blend(
+        &self,
+        weights: &[f32],
+        out: &mut [Vertex],
+    ) {
+        let f2i = |x: f32| gfx::format::I8Norm(cmp::min(cmp::max((x * 127.0) as isize, -128), 127) as i8);
+        let i2f = |x: gfx::format::I8Norm| x.0 as f32 / 127.0;
+        for (i, (vertex, base)) in out.iter_mut().zip(self.vertices.iter()).enumerate() {
+            let mut position = Vector3::new(base.pos[0], base.pos[1], base.pos[2]);
+            let mut normal = Vector3::new(i2f(base.normal[0]), i2f(base.normal[1]), i2f(base.normal[2]));
+            for (target, &weight) in self.targets.iter().zip(weights) {
+                if let Some(&displacement) = target.positions.get(i) {
+                    position += weight * displacement;
+                }
+                if let Some(&displacement) = target.normals.get(i) {
+                    normal += weight * displacement;
+                }
+            }
+            if normal.magnitude2() > 0.0 {
+                normal = normal.normalize();
+            }
+            *vertex = Vertex {
+                pos: [position.x, position.y, position.z, 1.0],
+                normal: [f2i(normal.x), f2i(normal.y), f2i(normal.z), gfx::format::I8Norm(0)],
+                ..*base
+            };
+        }
+    }
+}
+
+/// CPU-side copy of the geometry uploaded to the GPU, used for ray casting
+/// and exporting.
+#[derive(Clone, Debug)]
+pub(crate) struct CpuData {
+    /// The vertex attributes of the base shape.
+    pub shape: Shape,
+    /// Triangle indices into the vertices of `shape`.
+    ///
+    /// When empty, every three consecutive vertices form a triangle.
+    pub faces: Vec<[u32; 3]>,
+    /// Joints of the vertices of `shape`, if the mesh is skinned.
+    pub joints: Joints,
 }
 
 #[derive(Clone, Debug)]
@@ -208,6 +326,37 @@ pub(crate) struct DynamicData {
     pub buffer: gfx::handle::Buffer<back::Resources, Vertex>,
 }
 
+/// An offscreen color and depth target.
+///
+/// Scenes are drawn into a render target with [`Renderer::render_to`], after
+/// which its color attachment may be sampled like any other [`Texture`], for
+/// example as `material::Basic::map` or `Background::Texture`.
+///
+/// Created with [`Factory::render_target`].
+///
+/// [`Factory::render_target`]: struct.Factory.html#method.render_target
+/// [`Renderer::render_to`]: struct.Renderer.html#method.render_to
+/// [`Texture`]: struct.Texture.html
+#[derive(Clone, Debug, PartialEq)]
+pub struct RenderTarget {
+    pub(crate) color: gfx::handle::RenderTargetView<back::Resources, ColorFormat>,
+    pub(crate) depth: gfx::handle::DepthStencilView<back::Resources, DepthFormat>,
+    pub(crate) texture: Texture<[f32; 4]>,
+    pub(crate) size: [u32; 2],
+}
+
+impl RenderTarget {
+    /// Returns the color attachment of this target as a texture.
+    pub fn texture(&self) -> Texture<[f32; 4]> {
+        self.texture.clone()
+    }
+
+    /// Returns the size of this target in pixels.
+    pub fn size(&self) -> mint::Vector2<u32> {
+        self.size.into()
+    }
+}
+
 /// Shadow type is used to specify shadow's rendering algorithm.
 pub enum ShadowType {
     /// Force no shadows.
@@ -225,6 +374,14 @@ bitflags! {
         const METALLIC_ROUGHNESS_MAP = 1 << 2;
         const EMISSIVE_MAP           = 1 << 3;
         const OCCLUSION_MAP          = 1 << 4;
+        const BASE_COLOR_UV1         = 1 << 5;
+        const NORMAL_UV1             = 1 << 6;
+        const METALLIC_ROUGHNESS_UV1 = 1 << 7;
+        const EMISSIVE_UV1           = 1 << 8;
+        const OCCLUSION_UV1          = 1 << 9;
+        const ALPHA_MASK             = 1 << 10;
+        const ALPHA_BLEND            = 1 << 11;
+        const DOUBLE_SIDED           = 1 << 12;
     }
 }
 
@@ -260,9 +417,19 @@ pub struct PipelineStates {
     /// Used internally for rendering sprites.
     quad: gfx::PipelineState<back::Resources, quad_pipe::Meta>,
 
-    /// Corresponds to `Material::Pbr`.
+    /// Corresponds to `Material::Pbr` with opaque or masked alpha.
     pbr: gfx::PipelineState<back::Resources, pbr_pipe::Meta>,
 
+    /// Corresponds to a double-sided `Material::Pbr` with opaque or masked
+    /// alpha.
+    pbr_double_sided: gfx::PipelineState<back::Resources, pbr_pipe::Meta>,
+
+    /// Corresponds to `Material::Pbr` with blended alpha.
+    pbr_blend: gfx::PipelineState<back::Resources, pbr_pipe::Meta>,
+
+    /// Corresponds to a double-sided `Material::Pbr` with blended alpha.
+    pbr_blend_double_sided: gfx::PipelineState<back::Resources, pbr_pipe::Meta>,
+
     /// Used internally for rendering `Background::Skybox`.
     skybox: gfx::PipelineState<back::Resources, quad_pipe::Meta>,
 }
@@ -292,6 +459,7 @@ impl PipelineStates {
 
         let rast_quad = gfx::state::Rasterizer::new_fill();
         let rast_fill = gfx::state::Rasterizer::new_fill().with_cull_back();
+        let rast_double_sided = gfx::state::Rasterizer::new_fill();
         let rast_wire = gfx::state::Rasterizer {
             method: gfx::state::RasterMethod::Line(1),
             ..rast_fill
@@ -358,12 +526,35 @@ impl PipelineStates {
             rast_quad,
             quad_pipe::new(),
         )?;
+        let pbr_blend_init = || pbr_pipe::Init {
+            color_target: ("Target0", gfx::state::MASK_ALL, gfx::preset::blend::ALPHA),
+            depth_target: gfx::preset::depth::LESS_EQUAL_TEST,
+            ..pbr_pipe::new()
+        };
         let pso_pbr = backend.create_pipeline_state(
             &pbr,
             gfx::Primitive::TriangleList,
             rast_fill,
             pbr_pipe::new(),
         )?;
+        let pso_pbr_double_sided = backend.create_pipeline_state(
+            &pbr,
+            gfx::Primitive::TriangleList,
+            rast_double_sided,
+            pbr_pipe::new(),
+        )?;
+        let pso_pbr_blend = backend.create_pipeline_state(
+            &pbr,
+            gfx::Primitive::TriangleList,
+            rast_fill,
+            pbr_blend_init(),
+        )?;
+        let pso_pbr_blend_double_sided = backend.create_pipeline_state(
+            &pbr,
+            gfx::Primitive::TriangleList,
+            rast_double_sided,
+            pbr_blend_init(),
+        )?;
 
         Ok(PipelineStates {
             mesh_basic_fill: pso_mesh_basic_fill,
@@ -375,6 +566,9 @@ impl PipelineStates {
             shadow: pso_shadow,
             quad: pso_quad,
             pbr: pso_pbr,
+            pbr_double_sided: pso_pbr_double_sided,
+            pbr_blend: pso_pbr_blend,
+            pbr_blend_double_sided: pso_pbr_blend_double_sided,
             skybox: pso_skybox,
         })
     }
@@ -385,11 +579,45 @@ impl PipelineStates {
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct DebugQuadHandle(froggy::Pointer<DebugQuad>);
 
+/// Color and depth targets of an offscreen framebuffer.
+type OffscreenTargets = (
+    gfx::handle::RenderTargetView<back::Resources, ColorFormat>,
+    gfx::handle::DepthStencilView<back::Resources, DepthFormat>,
+);
+
+/// Creates offscreen color and depth targets of the given size.
+///
+/// The textures are created with `TRANSFER_SRC` so that they can be read
+/// back by `Renderer::capture`.
+fn offscreen_targets(
+    gl_factory: &mut back::Factory,
+    width: u16,
+    height: u16,
+) -> OffscreenTargets {
+    use gfx::memory::{Usage, DEPTH_STENCIL, RENDER_TARGET, TRANSFER_SRC};
+    use gfx::texture as t;
+    let kind = t::Kind::D2(width, height, t::AaMode::Single);
+    let color_texture = gl_factory
+        .create_texture::<gfx::format::R8_G8_B8_A8>(kind, 1, RENDER_TARGET | TRANSFER_SRC, Usage::Data, Some(gfx::format::ChannelType::Unorm))
+        .unwrap();
+    let color = gl_factory
+        .view_texture_as_render_target::<ColorFormat>(&color_texture, 0, None)
+        .unwrap();
+    let depth_texture = gl_factory
+        .create_texture::<<DepthFormat as Formatted>::Surface>(kind, 1, DEPTH_STENCIL | TRANSFER_SRC, Usage::Data, Some(<<DepthFormat as Formatted>::Channel as gfx::format::ChannelTyped>::get_channel_type()))
+        .unwrap();
+    let depth = gl_factory
+        .view_texture_as_depth_stencil_trivial::<DepthFormat>(&depth_texture)
+        .unwrap();
+    (color, depth)
+}
+
 /// Renders [`Scene`](struct.Scene.html) by [`Camera`](struct.Camera.html).
 ///
 /// See [Window::render](struct.Window.html#method.render).
 pub struct Renderer {
     device: back::Device,
+    factory: back::Factory,
     encoder: gfx::Encoder<back::Resources, back::CommandBuffer>,
     const_buf: gfx::handle::Buffer<back::Resources, Globals>,
     quad_buf: gfx::handle::Buffer<back::Resources, QuadParams>,
@@ -397,9 +625,16 @@ pub struct Renderer {
     pbr_buf: gfx::handle::Buffer<back::Resources, PbrParams>,
     out_color: gfx::handle::RenderTargetView<back::Resources, ColorFormat>,
     out_depth: gfx::handle::DepthStencilView<back::Resources, DepthFormat>,
+    /// Whether `out_color` and `out_depth` are offscreen textures which can
+    /// be read back, rather than the default framebuffer of a window.
+    offscreen: bool,
+    /// Offscreen targets the size of the window, created on demand by
+    /// `Renderer::capture_scene`.
+    capture_targets: Option<OffscreenTargets>,
     pso: PipelineStates,
     map_default: Texture<[f32; 4]>,
     shadow_default: Texture<f32>,
+    joints_default: gfx::handle::ShaderResourceView<back::Resources, [f32; 4]>,
     debug_quads: froggy::Storage<DebugQuad>,
     size: (u32, u32),
     font_cache: HashMap<PathBuf, Font>,
@@ -415,14 +650,52 @@ impl Renderer {
         event_loop: &glutin::EventsLoop,
         source: &source::Set,
     ) -> (Self, glutin::GlWindow, Factory) {
+        let (window, device, gl_factory, out_color, out_depth) = gfx_window_glutin::init(builder, context, event_loop);
+        let size = window.get_inner_size_pixels().unwrap();
+        let (renderer, factory) = Renderer::init(device, gl_factory, out_color, out_depth, false, size, source);
+        (renderer, window, factory)
+    }
+
+    /// Creates a renderer drawing into an offscreen framebuffer of the given size.
+    ///
+    /// The `context` must be made current on the calling thread beforehand.
+    #[cfg(feature = "opengl")]
+    pub(crate) fn headless(
+        context: &glutin::HeadlessContext,
+        width: u16,
+        height: u16,
+        source: &source::Set,
+    ) -> (Self, Factory) {
+        let (device, mut gl_factory) = back::create(|s| context.get_proc_address(s) as *const _);
+        let (out_color, out_depth) = offscreen_targets(&mut gl_factory, width, height);
+        let size = (width as u32, height as u32);
+        Renderer::init(device, gl_factory, out_color, out_depth, true, size, source)
+    }
+
+    /// Implementation shared by `Renderer::new` and `Renderer::headless`.
+    fn init(
+        device: back::Device,
+        mut gl_factory: back::Factory,
+        out_color: gfx::handle::RenderTargetView<back::Resources, ColorFormat>,
+        out_depth: gfx::handle::DepthStencilView<back::Resources, DepthFormat>,
+        offscreen: bool,
+        size: (u32, u32),
+        source: &source::Set,
+    ) -> (Self, Factory) {
         use gfx::texture as t;
-        let (window, device, mut gl_factory, out_color, out_depth) = gfx_window_glutin::init(builder, context, event_loop);
         let (_, srv_white) = gl_factory
             .create_texture_immutable::<gfx::format::Rgba8>(t::Kind::D2(1, 1, t::AaMode::Single), &[&[[0xFF; 4]]])
             .unwrap();
         let (_, srv_shadow) = gl_factory
             .create_texture_immutable::<(gfx::format::R32, gfx::format::Float)>(t::Kind::D2(1, 1, t::AaMode::Single), &[&[0x3F800000]])
             .unwrap();
+        let identity = [[1.0, 0.0, 0.0, 0.0], [0.0, 1.0, 0.0, 0.0], [0.0, 0.0, 1.0, 0.0], [0.0, 0.0, 0.0, 1.0]];
+        let joints_buf = gl_factory
+            .create_buffer_immutable(&identity, gfx::buffer::Role::Constant, gfx::memory::SHADER_RESOURCE)
+            .unwrap();
+        let joints_default = gl_factory
+            .view_buffer_as_shader_resource(&joints_buf)
+            .unwrap();
         let sampler = gl_factory.create_sampler_linear();
         let sampler_shadow = gl_factory.create_sampler(t::SamplerInfo {
             comparison: Some(gfx::state::Comparison::Less),
@@ -437,6 +710,7 @@ impl Renderer {
         let pso = PipelineStates::init(source, &mut gl_factory).unwrap();
         let renderer = Renderer {
             device,
+            factory: gl_factory.clone(),
             encoder,
             const_buf,
             quad_buf,
@@ -444,16 +718,19 @@ impl Renderer {
             pbr_buf,
             out_color,
             out_depth,
+            offscreen,
+            capture_targets: None,
             pso,
             map_default: Texture::new(srv_white, sampler, [1, 1]),
             shadow_default: Texture::new(srv_shadow, sampler_shadow, [1, 1]),
+            joints_default,
             shadow: ShadowType::Basic,
             debug_quads: froggy::Storage::new(),
             font_cache: HashMap::new(),
-            size: window.get_inner_size_pixels().unwrap(),
+            size,
         };
         let factory = Factory::new(gl_factory);
-        (renderer, window, factory)
+        (renderer, factory)
     }
 
     /// Reloads the shaders.
@@ -477,9 +754,15 @@ impl Renderer {
         }
 
         self.size = size;
+        self.capture_targets = None;
         gfx_window_glutin::update_views(window, &mut self.out_color, &mut self.out_depth);
     }
 
+    /// Returns current viewport size in pixels.
+    pub(crate) fn size(&self) -> (u32, u32) {
+        self.size
+    }
+
     /// Returns current viewport aspect ratio, i.e. width / height.
     pub fn aspect_ratio(&self) -> f32 {
         self.size.0 as f32 / self.size.1 as f32
@@ -504,6 +787,55 @@ impl Renderer {
         &mut self,
         scene: &Scene,
         camera: &Camera,
+    ) {
+        let out_color = self.out_color.clone();
+        let out_depth = self.out_depth.clone();
+        let size = self.size;
+        self.draw(scene, camera, &out_color, &out_depth, size, true);
+    }
+
+    /// Renders `scene` from the view of `camera` into an offscreen [`RenderTarget`].
+    ///
+    /// UI text and debug quads are screen overlays and are not drawn into
+    /// render targets. An object must not sample the target's texture while
+    /// that same target is being rendered into.
+    ///
+    /// [`RenderTarget`]: struct.RenderTarget.html
+    pub fn render_to(
+        &mut self,
+        target: &RenderTarget,
+        scene: &Scene,
+        camera: &Camera,
+    ) {
+        let size = (target.size[0], target.size[1]);
+        self.draw(scene, camera, &target.color, &target.depth, size, false);
+    }
+
+    /// Returns the joint transforms of the skeleton deforming `gpu_data`, or
+    /// a single identity transform if the mesh is not skinned.
+    fn joint_transforms(
+        &self,
+        hub: &Hub,
+        gpu_data: &GpuData,
+    ) -> gfx::handle::ShaderResourceView<back::Resources, [f32; 4]> {
+        match gpu_data.skeleton {
+            Some(ref skeleton) => match hub.nodes[skeleton].sub_node {
+                SubNode::Skeleton(ref data) => data.gpu_buffer_view.clone(),
+                _ => unreachable!(),
+            },
+            None => self.joints_default.clone(),
+        }
+    }
+
+    /// Implementation of `Renderer::render` and `Renderer::render_to`.
+    fn draw(
+        &mut self,
+        scene: &Scene,
+        camera: &Camera,
+        out_color: &gfx::handle::RenderTargetView<back::Resources, ColorFormat>,
+        out_depth: &gfx::handle::DepthStencilView<back::Resources, DepthFormat>,
+        size: (u32, u32),
+        overlays: bool,
     ) {
         self.device.cleanup();
         let mut hub = scene.hub.lock().unwrap();
@@ -518,6 +850,9 @@ impl Renderer {
                 continue;
             }
             if let SubNode::Visual(_, ref mut gpu_data) = node.sub_node {
+                if let Some(uv_set) = gpu_data.pending_tangents.take() {
+                    factory::upload_tangents(&mut self.factory, gpu_data, uv_set);
+                }
                 if let Some(dynamic) = gpu_data.pending.take() {
                     self.encoder
                         .copy_buffer(
@@ -529,9 +864,57 @@ impl Renderer {
                         )
                         .unwrap();
                 }
+                if let Some(ref mut morph) = gpu_data.morph {
+                    if let Some(weights) = morph.pending.take() {
+                        {
+                            let mut mapping = self.factory.write_mapping(&morph.upload).unwrap();
+                            morph.blend(&weights, &mut mapping);
+                        }
+                        morph.weights = weights;
+                        self.encoder
+                            .copy_buffer(
+                                &morph.upload,
+                                &gpu_data.vertices,
+                                0,
+                                0,
+                                morph.vertices.len(),
+                            )
+                            .unwrap();
+                    }
+                }
             }
         }
 
+        // update joint transforms of skeletons in use
+        let mut skeletons = HashSet::new();
+        for node in hub.nodes.iter() {
+            if !node.visible || node.scene_id != scene_id {
+                continue;
+            }
+            if let SubNode::Visual(_, GpuData { skeleton: Some(ref skeleton), .. }) = node.sub_node {
+                skeletons.insert(skeleton.clone());
+            }
+        }
+        for skeleton in &skeletons {
+            let data = match hub.nodes[skeleton].sub_node {
+                SubNode::Skeleton(ref data) => data,
+                _ => unreachable!(),
+            };
+            let mut joints = Vec::with_capacity(4 * data.bones.len());
+            for bone in &data.bones {
+                let node = &hub.nodes[&bone.object.node];
+                let inverse_bind_matrix = match node.sub_node {
+                    SubNode::Bone(ref bone) => Matrix4::from(bone.inverse_bind_matrix),
+                    _ => unreachable!(),
+                };
+                let matrix: [[f32; 4]; 4] = (node.world_transform * inverse_bind_matrix).into();
+                joints.extend_from_slice(&matrix);
+            }
+            self.encoder
+                .update_buffer(&data.gpu_buffer, &joints, 0)
+                .unwrap();
+        }
+
         // gather lights
         struct ShadowRequest {
             target: gfx::handle::DepthStencilView<back::Resources, ShadowFormat>,
@@ -557,7 +940,7 @@ impl Renderer {
                     let mx_proj = match projection {
                         &ShadowProjection::Orthographic(ref p) => p.matrix(aspect),
                     };
-                    let mx_view = Matrix4::from(node.world_transform.inverse_transform().unwrap());
+                    let mx_view = node.world_transform.invert().unwrap();
                     shadow_requests.push(ShadowRequest {
                         target,
                         resource: map.to_resource(),
@@ -569,8 +952,9 @@ impl Renderer {
                     -1
                 };
                 let mut color_back = 0;
-                let mut p = node.world_transform.disp.extend(1.0);
-                let d = node.world_transform.rot * Vector3::unit_z();
+                let mut focus = [0.0; 4];
+                let mut p = node.world_transform.w;
+                let d = node.world_transform.z.truncate().normalize();
                 let intensity = match light.sub_light {
                     SubLight::Ambient => [light.intensity, 0.0, 0.0, 0.0],
                     SubLight::Directional => {
@@ -583,6 +967,13 @@ impl Renderer {
                         [light.intensity, 0.0, 0.0, 0.0]
                     }
                     SubLight::Point => [0.0, light.intensity, 0.0, 0.0],
+                    SubLight::Spot {
+                        inner_cone_angle,
+                        outer_cone_angle,
+                    } => {
+                        focus = [inner_cone_angle.cos(), outer_cone_angle.cos(), 1.0, 0.0];
+                        [0.0, light.intensity, 0.0, 0.0]
+                    }
                 };
                 let projection = if shadow_index >= 0 {
                     let request = &shadow_requests[shadow_index as usize];
@@ -595,7 +986,7 @@ impl Renderer {
                     projection,
                     pos: p.into(),
                     dir: d.extend(0.0).into(),
-                    focus: [0.0, 0.0, 0.0, 0.0],
+                    focus,
                     color: {
                         let rgb = color::to_linear_rgb(light.color);
                         [rgb[0], rgb[1], rgb[2], 0.0]
@@ -623,6 +1014,7 @@ impl Renderer {
                     num_lights: 0,
                 },
             );
+            let frustum = Frustum::from_matrix(mx_vp);
             for node in hub.nodes.iter() {
                 if !node.visible || node.scene_id != scene_id {
                     continue;
@@ -631,10 +1023,15 @@ impl Renderer {
                     SubNode::Visual(_, ref data) => data,
                     _ => continue,
                 };
+                if let Some(ref bounds) = gpu_data.bounds {
+                    if !frustum.intersects(&bounds.transformed(&node.world_transform)) {
+                        continue;
+                    }
+                }
                 self.encoder.update_constant_buffer(
                     &gpu_data.constants,
                     &Locals {
-                        mx_world: Matrix4::from(node.world_transform).into(),
+                        mx_world: node.world_transform.into(),
                         color: [0.0; 4],
                         mat_params: [0.0; 4],
                         uv_range: [0.0; 4],
@@ -645,6 +1042,7 @@ impl Renderer {
                     vbuf: gpu_data.vertices.clone(),
                     cb_locals: gpu_data.constants.clone(),
                     cb_globals: self.const_buf.clone(),
+                    joint_transforms: self.joint_transforms(&hub, gpu_data),
                     target: request.target.clone(),
                 };
                 self.encoder.draw(&gpu_data.slice, &self.pso.shadow, &data);
@@ -653,14 +1051,14 @@ impl Renderer {
 
         // prepare target and globals
         let (mx_inv_proj, mx_view, mx_vp) = {
-            let p: [[f32; 4]; 4] = camera.matrix(self.aspect_ratio()).into();
+            let p: [[f32; 4]; 4] = camera.matrix(size.0 as f32 / size.1 as f32).into();
             let node = &hub.nodes[&camera.object.node];
             let w = match node.scene_id {
                 Some(id) if Some(id) == scene_id => node.world_transform,
                 Some(_) => panic!("Camera does not belong to this scene"),
-                None => node.transform,
+                None => Matrix4::from(node.transform),
             };
-            let mx_view = Matrix4::from(w.inverse_transform().unwrap());
+            let mx_view = w.invert().unwrap();
             let mx_vp = Matrix4::from(p) * mx_view;
             (Matrix4::from(p).invert().unwrap(), mx_view, mx_vp)
         };
@@ -678,13 +1076,63 @@ impl Renderer {
             .update_buffer(&self.light_buf, &lights, 0)
             .unwrap();
 
-        self.encoder.clear_depth(&self.out_depth, 1.0);
-        self.encoder.clear_stencil(&self.out_depth, 0);
+        self.encoder.clear_depth(out_depth, 1.0);
+        self.encoder.clear_stencil(out_depth, 0);
 
         if let Background::Color(color) = scene.background {
             let rgb = color::to_linear_rgb(color);
             self.encoder
-                .clear(&self.out_color, [rgb[0], rgb[1], rgb[2], 0.0]);
+                .clear(out_color, [rgb[0], rgb[1], rgb[2], 0.0]);
+        }
+
+        let quad_slice = gfx::Slice {
+            start: 0,
+            end: 4,
+            base_vertex: 0,
+            instances: None,
+            buffer: gfx::IndexBuffer::Auto,
+        };
+
+        // draw background (if any)
+        match scene.background {
+            Background::Texture(ref texture) => {
+                // TODO: Reduce code duplication (see drawing debug quads)
+                self.encoder.update_constant_buffer(
+                    &self.quad_buf,
+                    &QuadParams {
+                        rect: [-1.0, -1.0, 1.0, 1.0],
+                        depth: 1.0,
+                    },
+                );
+                let data = quad_pipe::Data {
+                    params: self.quad_buf.clone(),
+                    globals: self.const_buf.clone(),
+                    resource: texture.to_param().0.raw().clone(),
+                    sampler: texture.to_param().1,
+                    target: out_color.clone(),
+                    depth_target: out_depth.clone(),
+                };
+                self.encoder.draw(&quad_slice, &self.pso.quad, &data);
+            }
+            Background::Skybox(ref cubemap) => {
+                self.encoder.update_constant_buffer(
+                    &self.quad_buf,
+                    &QuadParams {
+                        rect: [-1.0, -1.0, 1.0, 1.0],
+                        depth: 1.0,
+                    },
+                );
+                let data = quad_pipe::Data {
+                    params: self.quad_buf.clone(),
+                    resource: cubemap.to_param().0.raw().clone(),
+                    sampler: cubemap.to_param().1,
+                    globals: self.const_buf.clone(),
+                    target: out_color.clone(),
+                    depth_target: out_depth.clone(),
+                };
+                self.encoder.draw(&quad_slice, &self.pso.skybox, &data);
+            }
+            Background::Color(_) => {}
         }
 
         // render everything
@@ -697,6 +1145,11 @@ impl Renderer {
             Some(ref request) => request.resource.clone(),
             None => shadow_default.clone(),
         };
+        let frustum = Frustum::from_matrix(mx_vp);
+        // Blended meshes are drawn last, from back to front, so that they
+        // blend with everything behind them.
+        let mut visuals = Vec::new();
+        let mut blended = Vec::new();
         for node in hub.nodes.iter() {
             if !node.visible || node.scene_id != scene_id {
                 continue;
@@ -705,6 +1158,29 @@ impl Renderer {
                 SubNode::Visual(ref mat, ref data) => (mat, data),
                 _ => continue,
             };
+            if let Some(ref bounds) = gpu_data.bounds {
+                if !frustum.intersects(&bounds.transformed(&node.world_transform)) {
+                    continue;
+                }
+            }
+            match *material {
+                Material::Pbr(ref params) if params.alpha_mode == AlphaMode::Blend => {
+                    let center = gpu_data.bounds.map_or(Point3::origin(), |bounds| bounds.center.into());
+                    let depth = (mx_view * node.world_transform).transform_point(center).z;
+                    blended.push((depth, node));
+                }
+                _ => visuals.push(node),
+            }
+        }
+        // The camera looks down -Z, so the furthest meshes come first.
+        blended.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(cmp::Ordering::Equal));
+        visuals.extend(blended.into_iter().map(|(_, node)| node));
+
+        for node in visuals {
+            let (material, gpu_data) = match node.sub_node {
+                SubNode::Visual(ref mat, ref data) => (mat, data),
+                _ => unreachable!(),
+            };
 
             //TODO: batch per PSO
             match *material {
@@ -712,7 +1188,7 @@ impl Renderer {
                     self.encoder.update_constant_buffer(
                         &gpu_data.constants,
                         &Locals {
-                            mx_world: Matrix4::from(node.world_transform).into(),
+                            mx_world: node.world_transform.into(),
                             ..unsafe { mem::zeroed() }
                         },
                     );
@@ -732,6 +1208,28 @@ impl Renderer {
                     if params.occlusion_map.is_some() {
                         pbr_flags.insert(OCCLUSION_MAP);
                     }
+                    let uv_sets = [
+                        (params.uv_sets.base_color, BASE_COLOR_UV1),
+                        (params.uv_sets.normal, NORMAL_UV1),
+                        (params.uv_sets.metallic_roughness, METALLIC_ROUGHNESS_UV1),
+                        (params.uv_sets.emissive, EMISSIVE_UV1),
+                        (params.uv_sets.occlusion, OCCLUSION_UV1),
+                    ];
+                    for &(set, flag) in &uv_sets {
+                        if set == 1 {
+                            pbr_flags.insert(flag);
+                        }
+                    }
+                    match params.alpha_mode {
+                        AlphaMode::Opaque => {}
+                        AlphaMode::Mask => pbr_flags.insert(ALPHA_MASK),
+                        AlphaMode::Blend => pbr_flags.insert(ALPHA_BLEND),
+                    }
+                    if params.double_sided {
+                        pbr_flags.insert(DOUBLE_SIDED);
+                    }
+                    let map_default = &self.map_default;
+                    let uv_matrix = |map: &Option<Texture<[f32; 4]>>| map.as_ref().unwrap_or(map_default).uv_matrix();
                     let bcf = color::to_linear_rgb(params.base_color_factor);
                     let emf = color::to_linear_rgb(params.emissive_factor);
                     self.encoder.update_constant_buffer(
@@ -744,8 +1242,15 @@ impl Renderer {
                             normal_scale: params.normal_scale,
                             occlusion_strength: params.occlusion_strength,
                             pbr_flags: pbr_flags.bits(),
+                            alpha_cutoff: params.alpha_cutoff,
+                            base_color_transform: uv_matrix(&params.base_color_map),
+                            normal_transform: uv_matrix(&params.normal_map),
+                            emissive_transform: uv_matrix(&params.emissive_map),
+                            metallic_roughness_transform: uv_matrix(&params.metallic_roughness_map),
+                            occlusion_transform: uv_matrix(&params.occlusion_map),
                             _padding0: unsafe { mem::uninitialized() },
                             _padding1: unsafe { mem::uninitialized() },
+                            _padding2: unsafe { mem::uninitialized() },
                         },
                     );
                     let data = pbr_pipe::Data {
@@ -789,10 +1294,17 @@ impl Renderer {
                                 .unwrap_or(&self.map_default)
                                 .to_param()
                         },
-                        color_target: self.out_color.clone(),
-                        depth_target: self.out_depth.clone(),
+                        joint_transforms: self.joint_transforms(&hub, gpu_data),
+                        color_target: out_color.clone(),
+                        depth_target: out_depth.clone(),
+                    };
+                    let pso = match (params.alpha_mode, params.double_sided) {
+                        (AlphaMode::Blend, false) => &self.pso.pbr_blend,
+                        (AlphaMode::Blend, true) => &self.pso.pbr_blend_double_sided,
+                        (_, false) => &self.pso.pbr,
+                        (_, true) => &self.pso.pbr_double_sided,
                     };
-                    self.encoder.draw(&gpu_data.slice, &self.pso.pbr, &data);
+                    self.encoder.draw(&gpu_data.slice, pso, &data);
                 }
                 ref other => {
                     let (pso, color, param0, map) = match *other {
@@ -822,7 +1334,7 @@ impl Renderer {
                     self.encoder.update_constant_buffer(
                         &gpu_data.constants,
                         &Locals {
-                            mx_world: Matrix4::from(node.world_transform).into(),
+                            mx_world: node.world_transform.into(),
                             color: {
                                 let rgb = color::to_linear_rgb(color);
                                 [rgb[0], rgb[1], rgb[2], 0.0]
@@ -840,117 +1352,213 @@ impl Renderer {
                         tex_map: map.unwrap_or(&self.map_default).to_param(),
                         shadow_map0: (shadow0.clone(), shadow_sampler.clone()),
                         shadow_map1: (shadow1.clone(), shadow_sampler.clone()),
-                        out_color: self.out_color.clone(),
-                        out_depth: (self.out_depth.clone(), (0, 0)),
+                        joint_transforms: self.joint_transforms(&hub, gpu_data),
+                        out_color: out_color.clone(),
+                        out_depth: (out_depth.clone(), (0, 0)),
                     };
                     self.encoder.draw(&gpu_data.slice, pso, &data);
                 }
             };
         }
 
-        let quad_slice = gfx::Slice {
-            start: 0,
-            end: 4,
-            base_vertex: 0,
-            instances: None,
-            buffer: gfx::IndexBuffer::Auto,
-        };
+        if overlays {
+            // draw ui text
+            for node in hub.nodes.iter() {
+                if let SubNode::UiText(ref text) = node.sub_node {
+                    text.font.queue(&text.section);
+                    if !self.font_cache.contains_key(&text.font.path) {
+                        self.font_cache
+                            .insert(text.font.path.clone(), text.font.clone());
+                    }
+                }
+            }
+            for (_, font) in &self.font_cache {
+                font.draw(&mut self.encoder, out_color, out_depth);
+            }
 
-        // draw background (if any)
-        match scene.background {
-            Background::Texture(ref texture) => {
-                // TODO: Reduce code duplication (see drawing debug quads)
-                self.encoder.update_constant_buffer(
-                    &self.quad_buf,
-                    &QuadParams {
-                        rect: [-1.0, -1.0, 1.0, 1.0],
-                        depth: 1.0,
+            // draw debug quads
+            self.debug_quads.sync_pending();
+            for quad in self.debug_quads.iter() {
+                let pos = [
+                    if quad.pos[0] >= 0 {
+                        quad.pos[0]
+                    } else {
+                        self.size.0 as i32 + quad.pos[0] - quad.size[0]
                     },
-                );
-                let data = quad_pipe::Data {
-                    params: self.quad_buf.clone(),
-                    globals: self.const_buf.clone(),
-                    resource: texture.to_param().0.raw().clone(),
-                    sampler: texture.to_param().1,
-                    target: self.out_color.clone(),
-                    depth_target: self.out_depth.clone(),
-                };
-                self.encoder.draw(&quad_slice, &self.pso.quad, &data);
-            }
-            Background::Skybox(ref cubemap) => {
+                    if quad.pos[1] >= 0 {
+                        quad.pos[1]
+                    } else {
+                        self.size.1 as i32 + quad.pos[1] - quad.size[1]
+                    },
+                ];
+                let p0 = self.map_to_ndc([pos[0] as f32, pos[1] as f32]);
+                let p1 = self.map_to_ndc([
+                    (pos[0] + quad.size[0]) as f32,
+                    (pos[1] + quad.size[1]) as f32,
+                ]);
                 self.encoder.update_constant_buffer(
                     &self.quad_buf,
                     &QuadParams {
-                        rect: [-1.0, -1.0, 1.0, 1.0],
-                        depth: 1.0,
+                        rect: [p0.x, p0.y, p1.x, p1.y],
+                        depth: -1.0,
                     },
                 );
                 let data = quad_pipe::Data {
                     params: self.quad_buf.clone(),
-                    resource: cubemap.to_param().0.raw().clone(),
-                    sampler: cubemap.to_param().1,
                     globals: self.const_buf.clone(),
-                    target: self.out_color.clone(),
-                    depth_target: self.out_depth.clone(),
+                    resource: quad.resource.clone(),
+                    sampler: self.map_default.to_param().1,
+                    target: out_color.clone(),
+                    depth_target: out_depth.clone(),
                 };
-                self.encoder.draw(&quad_slice, &self.pso.skybox, &data);
+                self.encoder.draw(&quad_slice, &self.pso.quad, &data);
             }
-            Background::Color(_) => {}
         }
 
-        // draw ui text
-        for node in hub.nodes.iter() {
-            if let SubNode::UiText(ref text) = node.sub_node {
-                text.font.queue(&text.section);
-                if !self.font_cache.contains_key(&text.font.path) {
-                    self.font_cache
-                        .insert(text.font.path.clone(), text.font.clone());
-                }
+        self.encoder.flush(&mut self.device);
+    }
+
+    /// Reads back the color buffer of the most recently rendered frame.
+    ///
+    /// OpenGL stores rows bottom-up, so the rows are flipped such that the
+    /// returned image has its origin at the top-left corner.
+    ///
+    /// Only the offscreen framebuffer of a [`Headless`] renderer can be read
+    /// back. The back buffer of a window is undefined once it is presented,
+    /// so `CaptureError::Window` is returned for window renderers; use
+    /// [`capture_scene`](#method.capture_scene) for those instead.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # let mut headless = three::Headless::new(256, 256);
+    /// # let camera = headless.factory.perspective_camera(60.0, 0.1 .. 10.0);
+    /// headless.render(&camera);
+    /// let image = headless.renderer.capture().unwrap();
+    /// image.save("screenshot.png").unwrap();
+    /// ```
+    ///
+    /// [`Headless`]: struct.Headless.html
+    pub fn capture(&mut self) -> Result<image::RgbaImage, CaptureError> {
+        if !self.offscreen {
+            return Err(CaptureError::Window);
+        }
+        let texture = self.out_color.raw().get_texture().clone();
+        Ok(self.read_back_color(&texture))
+    }
+
+    /// Renders `scene` from the view of `camera` into an offscreen
+    /// framebuffer the size of the viewport, and reads it back like
+    /// [`capture`](#method.capture).
+    ///
+    /// Unlike `capture`, this works for window renderers too. The window
+    /// itself is left untouched, so the scene is drawn twice in frames that
+    /// are both displayed and captured.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # let mut window = three::Window::new("");
+    /// # let camera = window.factory.perspective_camera(60.0, 0.1 .. 10.0);
+    /// while window.update() {
+    ///     window.render(&camera);
+    ///     if window.input.hit(three::Key::F12) {
+    ///         let image = window.renderer.capture_scene(&window.scene, &camera);
+    ///         image.save("screenshot.png").unwrap();
+    ///     }
+    /// }
+    /// ```
+    pub fn capture_scene(
+        &mut self,
+        scene: &Scene,
+        camera: &Camera,
+    ) -> image::RgbaImage {
+        let size = self.size;
+        let (color, depth) = match self.capture_targets {
+            Some(ref targets) => targets.clone(),
+            None => {
+                let targets = offscreen_targets(&mut self.factory, size.0 as u16, size.1 as u16);
+                self.capture_targets = Some(targets.clone());
+                targets
             }
+        };
+        self.draw(scene, camera, &color, &depth, size, true);
+        let texture = color.raw().get_texture().clone();
+        self.read_back_color(&texture)
+    }
+
+    /// Reads back the depth buffer of the most recently rendered frame.
+    ///
+    /// Depth values are normalized to the range `[0.0, 1.0]`. Like
+    /// [`capture`](#method.capture), the origin is at the top-left corner,
+    /// and `CaptureError::Window` is returned for window renderers.
+    pub fn capture_depth(&mut self) -> Result<image::ImageBuffer<image::Luma<f32>, Vec<f32>>, CaptureError> {
+        if !self.offscreen {
+            return Err(CaptureError::Window);
         }
-        for (_, font) in &self.font_cache {
-            font.draw(&mut self.encoder, &self.out_color, &self.out_depth);
+        let format = <DepthFormat as Formatted>::get_format();
+        let decode: fn(u32) -> f32 = match format.0 {
+            // The upper 24 bits contain depth, the lower 8 bits stencil.
+            gfx::format::SurfaceType::D24_S8 => |texel| (texel >> 8) as f32 / 0xFFFFFF as f32,
+            gfx::format::SurfaceType::D24 => |texel| (texel & 0xFFFFFF) as f32 / 0xFFFFFF as f32,
+            gfx::format::SurfaceType::D32 => f32::from_bits,
+            ref other => unimplemented!("Reading back {:?} depth buffers", other),
+        };
+        let (width, height) = self.size;
+        let texture = self.out_depth.raw().get_texture().clone();
+        let texels = self.read_back::<u32>(&texture, format);
+        let mut image = image::ImageBuffer::new(width, height);
+        for (y, row) in texels.chunks(width as usize).enumerate() {
+            for (x, &texel) in row.iter().enumerate() {
+                image.put_pixel(x as u32, height - 1 - y as u32, image::Luma([decode(texel)]));
+            }
         }
+        Ok(image)
+    }
 
-        // draw debug quads
-        self.debug_quads.sync_pending();
-        for quad in self.debug_quads.iter() {
-            let pos = [
-                if quad.pos[0] >= 0 {
-                    quad.pos[0]
-                } else {
-                    self.size.0 as i32 + quad.pos[0] - quad.size[0]
-                },
-                if quad.pos[1] >= 0 {
-                    quad.pos[1]
-                } else {
-                    self.size.1 as i32 + quad.pos[1] - quad.size[1]
-                },
-            ];
-            let p0 = self.map_to_ndc([pos[0] as f32, pos[1] as f32]);
-            let p1 = self.map_to_ndc([
-                (pos[0] + quad.size[0]) as f32,
-                (pos[1] + quad.size[1]) as f32,
-            ]);
-            self.encoder.update_constant_buffer(
-                &self.quad_buf,
-                &QuadParams {
-                    rect: [p0.x, p0.y, p1.x, p1.y],
-                    depth: -1.0,
-                },
-            );
-            let data = quad_pipe::Data {
-                params: self.quad_buf.clone(),
-                globals: self.const_buf.clone(),
-                resource: quad.resource.clone(),
-                sampler: self.map_default.to_param().1,
-                target: self.out_color.clone(),
-                depth_target: self.out_depth.clone(),
-            };
-            self.encoder.draw(&quad_slice, &self.pso.quad, &data);
+    /// Reads back a color `texture` the size of the viewport, flipping its
+    /// rows such that the origin is at the top-left corner.
+    fn read_back_color(
+        &mut self,
+        texture: &gfx::handle::RawTexture<back::Resources>,
+    ) -> image::RgbaImage {
+        let (width, height) = self.size;
+        let texels = self.read_back::<[u8; 4]>(texture, <ColorFormat as Formatted>::get_format());
+        let mut image = image::RgbaImage::new(width, height);
+        for (y, row) in texels.chunks(width as usize).enumerate() {
+            for (x, texel) in row.iter().enumerate() {
+                image.put_pixel(x as u32, height - 1 - y as u32, image::Rgba(*texel));
+            }
         }
+        image
+    }
 
+    /// Copies the whole of `texture` into CPU memory, row by row from the bottom.
+    fn read_back<T: Copy>(
+        &mut self,
+        texture: &gfx::handle::RawTexture<back::Resources>,
+        format: gfx::format::Format,
+    ) -> Vec<T> {
+        let (width, height) = self.size;
+        let download = self.factory
+            .create_download_buffer::<T>((width * height) as usize)
+            .unwrap();
+        let info = gfx::texture::RawImageInfo {
+            xoffset: 0,
+            yoffset: 0,
+            zoffset: 0,
+            width: width as gfx::texture::Size,
+            height: height as gfx::texture::Size,
+            depth: 0,
+            format,
+            mipmap: 0,
+        };
+        self.encoder
+            .copy_texture_to_buffer_raw(texture, None, info, download.raw(), 0)
+            .unwrap();
         self.encoder.flush(&mut self.device);
+        let reader = self.factory.read_mapping(&download).unwrap();
+        reader.to_vec()
     }
 
     /// Draw [`ShadowMap`](struct.ShadowMap.html) for debug purposes.
@@ -0,0 +1,52 @@
+use gfx;
+use mint;
+use object;
+
+use render::BackendResources;
+
+/// Contains array of bones.
+///
+/// A skeleton deforms the meshes bound to it with
+/// [`Mesh::set_skeleton`](struct.Mesh.html#method.set_skeleton). Each vertex
+/// of such a mesh is transformed by up to four bones, as given by the
+/// [`Joints`](geometry/struct.Joints.html) of its geometry.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Skeleton {
+    pub(crate) object: object::Base,
+}
+three_object!(Skeleton::object);
+
+/// A single bone that forms one component of a [`Skeleton`].
+///
+/// Bones are ordinary scene nodes: they may be attached to other objects
+/// and driven by an [`animation::Mixer`] like any other object.
+///
+/// [`Skeleton`]: struct.Skeleton.html
+/// [`animation::Mixer`]: animation/struct.Mixer.html
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Bone {
+    pub(crate) object: object::Base,
+}
+three_object!(Bone::object);
+
+/// A matrix transforming vertices from mesh space into the local space of a
+/// bone in its rest pose.
+pub type InverseBindMatrix = mint::ColumnMatrix4<f32>;
+
+/// Internal data for a [`Skeleton`](struct.Skeleton.html).
+#[derive(Clone, Debug)]
+pub(crate) struct SkeletonData {
+    /// The bones of the skeleton, in joint index order.
+    pub bones: Vec<Bone>,
+    /// Joint matrices, stored as four texels per bone.
+    pub gpu_buffer: gfx::handle::Buffer<BackendResources, [f32; 4]>,
+    /// Shader view of `gpu_buffer`.
+    pub gpu_buffer_view: gfx::handle::ShaderResourceView<BackendResources, [f32; 4]>,
+}
+
+/// Internal data for a [`Bone`](struct.Bone.html).
+#[derive(Clone, Debug)]
+pub(crate) struct BoneData {
+    /// See [`InverseBindMatrix`](type.InverseBindMatrix.html).
+    pub inverse_bind_matrix: InverseBindMatrix,
+}
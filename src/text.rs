@@ -10,6 +10,7 @@ use mint;
 use object;
 
 use color::Color;
+use factory::LoadError;
 use hub::Operation as HubOperation;
 use render::{BackendCommandBuffer, BackendFactory, BackendResources, ColorFormat, DepthFormat};
 
@@ -95,13 +96,14 @@ impl Font {
         buf: Vec<u8>,
         path: PathBuf,
         factory: BackendFactory,
-    ) -> Font {
-        Font {
+    ) -> Result<Font, LoadError> {
+        let font = g::font(buf).map_err(|e| LoadError::decode(&path, format!("{:?}", e)))?;
+        Ok(Font {
             brush: Rc::new(RefCell::new(
-                g::GlyphBrushBuilder::using_font(g::font(buf).unwrap()).build(factory),
+                g::GlyphBrushBuilder::using_font(font).build(factory),
             )),
             path: path,
-        }
+        })
     }
 
     pub(crate) fn queue(
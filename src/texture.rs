@@ -1,7 +1,11 @@
 use gfx::handle as h;
+use gfx::texture::SamplerInfo;
 use render::BackendResources;
-use std::path::Path;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
+use image;
 use mint;
 
 pub use gfx::texture::{FilterMethod, WrapMode};
@@ -18,6 +22,33 @@ pub struct Texture<T> {
     total_size: [u32; 2],
     tex0: [f32; 2],
     tex1: [f32; 2],
+    uv_offset: [f32; 2],
+    uv_rotation: f32,
+    uv_scale: [f32; 2],
+    pixels: Option<Pixels>,
+    path: Option<Arc<PathBuf>>,
+}
+
+/// CPU-side copy of the pixels of a texture, kept for exporting.
+#[derive(Clone)]
+pub(crate) struct Pixels(pub(crate) Arc<image::RgbaImage>);
+
+impl fmt::Debug for Pixels {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter,
+    ) -> fmt::Result {
+        write!(f, "Pixels({}x{})", self.0.width(), self.0.height())
+    }
+}
+
+impl PartialEq for Pixels {
+    fn eq(
+        &self,
+        other: &Pixels,
+    ) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
 }
 
 impl<T> Texture<T> {
@@ -32,9 +63,53 @@ impl<T> Texture<T> {
             total_size,
             tex0: [0.0; 2],
             tex1: [total_size[0] as f32, total_size[1] as f32],
+            uv_offset: [0.0; 2],
+            uv_rotation: 0.0,
+            uv_scale: [1.0; 2],
+            pixels: None,
+            path: None,
         }
     }
 
+    /// Keeps a CPU-side copy of the pixels of the texture.
+    pub(crate) fn with_pixels(
+        mut self,
+        pixels: Arc<image::RgbaImage>,
+    ) -> Self {
+        self.pixels = Some(Pixels(pixels));
+        self
+    }
+
+    /// Returns the CPU-side copy of the pixels of the texture, if any.
+    pub(crate) fn pixels(&self) -> Option<&Arc<image::RgbaImage>> {
+        self.pixels.as_ref().map(|pixels| &pixels.0)
+    }
+
+    /// Records the file the texture was loaded from.
+    pub(crate) fn with_path(
+        mut self,
+        path: &Path,
+    ) -> Self {
+        self.path = Some(Arc::new(path.to_owned()));
+        self
+    }
+
+    /// Returns the file the texture was loaded from, if any.
+    pub(crate) fn path(&self) -> Option<&Path> {
+        self.path.as_ref().map(|path| path.as_path())
+    }
+
+    /// Returns the sampling properties of the texture.
+    pub(crate) fn sampler_info(&self) -> &SamplerInfo {
+        self.sampler.get_info()
+    }
+
+    /// Returns the offset, rotation and scale set with
+    /// [`set_uv_transform`](#method.set_uv_transform).
+    pub(crate) fn uv_transform(&self) -> ([f32; 2], f32, [f32; 2]) {
+        (self.uv_offset, self.uv_rotation, self.uv_scale)
+    }
+
     pub(crate) fn to_param(
         &self,
     ) -> (
@@ -69,6 +144,39 @@ impl<T> Texture<T> {
             self.tex1[1] / self.total_size[1] as f32,
         ]
     }
+
+    /// Sets the transform applied to texture co-ordinates before sampling,
+    /// as defined by the `KHR_texture_transform` glTF extension.
+    ///
+    /// Co-ordinates are scaled by `scale`, rotated by `rotation` radians
+    /// and then translated by `offset`. The transform is only honoured by
+    /// [`Pbr`](material/struct.Pbr.html) materials.
+    pub fn set_uv_transform(
+        &mut self,
+        offset: mint::Vector2<f32>,
+        rotation: f32,
+        scale: mint::Vector2<f32>,
+    ) {
+        self.uv_offset = offset.into();
+        self.uv_rotation = rotation;
+        self.uv_scale = scale.into();
+    }
+
+    /// Returns the column-major matrix mapping texture co-ordinates to the
+    /// current texel range, after applying the UV transform.
+    pub(crate) fn uv_matrix(&self) -> [[f32; 4]; 4] {
+        let range = self.uv_range();
+        let (w, h) = (range[2] - range[0], range[3] - range[1]);
+        let (sin, cos) = self.uv_rotation.sin_cos();
+        let (sx, sy) = (self.uv_scale[0], self.uv_scale[1]);
+        let (ox, oy) = (self.uv_offset[0], self.uv_offset[1]);
+        [
+            [w * sx * cos, -h * sx * sin, 0.0, 0.0],
+            [w * sy * sin, h * sy * cos, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [range[0] + w * ox, range[1] + h * oy, 0.0, 1.0],
+        ]
+    }
 }
 
 /// Represents paths to cube map texture, useful for loading
@@ -11,6 +11,10 @@ use input::Input;
 use render::Renderer;
 use scene::Scene;
 use std::path::PathBuf;
+use std::time::Duration;
+
+/// Time spent each frame finalising background loads.
+const LOAD_BUDGET_MS: u64 = 4;
 
 /// `Window` is the core entity of every `three-rs` application.
 ///
@@ -177,7 +181,11 @@ impl Window {
     }
 
     /// `update` method returns `false` if the window was closed.
+    ///
+    /// Also finalises assets loaded in the background, see
+    /// [`Factory::process_loads`](struct.Factory.html#method.process_loads).
     pub fn update(&mut self) -> bool {
+        self.factory.process_loads(Duration::from_millis(LOAD_BUDGET_MS));
         let mut running = true;
         let renderer = &mut self.renderer;
         let input = &mut self.input;
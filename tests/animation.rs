@@ -0,0 +1,143 @@
+extern crate mint;
+extern crate three;
+
+#[macro_use]
+mod common;
+
+use three::Object;
+
+#[test]
+fn fades_blend_with_rest_pose() {
+    let mut headless = headless!(64, 64);
+
+    let mut group = headless.factory.group();
+    group.set_parent(&headless.scene);
+    group.set_position([1.0, 0.0, 0.0]);
+    let clip = three::animation::Clip::builder("hold")
+        .position(&group)
+        .key(0.0, [3.0, 0.0, 0.0])
+        .key(1.0, [3.0, 0.0, 0.0])
+        .build()
+        .unwrap();
+    let mut mixer = three::animation::Mixer::new();
+    let mut action = mixer.action(clip);
+
+    let mut step = |mixer: &mut three::animation::Mixer| {
+        mixer.update(0.5);
+        group.sync(&headless.scene).transform.position.x
+    };
+    action.fade_in(1.0);
+    assert_eq!(step(&mut mixer), 2.0);
+    assert_eq!(step(&mut mixer), 3.0);
+    action.fade_out(1.0);
+    assert_eq!(step(&mut mixer), 2.0);
+    assert_eq!(step(&mut mixer), 1.0);
+    assert_eq!(step(&mut mixer), 1.0);
+}
+
+#[test]
+fn markers_fire_on_every_loop() {
+    use three::animation::{EventKind, LoopMode, Mixer};
+
+    let headless = headless!(64, 64);
+    let group = headless.factory.group();
+    let clip = three::animation::Clip::builder("cycle")
+        .position(&group)
+        .key(0.0, [0.0, 0.0, 0.0])
+        .key(1.0, [1.0, 0.0, 0.0])
+        .marker(0.0, "start")
+        .marker(1.0, "end")
+        .build()
+        .unwrap();
+
+    // Counts the markers passed over 3.25 seconds.
+    let count = |loop_mode: LoopMode| {
+        let mut mixer = Mixer::new();
+        let mut action = mixer.action(clip.clone());
+        action.set_loop_mode(loop_mode);
+        let (mut start, mut end) = (0, 0);
+        for _ in 0 .. 13 {
+            mixer.update(0.25);
+            for event in mixer.events() {
+                match event.kind {
+                    EventKind::Marker(ref name) if name == "start" => start += 1,
+                    EventKind::Marker(ref name) if name == "end" => end += 1,
+                    _ => {}
+                }
+            }
+        }
+        (start, end)
+    };
+    // Repeating passes the start of loops at 0, 1, 2 and 3 seconds, and the
+    // end at 1, 2 and 3 seconds.
+    assert_eq!(count(LoopMode::Repeat { limit: None }), (4, 3));
+    // Ping-pong turns at the end after 1 and 3 seconds, and at the start
+    // after 2 seconds.
+    assert_eq!(count(LoopMode::PingPong { limit: None }), (2, 2));
+}
+
+#[test]
+fn invalid_tracks_are_skipped() {
+    use three::animation::{Binding, Clip, Interpolation, Mixer, Track, Values};
+
+    let headless = headless!(64, 64);
+    let mut group = headless.factory.group();
+    group.set_parent(&headless.scene);
+    let track = |binding, values| Track {
+        binding,
+        interpolation: Interpolation::Linear,
+        times: vec![0.0, 1.0],
+        values,
+    };
+    let clip = Clip {
+        name: None,
+        tracks: vec![
+            // Positions take vectors, not scalars.
+            (track(Binding::Position, Values::Scalar(vec![0.0, 1.0])), group.upcast()),
+            (track(Binding::Scale, Values::Scalar(vec![2.0, 2.0])), group.upcast()),
+        ],
+        markers: Vec::new(),
+    };
+    let mut mixer = Mixer::new();
+    let _action = mixer.action(clip);
+    mixer.update(0.5);
+    let transform = group.sync(&headless.scene).transform;
+    assert_eq!(transform.position.x, 0.0);
+    assert_eq!(transform.scale.x, 2.0);
+}
+
+#[test]
+fn opposite_quaternions_blend_to_the_same_orientation() {
+    use three::animation::{Clip, Mixer};
+
+    let quaternion = |y: f32, s: f32| mint::Quaternion {
+        v: mint::Vector3 { x: 0.0, y, z: 0.0 },
+        s,
+    };
+    let q = quaternion(0.6, 0.8);
+    let mut headless = headless!(64, 64);
+    let mut group = headless.factory.group();
+    group.set_parent(&headless.scene);
+    group.set_orientation(q);
+    let hold = |orientation: mint::Quaternion<f32>| {
+        Clip::builder("hold")
+            .orientation(&group)
+            .key(0.0, orientation)
+            .key(1.0, orientation)
+            .build()
+            .unwrap()
+    };
+    let mut mixer = Mixer::new();
+    // Cross-fades `q` with `-q`, both between the actions and with the rest
+    // pose.
+    let _action = mixer.action(hold(q));
+    let mut action = mixer.action(hold(quaternion(-0.6, -0.8)));
+    action.fade_in(1.0);
+    mixer.update(0.5);
+    let orientation = group.sync(&headless.scene).transform.orientation;
+    let actual = [orientation.v.x, orientation.v.y, orientation.v.z, orientation.s];
+    for (a, b) in actual.iter().zip(&[q.v.x, q.v.y, q.v.z, q.s]) {
+        assert!(a.is_finite());
+        assert!((a - b).abs() < 1e-5, "{:?} != {:?}", actual, q);
+    }
+}
@@ -0,0 +1,15 @@
+//! Helpers shared by the integration tests.
+
+/// Creates a `three::Headless` of the given size, or returns from the
+/// calling test if no OpenGL context is available.
+macro_rules! headless {
+    ($width:expr, $height:expr) => {
+        match three::Headless::try_new($width, $height) {
+            Ok(headless) => headless,
+            Err(err) => {
+                eprintln!("Skipping test: {}", err);
+                return;
+            }
+        }
+    };
+}
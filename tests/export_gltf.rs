@@ -0,0 +1,68 @@
+extern crate three;
+
+#[macro_use]
+mod common;
+
+use three::Object;
+
+#[test]
+fn export_gltf_round_trip() {
+    let mut headless = headless!(64, 64);
+
+    let group = headless.factory.group();
+    let material = three::material::Pbr {
+        metallic_factor: 0.25,
+        roughness_factor: 0.75,
+        ..Default::default()
+    };
+    let mut cube = headless
+        .factory
+        .mesh(three::Geometry::cuboid(2.0, 4.0, 6.0), material);
+    cube.set_parent(&group);
+    cube.set_position([1.0, 2.0, 3.0]);
+    cube.set_scale([2.0, 2.0, 2.0]);
+    let clip = three::animation::Clip::builder("slide")
+        .position(&cube)
+        .key(0.0, [1.0, 2.0, 3.0])
+        .key(1.0, [3.0, 2.0, 1.0])
+        .build()
+        .unwrap();
+
+    // A unique name, so that concurrent runs do not share the file.
+    let stamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap();
+    let name = format!("three-export-round-trip-{}-{}.glb", stamp.as_secs(), stamp.subsec_nanos());
+    let path = std::env::temp_dir().join(name);
+    headless
+        .factory
+        .export_gltf(&group, &[clip], &path)
+        .unwrap();
+    let mut gltf = headless.factory.load_gltf(path.to_str().unwrap()).unwrap();
+    let _ = std::fs::remove_file(&path);
+    gltf.group.set_parent(&headless.scene);
+    assert_eq!(gltf.clips.len(), 1);
+    assert_eq!(gltf.clips[0].name, Some("slide".to_string()));
+    assert_eq!(gltf.meshes.len(), 1);
+
+    let mut sync = headless.scene.sync_guard();
+    // The exported group is the single root, with the cube as its child.
+    let cube_node = sync.resolve(&gltf.nodes[1]);
+    let position = cube_node.transform.position;
+    assert_eq!([position.x, position.y, position.z], [1.0, 2.0, 3.0]);
+    let scale = cube_node.transform.scale;
+    assert_eq!([scale.x, scale.y, scale.z], [2.0, 2.0, 2.0]);
+
+    let (_, meshes) = gltf.meshes.iter().next().unwrap();
+    let mesh_node = sync.resolve(&meshes[0]);
+    let bounds = mesh_node.bounds.unwrap();
+    assert_eq!([bounds.min.x, bounds.min.y, bounds.min.z], [-1.0, -2.0, -3.0]);
+    assert_eq!([bounds.max.x, bounds.max.y, bounds.max.z], [1.0, 2.0, 3.0]);
+    match mesh_node.material {
+        Some(three::Material::Pbr(ref pbr)) => {
+            assert_eq!(pbr.metallic_factor, 0.25);
+            assert_eq!(pbr.roughness_factor, 0.75);
+        }
+        ref other => panic!("Expected a PBR material, found {:?}", other),
+    }
+}
@@ -0,0 +1,74 @@
+extern crate three;
+
+#[macro_use]
+mod common;
+
+/// A triangle, with three positions followed by the indices `0, 1, 2`.
+const TRIANGLE: &str = "AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAAAAABAAIAAAA=";
+/// The same triangle, with the indices `0, 1, 5`.
+const BAD_INDEX_TRIANGLE: &str = "AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAAAAABAAUAAAA=";
+
+fn triangle_gltf(
+    data: &str,
+    positions_length: usize,
+) -> String {
+    format!(
+        r#"{{
+            "asset": {{ "version": "2.0" }},
+            "buffers": [{{ "byteLength": 44, "uri": "data:application/octet-stream;base64,{}" }}],
+            "bufferViews": [
+                {{ "buffer": 0, "byteOffset": 0, "byteLength": {} }},
+                {{ "buffer": 0, "byteOffset": 36, "byteLength": 6 }}
+            ],
+            "accessors": [
+                {{ "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3",
+                   "min": [0, 0, 0], "max": [1, 1, 0] }},
+                {{ "bufferView": 1, "componentType": 5123, "count": 3, "type": "SCALAR" }}
+            ],
+            "meshes": [{{ "primitives": [{{ "attributes": {{ "POSITION": 0 }}, "indices": 1 }}] }}],
+            "nodes": [{{ "mesh": 0 }}],
+            "scenes": [{{ "nodes": [0] }}],
+            "scene": 0
+        }}"#,
+        data,
+        positions_length,
+    )
+}
+
+fn load(
+    headless: &mut three::Headless,
+    gltf: String,
+) -> Result<three::Gltf, three::LoadError> {
+    headless.factory.load_gltf_from_memory(gltf.as_bytes(), "")
+}
+
+fn is_decode_error(result: Result<three::Gltf, three::LoadError>) -> bool {
+    match result {
+        Err(three::LoadError::Decode(..)) => true,
+        _ => false,
+    }
+}
+
+#[test]
+fn load_gltf_triangle() {
+    let mut headless = headless!(64, 64);
+    assert!(load(&mut headless, triangle_gltf(TRIANGLE, 36)).is_ok());
+}
+
+#[test]
+fn load_gltf_rejects_out_of_range_view() {
+    let mut headless = headless!(64, 64);
+    assert!(is_decode_error(load(&mut headless, triangle_gltf(TRIANGLE, 48))));
+}
+
+#[test]
+fn load_gltf_rejects_truncated_accessor() {
+    let mut headless = headless!(64, 64);
+    assert!(is_decode_error(load(&mut headless, triangle_gltf(TRIANGLE, 24))));
+}
+
+#[test]
+fn load_gltf_rejects_out_of_range_index() {
+    let mut headless = headless!(64, 64);
+    assert!(is_decode_error(load(&mut headless, triangle_gltf(BAD_INDEX_TRIANGLE, 36))));
+}
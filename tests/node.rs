@@ -0,0 +1,20 @@
+extern crate cgmath;
+extern crate three;
+
+#[test]
+fn sheared_matrix_decomposes_into_a_rotation() {
+    let sheared = cgmath::Matrix4::new(
+        2.0, 0.0, 0.0, 0.0,
+        // The Y axis is sheared along X.
+        1.0, 3.0, 0.0, 0.0,
+        0.0, 0.0, 4.0, 0.0,
+        5.0, 6.0, 7.0, 1.0,
+    );
+    let transform = three::Transform::from(sheared);
+    let q = transform.orientation;
+    let norm = (q.v.x * q.v.x + q.v.y * q.v.y + q.v.z * q.v.z + q.s * q.s).sqrt();
+    assert!((norm - 1.0).abs() < 1.0e-5, "{:?} is not normalized", q);
+    assert!((q.s.abs() - 1.0).abs() < 1.0e-5, "{:?} is not the identity", q);
+    assert_eq!(transform.position, [5.0, 6.0, 7.0].into());
+    assert_eq!(transform.scale, [2.0, 3.0, 4.0].into());
+}